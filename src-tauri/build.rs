@@ -1,3 +1,5 @@
 fn main() {
+    // 只构建命令行工具时不需要生成 Tauri 的上下文
+    #[cfg(feature = "gui")]
     tauri_build::build()
 }
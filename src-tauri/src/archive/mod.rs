@@ -0,0 +1,474 @@
+//! 归档模块
+//!
+//! 此模块负责将知识库目录打包为压缩归档（`.tar.gz` 或 `.zip`），
+//! 用于在删除知识库之前保留一份可恢复的完整副本，或在离线环境之间交接知识库。
+
+use serde::Deserialize;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+/// 归档中保存 Git bundle 的文件名，位于顶层目录下
+pub const BUNDLE_FILE_NAME: &str = ".git.bundle";
+
+/// 归档操作可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 归档目标已存在
+    #[error("Archive already exists: {0}")]
+    AlreadyExists(String),
+
+    /// Git 操作错误
+    #[error("Git operation error: {0}")]
+    Git(#[from] crate::git::Error),
+
+    /// 读写 zip 归档失败
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    /// 归档中存在不安全的条目，如绝对路径、`..`、符号链接或多个顶层目录
+    #[error("Invalid archive entry: {0}")]
+    InvalidEntry(String),
+}
+
+/// 归档格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    /// `.tar.gz` 归档
+    #[default]
+    TarGz,
+    /// `.zip` 归档
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// 根据文件扩展名判断归档格式，`.zip` 以外的扩展名都视为 `.tar.gz`
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("zip") => ArchiveFormat::Zip,
+            _ => ArchiveFormat::TarGz,
+        }
+    }
+}
+
+/// 归档中包含的 Git 历史
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveHistory {
+    /// 只包含工作区文件
+    #[default]
+    None,
+    /// 包含完整的 `.git` 目录
+    GitDir,
+    /// 包含当前分支历史的 Git bundle，保存为顶层目录下的 `.git.bundle`
+    Bundle,
+}
+
+/// 将目录打包为 `.tar.gz` 归档，用于删除知识库之前保留完整的副本
+///
+/// 归档中的所有条目都位于以源目录名称命名的顶层目录下，解压后即可得到与源目录相同的结构。
+/// 与 [`pack_wiki`] 使用相同的规则：`.git` 目录会被打包，符号链接不会被打包。
+///
+/// # 参数
+/// * `src` - 要打包的目录
+/// * `dest` - 归档文件的保存路径
+///
+/// # 返回值
+/// * `Result<(), Error>` - 成功时返回 `Ok(())`
+/// * 失败时返回 `Err(Error)`，如目标已存在或读写失败
+pub fn pack_dir(src: &Path, dest: &Path) -> Result<(), Error> {
+    if dest.exists() {
+        return Err(Error::AlreadyExists(dest.display().to_string()));
+    }
+    pack_wiki(src, dest, ArchiveFormat::TarGz, ArchiveHistory::GitDir)
+}
+
+/// 将知识库打包为便于交接的归档
+///
+/// 归档中的所有条目都位于以知识库目录名称命名的顶层目录下。
+/// 工作区中的符号链接不会被打包，`.git` 目录只在 `history` 为 `GitDir` 时打包。
+/// 归档先写入临时文件，完成后再重命名为目标文件。
+///
+/// # 参数
+/// * `src` - 知识库目录
+/// * `dest` - 归档文件的保存路径，已存在时会被覆盖
+/// * `format` - 归档格式
+/// * `history` - 归档中包含的 Git 历史
+///
+/// # 返回值
+/// * `Result<(), Error>` - 成功时返回 `Ok(())`
+/// * 失败时返回 `Err(Error)`，如选择 bundle 时仓库还没有任何提交
+pub fn pack_wiki(
+    src: &Path,
+    dest: &Path,
+    format: ArchiveFormat,
+    history: ArchiveHistory,
+) -> Result<(), Error> {
+    let top_level = src
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "无法获取源目录名称")
+        })?;
+    let bundle = match history {
+        ArchiveHistory::Bundle => Some(crate::git::Repository::open(src)?.bundle(None)?),
+        _ => None,
+    };
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = dest.with_extension("partial");
+    let result = (|| -> Result<(), Error> {
+        let file = fs::File::create(&temp_path)?;
+        let mut writer = match format {
+            ArchiveFormat::TarGz => ArchiveWriter::TarGz(tar::Builder::new(
+                flate2::write::GzEncoder::new(file, flate2::Compression::default()),
+            )),
+            ArchiveFormat::Zip => ArchiveWriter::Zip(zip::ZipWriter::new(file)),
+        };
+
+        writer.add_dir(top_level)?;
+        add_dir_entries(
+            &mut writer,
+            src,
+            top_level,
+            history == ArchiveHistory::GitDir,
+        )?;
+        if let Some(bundle) = &bundle {
+            writer.add_bytes(&format!("{}/{}", top_level, BUNDLE_FILE_NAME), bundle)?;
+        }
+        writer.finish()
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    fs::rename(&temp_path, dest)?;
+    Ok(())
+}
+
+/// 递归地把目录中的文件加入归档
+fn add_dir_entries(
+    writer: &mut ArchiveWriter,
+    dir: &Path,
+    prefix: &str,
+    include_git: bool,
+) -> Result<(), Error> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" && !include_git {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        let entry_name = format!("{}/{}", prefix, name);
+        if file_type.is_dir() {
+            writer.add_dir(&entry_name)?;
+            add_dir_entries(writer, &entry.path(), &entry_name, include_git)?;
+        } else if file_type.is_file() {
+            writer.add_file(&entry_name, &entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// 写入 `.tar.gz` 或 `.zip` 归档
+enum ArchiveWriter {
+    TarGz(tar::Builder<flate2::write::GzEncoder<fs::File>>),
+    Zip(zip::ZipWriter<fs::File>),
+}
+
+impl ArchiveWriter {
+    /// 添加目录条目
+    fn add_dir(&mut self, name: &str) -> Result<(), Error> {
+        match self {
+            ArchiveWriter::TarGz(builder) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(0o755);
+                header.set_size(0);
+                builder.append_data(&mut header, format!("{}/", name), std::io::empty())?;
+            }
+            ArchiveWriter::Zip(writer) => {
+                writer.add_directory(name, zip_options())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 添加文件条目，内容从磁盘读取
+    fn add_file(&mut self, name: &str, path: &Path) -> Result<(), Error> {
+        match self {
+            ArchiveWriter::TarGz(builder) => {
+                builder.append_path_with_name(path, name)?;
+            }
+            ArchiveWriter::Zip(writer) => {
+                writer.start_file(name, zip_options())?;
+                std::io::copy(&mut fs::File::open(path)?, writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 添加文件条目，内容来自内存
+    fn add_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<(), Error> {
+        match self {
+            ArchiveWriter::TarGz(builder) => {
+                let mut header = tar::Header::new_gnu();
+                header.set_mode(0o644);
+                header.set_size(bytes.len() as u64);
+                builder.append_data(&mut header, name, bytes)?;
+            }
+            ArchiveWriter::Zip(writer) => {
+                writer.start_file(name, zip_options())?;
+                writer.write_all(bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 完成归档并写入磁盘
+    fn finish(self) -> Result<(), Error> {
+        match self {
+            ArchiveWriter::TarGz(builder) => builder.into_inner()?.finish()?.sync_all()?,
+            ArchiveWriter::Zip(writer) => writer.finish()?.sync_all()?,
+        }
+        Ok(())
+    }
+}
+
+/// zip 条目的压缩选项
+fn zip_options() -> zip::write::SimpleFileOptions {
+    zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated)
+}
+
+/// 解压归档到指定目录
+///
+/// 归档格式根据文件内容判断。归档中的所有条目必须位于同一个顶层目录下，
+/// 解压时去掉该顶层目录，其中的内容直接放在 `dest` 中。
+/// 任何条目是绝对路径、包含 `..`，或是符号链接、硬链接等特殊文件时，
+/// 整个归档都会被拒绝，已解压的内容需要由调用方清理。
+///
+/// # 参数
+/// * `archive` - 归档文件路径
+/// * `dest` - 解压目标目录，不存在时会自动创建
+///
+/// # 返回值
+/// * `Result<String, Error>` - 成功时返回归档的顶层目录名称
+/// * 失败时返回 `Err(Error)`，如存在不安全的条目或归档已损坏
+pub fn unpack(archive: &Path, dest: &Path) -> Result<String, Error> {
+    let mut magic = [0u8; 4];
+    let read = fs::File::open(archive)?.read(&mut magic)?;
+    fs::create_dir_all(dest)?;
+    let mut top_level = None;
+
+    if magic[..read].starts_with(b"PK") {
+        let mut zip = zip::ZipArchive::new(fs::File::open(archive)?)?;
+        for i in 0..zip.len() {
+            let mut file = zip.by_index(i)?;
+            let name = file.name().to_string();
+            if file.is_symlink() {
+                return Err(Error::InvalidEntry(name));
+            }
+            let Some(relative) = entry_path(Path::new(&name), &mut top_level)? else {
+                continue;
+            };
+            let target = dest.join(relative);
+            if file.is_dir() {
+                fs::create_dir_all(&target)?;
+            } else {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                std::io::copy(&mut file, &mut fs::File::create(&target)?)?;
+            }
+        }
+    } else {
+        let decoder = flate2::read::GzDecoder::new(fs::File::open(archive)?);
+        let mut tar = tar::Archive::new(decoder);
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_path_buf();
+            let entry_type = entry.header().entry_type();
+            if !entry_type.is_file() && !entry_type.is_dir() {
+                return Err(Error::InvalidEntry(path.display().to_string()));
+            }
+            let Some(relative) = entry_path(&path, &mut top_level)? else {
+                continue;
+            };
+            let target = dest.join(relative);
+            if entry_type.is_dir() {
+                fs::create_dir_all(&target)?;
+            } else {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                entry.unpack(&target)?;
+            }
+        }
+    }
+
+    top_level.ok_or_else(|| Error::InvalidEntry("归档为空".to_string()))
+}
+
+/// 校验归档条目的路径，返回去掉顶层目录后的相对路径
+///
+/// 顶层目录本身返回 `None`；绝对路径、`..` 以及位于其他顶层目录下的条目返回错误。
+fn entry_path(path: &Path, top_level: &mut Option<String>) -> Result<Option<PathBuf>, Error> {
+    let invalid = || Error::InvalidEntry(path.display().to_string());
+
+    let mut components = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_str().ok_or_else(invalid)?),
+            Component::CurDir => {}
+            _ => return Err(invalid()),
+        }
+    }
+
+    let Some((first, rest)) = components.split_first() else {
+        return Err(invalid());
+    };
+    match top_level {
+        Some(top) if top != first => return Err(invalid()),
+        Some(_) => {}
+        None => *top_level = Some(first.to_string()),
+    }
+
+    if rest.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(rest.iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试 `pack_dir` 函数
+    ///
+    /// # 测试目标：
+    /// * 验证归档包含以源目录命名的顶层目录及其中的全部文件
+    /// * 验证目标已存在时拒绝覆盖
+    #[test]
+    fn test_pack_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let src = temp_dir.path().join("my-wiki");
+        fs::create_dir_all(src.join("docs")).unwrap();
+        fs::write(src.join("index.md"), "# 首页").unwrap();
+        fs::write(src.join("docs/a.md"), "a").unwrap();
+
+        let dest = temp_dir.path().join("archive/my-wiki.tar.gz");
+        pack_dir(&src, &dest).unwrap();
+        assert!(dest.is_file());
+
+        let decoder = flate2::read::GzDecoder::new(fs::File::open(&dest).unwrap());
+        let mut names: Vec<String> = tar::Archive::new(decoder)
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().to_string())
+            .filter(|name| !name.ends_with('/'))
+            .collect();
+        names.sort();
+        assert!(names.contains(&"my-wiki/index.md".to_string()));
+        assert!(names.contains(&"my-wiki/docs/a.md".to_string()));
+
+        assert!(matches!(
+            pack_dir(&src, &dest),
+            Err(Error::AlreadyExists(_))
+        ));
+    }
+
+    /// 测试 `pack_wiki` 与 `unpack` 函数
+    ///
+    /// # 测试目标：
+    /// * 验证 `.tar.gz` 与 `.zip` 归档都能还原工作区，且默认不包含 `.git` 目录
+    /// * 验证选择 `GitDir` 时 `.git` 目录被打包
+    #[test]
+    fn test_pack_and_unpack_wiki() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let src = temp_dir.path().join("my-wiki");
+        fs::create_dir_all(src.join("docs")).unwrap();
+        fs::create_dir_all(src.join(".git")).unwrap();
+        fs::write(src.join(".git/HEAD"), "ref: refs/heads/master\n").unwrap();
+        fs::write(src.join("index.md"), "# 首页").unwrap();
+        fs::write(src.join("docs/a.md"), "a").unwrap();
+
+        for (file_name, history) in [
+            ("wiki.tar.gz", ArchiveHistory::None),
+            ("wiki.zip", ArchiveHistory::None),
+            ("full.zip", ArchiveHistory::GitDir),
+        ] {
+            let dest = temp_dir.path().join(file_name);
+            pack_wiki(&src, &dest, ArchiveFormat::from_path(&dest), history).unwrap();
+
+            let out = temp_dir.path().join(format!("out-{}", file_name));
+            assert_eq!(unpack(&dest, &out).unwrap(), "my-wiki");
+            assert_eq!(fs::read_to_string(out.join("docs/a.md")).unwrap(), "a");
+            assert_eq!(
+                out.join(".git/HEAD").exists(),
+                history == ArchiveHistory::GitDir
+            );
+        }
+    }
+
+    /// 测试解压时的路径校验
+    ///
+    /// # 测试目标：
+    /// * 验证包含 `..` 的条目、符号链接以及多个顶层目录的归档被拒绝
+    #[test]
+    fn test_unpack_rejects_unsafe_entries() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let zip_path = temp_dir.path().join("evil.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file("wiki/../../evil.md", zip_options())
+            .unwrap();
+        writer.write_all(b"evil").unwrap();
+        writer.finish().unwrap();
+        assert!(matches!(
+            unpack(&zip_path, &temp_dir.path().join("out1")),
+            Err(Error::InvalidEntry(_))
+        ));
+        assert!(!temp_dir.path().join("evil.md").exists());
+
+        let tar_path = temp_dir.path().join("link.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&tar_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder
+            .append_link(&mut header, "wiki/passwd", "/etc/passwd")
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+        assert!(matches!(
+            unpack(&tar_path, &temp_dir.path().join("out2")),
+            Err(Error::InvalidEntry(_))
+        ));
+
+        let mut top_level = Some("wiki".to_string());
+        assert!(entry_path(Path::new("other/a.md"), &mut top_level).is_err());
+        assert!(entry_path(Path::new("/wiki/a.md"), &mut top_level).is_err());
+        assert_eq!(
+            entry_path(Path::new("./wiki/docs/a.md"), &mut top_level).unwrap(),
+            Some(PathBuf::from("docs/a.md"))
+        );
+    }
+}
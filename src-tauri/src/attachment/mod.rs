@@ -0,0 +1,572 @@
+//! 附件模块
+//!
+//! 此模块负责把图片、PDF 等二进制文件导入知识库，并生成可以插入页面的 Markdown 链接。
+//! 附件可以保存在页面旁的资源文件夹（`<页面名>.assets/`）中，也可以保存在知识库根目录的
+//! `assets/` 文件夹中。导入时会为附件分配不与已有文件冲突的名称。
+
+use crate::git;
+use crate::wiki::link::{extract_links, relative_link, resolve_link, wikilink_matches};
+use crate::wiki::{self, list_files, normalize_relative_path, FileKind, TreeFilter, Wiki};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// 知识库级别的资源文件夹名称
+pub const WIKI_ASSETS_DIR: &str = "assets";
+
+/// 页面资源文件夹名称的后缀
+pub const PAGE_ASSETS_SUFFIX: &str = ".assets";
+
+/// 默认的大附件阈值（5 MiB）
+pub const DEFAULT_SIZE_THRESHOLD: u64 = 5 * 1024 * 1024;
+
+/// 知识库元数据中设置大附件阈值（字节）的键
+pub const SIZE_THRESHOLD_SETTING: &str = "attachment_size_threshold";
+
+/// 附件操作可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 知识库操作错误
+    #[error("Wiki error: {0}")]
+    Wiki(#[from] wiki::Error),
+
+    /// 要导入的文件不存在或不是文件
+    #[error("Source file not found: {0}")]
+    SourceNotFound(String),
+
+    /// 附件不存在
+    #[error("Attachment not found: {0}")]
+    NotFound(String),
+
+    /// 附件由 Git LFS 管理，内容尚未下载
+    #[error("Attachment content is not downloaded from Git LFS: {0}")]
+    NotDownloaded(String),
+
+    /// Git 操作错误
+    #[error("Git error: {0}")]
+    Git(#[from] git::Error),
+
+    /// 回收站操作错误
+    #[error("Trash error: {0}")]
+    Trash(#[from] crate::trash::Error),
+}
+
+/// 附件的保存位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetLocation {
+    /// 页面旁的资源文件夹，例如 `docs/guide.md` 的附件保存在 `docs/guide.assets/`
+    #[default]
+    Page,
+    /// 知识库根目录的 `assets/` 文件夹
+    Wiki,
+}
+
+/// 导入的附件
+///
+/// # 字段
+/// * `path` - 附件相对于知识库根目录的路径
+/// * `link` - 从页面指向附件的相对链接
+/// * `markdown` - 可以直接插入页面的 Markdown 文本，图片使用图片语法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Attachment {
+    pub path: String,
+    pub link: String,
+    pub markdown: String,
+}
+
+/// 附件信息
+///
+/// # 字段
+/// * `path` - 附件相对于知识库根目录的路径
+/// * `size` - 文件大小（字节）
+/// * `referenced` - 是否被至少一个页面引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AttachmentInfo {
+    pub path: String,
+    pub size: u64,
+    pub referenced: bool,
+}
+
+/// 附件检查报告
+///
+/// # 字段
+/// * `orphans` - 没有被任何页面引用的附件
+/// * `oversized` - 大小超过阈值的附件，按大小倒序排列
+/// * `size_threshold` - 检查时使用的大小阈值（字节）
+/// * `total_size` - 所有附件的总大小（字节）
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentReport {
+    pub orphans: Vec<AttachmentInfo>,
+    pub oversized: Vec<AttachmentInfo>,
+    pub size_threshold: u64,
+    pub total_size: u64,
+}
+
+/// 知识库附件管理
+pub struct Attachments {
+    /// 知识库根目录
+    root: PathBuf,
+}
+
+impl Attachments {
+    /// 创建附件管理实例
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    /// 获取指定知识库的附件管理实例
+    pub fn for_wiki(wiki: &Wiki) -> Self {
+        Self::new(Path::new(&wiki.path))
+    }
+
+    /// 从文件导入附件
+    ///
+    /// # 参数
+    /// * `page` - 插入附件的页面相对于知识库根目录的路径
+    /// * `source` - 要导入的文件的完整路径
+    /// * `location` - 附件的保存位置
+    ///
+    /// # 返回值
+    /// * `Result<Attachment, Error>` - 成功时返回导入的附件及其链接
+    /// * 失败时返回 `Err(Error)`
+    pub fn import_file(
+        &self,
+        page: &str,
+        source: &Path,
+        location: AssetLocation,
+    ) -> Result<Attachment, Error> {
+        if !source.is_file() {
+            return Err(Error::SourceNotFound(source.display().to_string()));
+        }
+        let file_name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let content = fs::read(source)?;
+        self.import_bytes(page, &file_name, &content, location)
+    }
+
+    /// 从内存中的数据导入附件，例如从剪贴板粘贴的图片
+    ///
+    /// # 参数
+    /// * `page` - 插入附件的页面相对于知识库根目录的路径
+    /// * `file_name` - 附件的文件名，已存在同名文件时会自动添加序号
+    /// * `content` - 附件内容
+    /// * `location` - 附件的保存位置
+    ///
+    /// # 返回值
+    /// * `Result<Attachment, Error>` - 成功时返回导入的附件及其链接
+    /// * 失败时返回 `Err(Error)`
+    pub fn import_bytes(
+        &self,
+        page: &str,
+        file_name: &str,
+        content: &[u8],
+        location: AssetLocation,
+    ) -> Result<Attachment, Error> {
+        let page = normalize_relative_path(page)?;
+        let assets_dir = assets_dir(&page, location);
+        let dir = self.root.join(&assets_dir);
+        fs::create_dir_all(&dir)?;
+
+        let file_name = sanitize_file_name(file_name);
+        let (stem, extension) = split_file_name(&file_name);
+        let mut counter = 0;
+        let (name, mut file) = loop {
+            let name = if counter == 0 {
+                format!("{}{}", stem, extension)
+            } else {
+                format!("{}-{}{}", stem, counter, extension)
+            };
+            // 使用 create_new 保证不会覆盖同时导入的同名附件
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(dir.join(&name))
+            {
+                Ok(file) => break (name, file),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => counter += 1,
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        let target = dir.join(&name);
+        if let Err(e) = file.write_all(content).and_then(|_| file.sync_all()) {
+            let _ = fs::remove_file(&target);
+            return Err(e.into());
+        }
+
+        let path = if assets_dir.is_empty() {
+            name.clone()
+        } else {
+            format!("{}/{}", assets_dir, name)
+        };
+        let link = relative_link(&page, &path);
+        let markdown = match FileKind::from_path(&target) {
+            FileKind::Image => format!("![{}]({})", stem, link),
+            _ => format!("[{}]({})", name, link),
+        };
+
+        Ok(Attachment {
+            path,
+            link,
+            markdown,
+        })
+    }
+
+    /// 读取附件内容
+    ///
+    /// # 参数
+    /// * `path` - 附件相对于知识库根目录的路径
+    ///
+    /// # 返回值
+    /// * `Result<Vec<u8>, Error>` - 成功时返回附件的原始内容
+    /// * 失败时返回 `Err(Error)`，如附件不存在或 Git LFS 内容尚未下载
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, Error> {
+        let relative = normalize_relative_path(path)?;
+        let full_path = self.root.join(&relative);
+        if relative.is_empty() || !full_path.is_file() {
+            return Err(Error::NotFound(path.to_string()));
+        }
+        if git::lfs::read_pointer_file(&full_path).is_some() {
+            return Err(Error::NotDownloaded(path.to_string()));
+        }
+        Ok(fs::read(full_path)?)
+    }
+
+    /// 扫描知识库，列出所有附件及其引用情况
+    ///
+    /// 附件是除 Markdown 页面之外、会显示在文件树中的文件，以 `.` 开头的文件（如 `.gitignore`）不计入。
+    /// 页面中的标准链接、图片链接和双链都会被视为引用。位于隐藏或被忽略的文件夹中的页面
+    /// 不会显示在文件树中，但它们的引用同样有效，因此会扫描知识库中的所有 Markdown 文件。
+    ///
+    /// # 返回值
+    /// * `Result<Vec<AttachmentInfo>, Error>` - 成功时返回按路径排列的附件列表
+    /// * 失败时返回 `Err(Error)`
+    pub fn scan(&self) -> Result<Vec<AttachmentInfo>, Error> {
+        let filter = TreeFilter::new(&self.root, &wiki::meta::WikiMeta::load(&self.root)?.ignore);
+        let (pages, files): (Vec<String>, Vec<String>) = list_files(&self.root)?
+            .into_iter()
+            .partition(|path| FileKind::from_path(Path::new(path)) == FileKind::Markdown);
+        let attachments = files.iter().filter(|path| self.is_visible(&filter, path));
+
+        // 收集所有页面引用的路径和双链目标
+        let mut linked = HashSet::new();
+        let mut wikilinks = Vec::new();
+        for page in &pages {
+            let Ok(content) = fs::read_to_string(self.root.join(page)) else {
+                continue;
+            };
+            for link in extract_links(&content) {
+                if link.is_wikilink {
+                    wikilinks.push(link.target);
+                } else if let Some(resolved) = resolve_link(page, &link.target) {
+                    linked.insert(resolved);
+                }
+            }
+        }
+
+        let mut infos = Vec::new();
+        for path in attachments {
+            let size = fs::metadata(self.root.join(path))
+                .map(|m| m.len())
+                .unwrap_or(0);
+            let referenced = linked.contains(path.as_str())
+                || wikilinks
+                    .iter()
+                    .any(|target| wikilink_matches(target, path));
+            infos.push(AttachmentInfo {
+                path: path.clone(),
+                size,
+                referenced,
+            });
+        }
+        Ok(infos)
+    }
+
+    /// 生成附件检查报告
+    ///
+    /// # 参数
+    /// * `size_threshold` - 大附件的阈值（字节），超过该大小的附件会被列入 `oversized`
+    ///
+    /// # 返回值
+    /// * `Result<AttachmentReport, Error>` - 成功时返回检查报告
+    /// * 失败时返回 `Err(Error)`
+    pub fn report(&self, size_threshold: u64) -> Result<AttachmentReport, Error> {
+        let attachments = self.scan()?;
+        let total_size = attachments.iter().map(|a| a.size).sum();
+
+        let orphans = attachments
+            .iter()
+            .filter(|a| !a.referenced)
+            .cloned()
+            .collect();
+        let mut oversized: Vec<AttachmentInfo> = attachments
+            .into_iter()
+            .filter(|a| a.size > size_threshold)
+            .collect();
+        oversized.sort_by(|a, b| b.size.cmp(&a.size).then(a.path.cmp(&b.path)));
+
+        Ok(AttachmentReport {
+            orphans,
+            oversized,
+            size_threshold,
+            total_size,
+        })
+    }
+
+    /// 将所有未被引用的附件移动到回收站，并把删除作为一次提交记录下来
+    ///
+    /// 只有这些删除会被提交，工作区中的其他修改保持不变。删除后变为空的资源文件夹也会被删除。
+    /// 附件被移动到知识库的回收站而不是直接删除，未纳入版本管理的附件也可以从回收站恢复。
+    ///
+    /// # 参数
+    /// * `message` - 提交信息
+    ///
+    /// # 返回值
+    /// * `Result<Vec<String>, Error>` - 成功时返回被删除的附件路径，没有未被引用的附件时返回空列表
+    /// * 失败时返回 `Err(Error)`
+    pub fn delete_orphans(&self, message: &str) -> Result<Vec<String>, Error> {
+        let orphans: Vec<String> = self
+            .scan()?
+            .into_iter()
+            .filter(|a| !a.referenced)
+            .map(|a| a.path)
+            .collect();
+        if orphans.is_empty() {
+            return Ok(orphans);
+        }
+
+        let trash = crate::trash::Trash::for_root(&self.root)?;
+        for path in &orphans {
+            let full_path = self.root.join(path);
+            trash.move_to_trash(path)?;
+            // 清理变为空的父目录（remove_dir 不会删除非空目录）
+            let mut dir = full_path.parent();
+            while let Some(parent) = dir {
+                if parent == self.root || fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                dir = parent.parent();
+            }
+        }
+
+        let mut repo = git::Repository::open(&self.root)?;
+        if repo.remove_from_index(&orphans)? > 0 {
+            repo.commit(message)?;
+        }
+        Ok(orphans)
+    }
+
+    /// 判断文件及其所在的文件夹是否都会显示在文件树中
+    fn is_visible(&self, filter: &TreeFilter, relative: &str) -> bool {
+        let mut current = self.root.clone();
+        let parts: Vec<&str> = relative.split('/').collect();
+        for (i, part) in parts.iter().enumerate() {
+            current.push(part);
+            if part.starts_with('.') || filter.is_hidden(&current, i + 1 < parts.len()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// 获取页面附件的保存目录，相对于知识库根目录
+fn assets_dir(page: &str, location: AssetLocation) -> String {
+    match location {
+        AssetLocation::Wiki => WIKI_ASSETS_DIR.to_string(),
+        AssetLocation::Page => {
+            let (dir, file_name) = page.rsplit_once('/').unwrap_or(("", page));
+            let (stem, _) = split_file_name(file_name);
+            let folder = format!("{}{}", stem, PAGE_ASSETS_SUFFIX);
+            if dir.is_empty() {
+                folder
+            } else {
+                format!("{}/{}", dir, folder)
+            }
+        }
+    }
+}
+
+/// 将文件名拆分为主干和扩展名（包含 `.`）
+fn split_file_name(file_name: &str) -> (&str, &str) {
+    match file_name.rfind('.') {
+        Some(index) if index > 0 => file_name.split_at(index),
+        _ => (file_name, ""),
+    }
+}
+
+/// 去除文件名中不能用于文件系统或会被隐藏的字符
+fn sanitize_file_name(file_name: &str) -> String {
+    // 只保留最后一个路径成分
+    let name = file_name.rsplit(['/', '\\']).next().unwrap_or("");
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = name
+        .trim()
+        .trim_start_matches('.')
+        .trim_end_matches(['.', '~']);
+    if name.is_empty() {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试附件的导入与读取
+    ///
+    /// # 测试目标：
+    /// * 验证附件保存在页面资源文件夹或知识库资源文件夹中，并返回相对于页面的链接
+    /// * 验证同名附件自动添加序号，不覆盖已有文件
+    /// * 验证文件名中的路径成分被去除，读取时拒绝越过知识库根目录的路径
+    #[test]
+    fn test_import_and_read() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        fs::create_dir_all(root.join("docs")).unwrap();
+        let attachments = Attachments::new(&root);
+
+        let png = [0x89, b'P', b'N', b'G', 0x00, 0xff];
+        let first = attachments
+            .import_bytes("docs/guide.md", "my shot.png", &png, AssetLocation::Page)
+            .unwrap();
+        assert_eq!(first.path, "docs/guide.assets/my shot.png");
+        assert_eq!(first.link, "guide.assets/my%20shot.png");
+        assert_eq!(first.markdown, "![my shot](guide.assets/my%20shot.png)");
+
+        let second = attachments
+            .import_bytes(
+                "docs/guide.md",
+                "my shot.png",
+                b"other",
+                AssetLocation::Page,
+            )
+            .unwrap();
+        assert_eq!(second.path, "docs/guide.assets/my shot-1.png");
+        assert_eq!(attachments.read(&first.path).unwrap(), png);
+
+        let source = temp_dir.path().join("report.pdf");
+        fs::write(&source, b"%PDF").unwrap();
+        let pdf = attachments
+            .import_file("docs/guide.md", &source, AssetLocation::Wiki)
+            .unwrap();
+        assert_eq!(pdf.path, "assets/report.pdf");
+        assert_eq!(pdf.markdown, "[report.pdf](../assets/report.pdf)");
+
+        let escaped = attachments
+            .import_bytes("index.md", "../../evil.png", b"x", AssetLocation::Wiki)
+            .unwrap();
+        assert_eq!(escaped.path, "assets/evil.png");
+
+        assert!(matches!(
+            attachments.read("../report.pdf"),
+            Err(Error::Wiki(_))
+        ));
+        assert!(matches!(
+            attachments.read("assets/missing.png"),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    /// 测试附件检查报告与删除未被引用的附件
+    ///
+    /// # 测试目标：
+    /// * 验证被标准链接、图片链接或双链引用的附件不被视为未引用
+    /// * 验证被忽略文件夹中的页面引用的附件同样不被视为未引用
+    /// * 验证超过阈值的附件被列出
+    /// * 验证删除未被引用的附件只产生一次只包含这些删除的提交
+    /// * 验证被删除的附件（包括未纳入版本管理的附件）可以从回收站恢复
+    #[test]
+    fn test_report_and_delete_orphans() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let mut repo = git::Repository::init(root, Some("test"), Some("test@example.com")).unwrap();
+        fs::create_dir_all(root.join("docs/guide.assets")).unwrap();
+        fs::create_dir_all(root.join("assets")).unwrap();
+        fs::write(
+            root.join("docs/guide.md"),
+            "![图](guide.assets/a%20b.png) [[logo.svg]]",
+        )
+        .unwrap();
+        fs::write(root.join("docs/guide.assets/a b.png"), vec![0u8; 10]).unwrap();
+        fs::write(root.join("assets/logo.svg"), "<svg/>").unwrap();
+        fs::write(root.join("assets/unused.pdf"), vec![0u8; 100]).unwrap();
+        fs::create_dir_all(root.join("old.assets")).unwrap();
+        fs::write(root.join("old.assets/x.png"), "x").unwrap();
+        fs::write(root.join(".gitignore"), "private/\n").unwrap();
+        fs::create_dir_all(root.join("private")).unwrap();
+        fs::write(root.join("private/notes.md"), "![](../assets/shared.png)").unwrap();
+        fs::write(root.join("assets/shared.png"), "shared").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("init").unwrap();
+        fs::write(root.join("assets/untracked.png"), "new").unwrap();
+
+        let attachments = Attachments::new(root);
+        let report = attachments.report(50).unwrap();
+        let orphans: Vec<&str> = report.orphans.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(
+            orphans,
+            vec![
+                "assets/untracked.png",
+                "assets/unused.pdf",
+                "old.assets/x.png"
+            ]
+        );
+        assert_eq!(report.oversized.len(), 1);
+        assert_eq!(report.oversized[0].path, "assets/unused.pdf");
+        assert_eq!(report.total_size, 10 + 6 + 6 + 100 + 3 + 1);
+
+        // 工作区中其他未提交的修改不应被一起提交
+        fs::write(
+            root.join("docs/guide.md"),
+            "![图](guide.assets/a%20b.png) [[logo.svg]] 修改",
+        )
+        .unwrap();
+        let deleted = attachments.delete_orphans("删除未被引用的附件").unwrap();
+        assert_eq!(deleted, orphans);
+        assert!(!root.join("assets/unused.pdf").exists());
+        assert!(!root.join("old.assets").exists());
+        assert!(root.join("assets/logo.svg").exists());
+        assert!(repo.has_uncommitted_changes().unwrap());
+        assert_eq!(repo.ahead_count().unwrap(), 2);
+        assert!(attachments.delete_orphans("再次删除").unwrap().is_empty());
+        assert_eq!(repo.ahead_count().unwrap(), 2);
+
+        let trash = crate::trash::Trash::for_root(root).unwrap();
+        let entry = trash
+            .list()
+            .unwrap()
+            .into_iter()
+            .find(|e| e.original_path == "assets/untracked.png")
+            .unwrap();
+        trash.restore(&entry.id).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("assets/untracked.png")).unwrap(),
+            "new"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! MarkWiki 命令行工具
+//!
+//! 不启动图形界面直接操作知识库，便于在定时任务和 CI 中使用。
+//! 与应用共用同一份知识库存储目录和配置，调用的也是图形界面命令所用的 `service` 模块，
+//! 不依赖 Tauri，可以在没有图形环境的机器上使用 `--no-default-features` 单独构建。
+//! 成功时向标准输出写入 JSON 结果，失败时向标准错误写入 `{"error": ...}` 并以状态码 1 退出。
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use markwiki_lib::git::{self, Repository};
+use markwiki_lib::service;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// 克隆时读取 Git 密码或访问令牌的环境变量，不通过命令行参数传递，以免出现在进程列表和 shell 历史中
+const PASSWORD_ENV: &str = "MARKWIKI_GIT_PASSWORD";
+
+/// MarkWiki 命令行工具
+#[derive(Parser)]
+#[command(name = "markwiki-cli", version)]
+struct Cli {
+    /// 应用数据目录，默认与图形界面使用相同的目录（也可以通过 MARKWIKI_DATA_DIR 环境变量指定）
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 列出所有知识库
+    List,
+    /// 创建本地知识库
+    Create {
+        /// 知识库名称
+        name: String,
+        /// Git 用户名
+        #[arg(long)]
+        username: String,
+        /// Git 邮箱
+        #[arg(long)]
+        email: String,
+    },
+    /// 从远程仓库克隆知识库，知识库名称取自仓库地址
+    ///
+    /// 需要密码或访问令牌时通过 MARKWIKI_GIT_PASSWORD 环境变量提供。
+    Clone {
+        /// 远程仓库地址
+        url: String,
+        /// Git 用户名
+        #[arg(long)]
+        username: String,
+        /// Git 邮箱
+        #[arg(long)]
+        email: String,
+    },
+    /// 删除知识库
+    Delete {
+        /// 知识库名称
+        name: String,
+        /// 忽略未提交的修改和未推送的提交，强制删除
+        #[arg(long)]
+        force: bool,
+        /// 删除前先打包归档
+        #[arg(long)]
+        archive: bool,
+    },
+    /// 读取页面或文件
+    Read {
+        /// 知识库名称
+        wiki: String,
+        /// 相对于知识库根目录的路径
+        path: String,
+    },
+    /// 写入页面或文件，内容从标准输入读取
+    Write {
+        /// 知识库名称
+        wiki: String,
+        /// 相对于知识库根目录的路径
+        path: String,
+        /// 读取时得到的版本标识，文件在此之后被修改时拒绝写入
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// 在页面中搜索
+    Search {
+        /// 知识库名称
+        wiki: String,
+        /// 查询内容，多个关键词之间为“且”的关系
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// 最多返回的页面数量
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// 查看知识库的 Git 状态
+    Status {
+        /// 知识库名称
+        wiki: String,
+    },
+    /// 提交知识库中的所有修改
+    Commit {
+        /// 知识库名称
+        wiki: String,
+        /// 提交信息
+        #[arg(short, long)]
+        message: String,
+    },
+    /// 与远程仓库同步：提交本地修改、获取、合并并推送
+    Sync {
+        /// 知识库名称
+        wiki: String,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(data_dir) = cli.data_dir {
+        markwiki_lib::config::set_data_dir(data_dir);
+    }
+
+    match run(cli.command) {
+        Ok(value) => {
+            // 输出被管道提前关闭（如 `| head`）时不视为错误
+            let _ = writeln!(std::io::stdout(), "{}", to_json(&value));
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{}", to_json(&json!({ "error": error })));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 执行子命令，返回要输出的 JSON 结果
+fn run(command: Command) -> Result<Value, Value> {
+    match command {
+        Command::List => output(service::list_wikis()),
+        Command::Create {
+            name,
+            username,
+            email,
+        } => output(service::create_local_wiki(&name, &username, &email)),
+        Command::Clone {
+            url,
+            username,
+            email,
+        } => {
+            let password = std::env::var(PASSWORD_ENV).unwrap_or_default();
+            output(service::create_remote_wiki(
+                &url, &username, &email, &password,
+            ))
+        }
+        Command::Delete {
+            name,
+            force,
+            archive,
+        } => output(
+            service::delete_wiki(&name, force, archive)
+                .map(|archive_path| json!({ "deleted": name, "archive_path": archive_path })),
+        ),
+        Command::Read { wiki, path } => output(service::read_file(&wiki, &path)),
+        Command::Write {
+            wiki,
+            path,
+            version,
+        } => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|e| json!(format!("读取标准输入失败: {}", e)))?;
+            output(service::save_file(
+                &wiki,
+                &path,
+                &content,
+                version.as_deref(),
+            ))
+        }
+        Command::Search { wiki, query, limit } => {
+            output(service::search_wiki(&wiki, &query.join(" "), limit))
+        }
+        Command::Status { wiki } => {
+            let (wiki, repo) = open(&wiki)?;
+            let remote = repo.get_remote_url("origin").map_err(git_error)?;
+            Ok(json!({
+                "wiki": wiki,
+                "uncommitted": repo.has_uncommitted_changes().map_err(git_error)?,
+                "unpushed": repo.ahead_count().map_err(git_error)?,
+                "remote": remote,
+            }))
+        }
+        Command::Commit { wiki, message } => {
+            let (wiki, mut repo) = open(&wiki)?;
+            repo.add_all().map_err(git_error)?;
+            let committed = match repo.commit(&message) {
+                Ok(()) => true,
+                Err(git::Error::NothingToCommit) => false,
+                Err(e) => return Err(git_error(e)),
+            };
+            Ok(json!({ "wiki": wiki, "committed": committed }))
+        }
+        Command::Sync { wiki } => {
+            let (wiki, mut repo) = open(&wiki)?;
+            repo.sync().map_err(|e| json!(format!("同步失败: {}", e)))?;
+            Ok(json!({ "wiki": wiki, "synced": true }))
+        }
+    }
+}
+
+/// 打开知识库及其 Git 仓库
+fn open(name: &str) -> Result<(String, Repository), Value> {
+    let wiki = service::open_wiki(name).map_err(|e| json!(e))?;
+    let repo = Repository::open(&PathBuf::from(&wiki.path))
+        .map_err(|e| json!(format!("无法打开 Git 仓库: {}", e)))?;
+    Ok((name.to_string(), repo))
+}
+
+/// 将命令的结果转换为 JSON
+fn output<T: Serialize, E: Serialize>(result: Result<T, E>) -> Result<Value, Value> {
+    match result {
+        Ok(value) => Ok(json!(value)),
+        Err(error) => Err(json!(error)),
+    }
+}
+
+/// 将 Git 错误转换为 JSON
+fn git_error(error: git::Error) -> Value {
+    json!(format!("Git 操作失败: {}", error))
+}
+
+/// 格式化 JSON 输出
+fn to_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
@@ -1,13 +1,31 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use crate::archive::ArchiveHistory;
+use crate::attachment::{AssetLocation, Attachment, AttachmentReport, Attachments};
 use crate::config::AppConfig;
-use crate::wiki::build_file_tree;
+use crate::draft::{DraftInfo, Drafts};
+use crate::export::document::{DocumentFormat, DocumentReport};
+use crate::export::site::SiteReport;
+use crate::git::lfs::LfsClient;
+use crate::git::Repository;
+use crate::importer::{ImportReport, ImportSource};
+use crate::server::{ServerInfo, ServerOptions, ServerRegistry, DEFAULT_BIND_ADDRESS};
+use crate::service::{self, DeleteWikiError, SaveFileError};
+use crate::trash::{Trash, TrashEntry};
+use crate::watcher::{WatchEvent, WatcherRegistry};
+use crate::wiki::content::{self, FileContent};
+use crate::wiki::meta::WikiMeta;
+use crate::wiki::page::{Page, PageMetadata};
+use crate::wiki::search::SearchHit;
+use crate::wiki::tag::{TagCount, TagIndex};
+use crate::wiki::template::{self, render_template, Template, TemplateContext};
 use crate::wiki::FileNode;
 use crate::wiki::Wiki;
 
 /// 获取指定知识库的文件结构
 ///
 /// 该函数会递归遍历指定知识库的目录结构，构建完整的文件树结构并返回。
+/// `.git` 目录、被 `.gitignore` 或知识库元数据忽略的路径不会出现在文件树中。
 ///
 /// # 参数
 /// * `wiki_name` - 知识库名称，用于确定要查询的目标知识库
@@ -17,18 +35,37 @@ use crate::wiki::Wiki;
 /// * 失败时返回 `Err(String)`，包含具体错误信息
 #[tauri::command]
 pub async fn get_wiki_file_structure(wiki_name: String) -> Result<FileNode, String> {
-    // 构建目标知识库的存储目录
-    let target_wiki_dir = Wiki::from_name(&wiki_name)
-        .map(|wiki| wiki.path)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    // 获取目标知识库
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 构建文件树并返回
-    build_file_tree(Path::new(&target_wiki_dir)).map_err(|e| e.to_string())
+    wiki.file_tree("", None).map_err(|e| e.to_string())
+}
+
+/// 获取知识库中指定目录的直接子项
+///
+/// 该函数只加载一层目录，子目录的 `children` 为空，可以通过 `has_children` 判断是否需要展开，
+/// 用于在大型知识库中按需加载文件树。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `dir_path` - 目录路径，相对于知识库根目录，空字符串表示知识库根目录
+///
+/// # 返回值
+/// * `Result<FileNode, String>` - 成功时返回 `Ok(FileNode)`，包含目录节点及其直接子项
+/// * 失败时返回 `Err(String)`，包含具体错误信息
+#[tauri::command]
+pub async fn get_wiki_directory(wiki_name: String, dir_path: String) -> Result<FileNode, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    wiki.file_tree(&dir_path, Some(1))
+        .map_err(|e| e.to_string())
 }
 
-/// 获取MarkWiki运行路径下的所有知识库列表
+/// 获取所有知识库列表
 ///
-/// 该函数会遍历Wiki目录下的所有文件夹，检查每个文件夹是否为Git仓库，并判断是否配置了远程仓库。
+/// 该函数会遍历Wiki目录下的所有文件夹，检查每个文件夹是否为Git仓库，并判断是否配置了远程仓库，
+/// 同时合并应用配置中注册的外部知识库。
 /// 主要用于在应用程序界面中显示所有可用的知识库。
 ///
 /// # 返回值
@@ -36,36 +73,7 @@ pub async fn get_wiki_file_structure(wiki_name: String) -> Result<FileNode, Stri
 /// * 失败时返回 `Err(String)`，包含具体错误信息
 #[tauri::command]
 pub async fn get_wiki_list() -> Result<Vec<Wiki>, String> {
-    // 统计知识库信息
-    let mut wikis = Vec::new();
-
-    // 遍历 Wikis 目录下的所有文件夹
-    for entry in std::fs::read_dir(
-        &Wiki::get_wiki_storage_dir()
-            .map_err(|e| format!("无法打开所有知识库的统一存储目录: {}", e))?,
-    )
-    .map_err(|e| format!("读取知识库目录失败: {}", e))?
-    {
-        let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
-        let path = entry.path();
-
-        // 跳过非目录
-        if !path.is_dir() {
-            continue;
-        }
-
-        // 将文件夹名称转换为知识库名称
-        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
-            continue;
-        };
-
-        // 尝试从知识库名称创建 Wiki 实例
-        if let Ok(wiki) = Wiki::from_name(name) {
-            wikis.push(wiki);
-        }
-    }
-
-    Ok(wikis)
+    service::list_wikis()
 }
 
 /// 创建本地知识库
@@ -87,20 +95,7 @@ pub async fn create_local_wiki(
     username: &str,
     email: &str,
 ) -> Result<Wiki, String> {
-    // 检查知识库是否已存在
-    if Wiki::exists(wiki_name) {
-        return Err(format!("知识库 {} 已存在", wiki_name));
-    }
-
-    // 创建知识库
-    let wiki = Wiki::create_local_wiki(wiki_name, username, email)
-        .map_err(|e| format!("创建本地知识库失败: {}", e))?;
-
-    // 保存用户配置到文件
-    let config = AppConfig::new(username, email, None);
-    config.save().map_err(|e| format!("保存配置失败: {}", e))?;
-
-    Ok(wiki)
+    service::create_local_wiki(wiki_name, username, email)
 }
 
 /// 从远程URL创建知识库
@@ -121,89 +116,43 @@ pub async fn create_remote_wiki(
     email: &str,
     password: &str,
 ) -> Result<Wiki, String> {
-    // 从URL提取仓库名称
-    let wiki_name = remote_url
-        .split('/')
-        .next_back()
-        .and_then(|s| s.split('.').next())
-        .ok_or("从URL提取仓库名称失败")?;
-
-    // 检查知识库是否已存在
-    if Wiki::exists(wiki_name) {
-        return Err(format!("知识库 {} 已存在", wiki_name));
-    }
-
-    // 从远程URL创建知识库
-    let wiki = Wiki::create_remote_wiki(
-        wiki_name,
-        remote_url,
-        username,
-        email,
-        if password.is_empty() {
-            None
-        } else {
-            Some(password)
-        },
-    )
-    .map_err(|e| format!("从远程URL创建知识库失败: {}", e))?;
-
-    // 保存用户配置到文件
-    let config = AppConfig::new(
-        username,
-        email,
-        if password.is_empty() {
-            None
-        } else {
-            Some(password)
-        },
-    );
-    config.save().map_err(|e| format!("保存配置失败: {}", e))?;
-
-    Ok(wiki)
+    service::create_remote_wiki(remote_url, username, email, password)
 }
 
 /// 删除知识库
 ///
 /// 该函数会删除指定名称的知识库，包括其所有内容。
-/// 请谨慎使用此功能，删除操作是不可逆的。
+/// 如果知识库存在未提交的修改或未推送的提交，除非 `force` 为 `true`，否则拒绝删除。
+/// 如果 `archive` 为 `true`，删除前会先将知识库打包为 `.tar.gz` 归档，便于之后恢复。
 ///
 /// # 参数
 /// * `wiki_name` - 要删除的知识库名称
+/// * `force` - 是否忽略未保存的工作强制删除
+/// * `archive` - 删除前是否先打包归档
 ///
 /// # 返回值
-/// * `Result<(), String>` - 成功时返回 `Ok(())`
-/// * 失败时返回 `Err(String)`，包含具体错误信息
+/// * `Result<Option<String>, DeleteWikiError>` - 成功时返回归档文件路径（未归档时为 `None`）
+/// * 失败时返回 `Err(DeleteWikiError)`，包含未保存的工作或具体错误信息
 #[tauri::command]
-pub async fn delete_wiki(wiki_name: &str) -> Result<(), String> {
-    // 构建目标知识库的存储目录
-    let target_wiki_dir = Wiki::get_wiki_storage_dir()
-        .map_err(|_| "无法打开所有知识库的统一存储目录")?
-        .join(wiki_name);
-
-    // 检查知识库目录是否存在
-    if !target_wiki_dir.exists() {
-        return Err(format!("知识库不存在: {}", wiki_name));
-    }
-
-    // 检查是否为目录
-    if !target_wiki_dir.is_dir() {
-        return Err(format!("指定的路径不是一个目录: {:?}", target_wiki_dir));
-    }
-
-    // 删除知识库目录
-    std::fs::remove_dir_all(&target_wiki_dir).map_err(|e| format!("删除知识库失败: {}", e))?;
-
-    Ok(())
+pub async fn delete_wiki(
+    wiki_name: &str,
+    force: Option<bool>,
+    archive: Option<bool>,
+) -> Result<Option<String>, DeleteWikiError> {
+    service::delete_wiki(wiki_name, force.unwrap_or(false), archive.unwrap_or(false))
 }
 
 /// 创建文件
 ///
 /// 该函数会在指定知识库的指定路径下创建一个新文件。
+/// 如果指定了模板，页面会使用模板内容预先填充，模板中的 `{{title}}`、`{{date}}`、
+/// `{{author}}` 占位符会被替换为页面标题、当前日期和 Git 用户名。
 ///
 /// # 参数
 /// * `wiki_name` - 知识库名称
 /// * `file_name` - 文件名
-/// * `parent_path` - 父目录路径，相对于知识库根目录
+/// * `parent_path` - 父目录路径，相对于知识库根目录（与 `FileNode.path` 的格式相同）
+/// * `template` - 可选的模板名称，为 `None` 时创建空文件
 ///
 /// # 返回值
 /// * `Result<(), String>` - 成功时返回 `Ok(())`
@@ -213,6 +162,7 @@ pub async fn create_file(
     wiki_name: String,
     file_name: String,
     parent_path: String,
+    template: Option<String>,
 ) -> Result<(), String> {
     // 验证文件名
     if !file_name.ends_with(".md") {
@@ -220,19 +170,12 @@ pub async fn create_file(
     }
 
     // 获取知识库路径
-    let wiki =
-        Wiki::from_name(&wiki_name).map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 构建文件的完整路径
-    let mut full_path = std::path::PathBuf::from(&wiki.path);
-
-    // 添加父目录路径
-    if !parent_path.is_empty() && parent_path != "/" {
-        full_path = full_path.join(&parent_path);
-    }
-
-    // 添加文件名
-    full_path = full_path.join(&file_name);
+    let full_path = wiki
+        .resolve_path(&format!("{}/{}", parent_path, file_name))
+        .map_err(|e| e.to_string())?;
 
     // 检查文件是否已存在
     if full_path.exists() {
@@ -244,12 +187,50 @@ pub async fn create_file(
         std::fs::create_dir_all(parent_dir).map_err(|e| format!("创建父目录失败: {}", e))?;
     }
 
-    // 创建空文件
-    std::fs::File::create(full_path).map_err(|e| format!("创建文件失败: {}", e))?;
+    // 使用模板填充页面内容
+    let content = match template.filter(|t| !t.is_empty()) {
+        Some(template) => {
+            let author = Repository::open(Path::new(&wiki.path))
+                .and_then(|repo| repo.get_user_config())
+                .map(|(name, _)| name)
+                .unwrap_or_default();
+            let title = file_name.trim_end_matches(".md");
+            let context = TemplateContext::new(title, &author);
+            render_template(Path::new(&wiki.path), &template, &context)
+                .map_err(|e| format!("读取模板失败: {}", e))?
+        }
+        None => String::new(),
+    };
+
+    // 创建文件，已存在时不覆盖
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&full_path)
+        .map_err(|e| format!("创建文件失败: {}", e))?;
+    std::io::Write::write_all(&mut file, content.as_bytes())
+        .map_err(|e| format!("写入模板内容失败: {}", e))?;
 
     Ok(())
 }
 
+/// 列出知识库中的页面模板
+///
+/// 模板保存在知识库的 `.markwiki/templates/` 目录中。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<Vec<Template>, String>` - 成功时返回按名称排列的模板列表
+/// * 失败时返回 `Err(String)`，包含具体错误信息
+#[tauri::command]
+pub async fn list_templates(wiki_name: String) -> Result<Vec<Template>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    template::list_templates(Path::new(&wiki.path)).map_err(|e| format!("读取模板失败: {}", e))
+}
+
 /// 创建文件夹
 ///
 /// 该函数会在指定知识库的指定路径下创建一个新文件夹。
@@ -257,7 +238,7 @@ pub async fn create_file(
 /// # 参数
 /// * `wiki_name` - 知识库名称
 /// * `folder_name` - 文件夹名
-/// * `parent_path` - 父目录路径，相对于知识库根目录
+/// * `parent_path` - 父目录路径，相对于知识库根目录（与 `FileNode.path` 的格式相同）
 ///
 /// # 返回值
 /// * `Result<(), String>` - 成功时返回 `Ok(())`
@@ -269,19 +250,12 @@ pub async fn create_folder(
     parent_path: String,
 ) -> Result<(), String> {
     // 获取知识库路径
-    let wiki =
-        Wiki::from_name(&wiki_name).map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 构建文件夹的完整路径
-    let mut full_path = std::path::PathBuf::from(&wiki.path);
-
-    // 添加父目录路径
-    if !parent_path.is_empty() && parent_path != "/" {
-        full_path = full_path.join(&parent_path);
-    }
-
-    // 添加文件夹名
-    full_path = full_path.join(&folder_name);
+    let full_path = wiki
+        .resolve_path(&format!("{}/{}", parent_path, folder_name))
+        .map_err(|e| e.to_string())?;
 
     // 检查文件夹是否已存在
     if full_path.exists() {
@@ -300,164 +274,218 @@ pub async fn create_folder(
 }
 /// 读取文件内容
 ///
-/// 该函数会读取指定知识库中指定路径的文件内容。
+/// 该函数会读取指定知识库中指定路径的文件内容，并返回内容的版本标识。
+/// 保存文件时传回版本标识，可以检测文件是否在编辑期间被修改。
 ///
 /// # 参数
 /// * `wiki_name` - 知识库名称
-/// * `file_path` - 文件路径，相对于知识库根目录
+/// * `file_path` - 文件路径，相对于知识库根目录（与 `FileNode.path` 的格式相同）
 ///
 /// # 返回值
-/// * `Result<String, String>` - 成功时返回 `Ok(String)`，包含文件内容
+/// * `Result<FileContent, String>` - 成功时返回 `Ok(FileContent)`，包含文件内容和版本信息
 /// * 失败时返回 `Err(String)`，包含具体错误信息
 #[tauri::command]
-pub async fn read_file(wiki_name: String, file_path: String) -> Result<String, String> {
-    // 添加详细调试信息
-    eprintln!(
-        "开始读取文件 - wiki_name: {}, file_path: {}",
-        wiki_name, file_path
-    );
-
-    // 获取知识库路径
-    let wiki = Wiki::from_name(&wiki_name).map_err(|e| {
-        let error_msg = format!("无法打开知识库 {}: {}", wiki_name, e);
-        eprintln!("{}", error_msg);
-        return error_msg;
-    })?;
-
-    eprintln!("知识库路径: {}", wiki.path);
+pub async fn read_file(wiki_name: String, file_path: String) -> Result<FileContent, String> {
+    service::read_file(&wiki_name, &file_path)
+}
 
-    // 构建文件的完整路径
-    let mut full_path = std::path::PathBuf::from(&wiki.path);
-
-    // 添加文件路径，使用更健壮的路径处理
-    if !file_path.is_empty() && file_path != "/" {
-        // 标准化路径分隔符
-        let normalized_path = file_path.replace('/', &std::path::MAIN_SEPARATOR.to_string());
-        // 如果路径以分隔符开头，移除它
-        let normalized_path = if normalized_path.starts_with(std::path::MAIN_SEPARATOR) {
-            &normalized_path[1..]
-        } else {
-            &normalized_path
-        };
-        full_path = full_path.join(normalized_path);
-    }
+/// 写入文件
+///
+/// 该函数会将指定内容写入指定知识库中指定路径的文件。
+/// 如果提供了读取文件时得到的版本标识，而文件在此之后被同步或外部编辑器修改，
+/// 写入会被拒绝并返回 `SaveFileError::Conflict`，磁盘上的内容保持不变。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `file_path` - 文件路径，相对于知识库根目录（与 `FileNode.path` 的格式相同）
+/// * `content` - 要写入的内容
+/// * `version` - 读取文件时得到的版本标识，为 `None` 时直接覆盖
+///
+/// # 返回值
+/// * `Result<FileContent, SaveFileError>` - 成功时返回写入后的内容和新的版本信息
+/// * 失败时返回 `Err(SaveFileError)`，包含冲突时的当前内容或具体错误信息
+#[tauri::command]
+pub async fn save_file(
+    wiki_name: String,
+    file_path: String,
+    content: String,
+    version: Option<String>,
+) -> Result<FileContent, SaveFileError> {
+    service::save_file(&wiki_name, &file_path, &content, version.as_deref())
+}
 
-    // 打印完整路径（调试用，确认路径是否正确）
-    eprintln!("读取文件完整路径: {:?}", full_path);
+/// 在知识库的页面中搜索
+///
+/// 查询按空白拆分为多个关键词，忽略大小写，只返回包含所有关键词的页面。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `query` - 查询内容
+/// * `limit` - 最多返回的页面数量，默认为 50
+///
+/// # 返回值
+/// * `Result<Vec<SearchHit>, String>` - 成功时返回按得分排列的匹配页面
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn search_wiki(
+    wiki_name: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    service::search_wiki(&wiki_name, &query, limit.unwrap_or(50))
+}
 
-    // 检查文件是否存在
-    if !full_path.exists() {
-        let error_msg = format!("文件不存在: {}", full_path.display());
-        eprintln!("{}", error_msg);
-        return Err(error_msg);
-    }
+/// 获取页面元数据
+///
+/// 该函数会解析页面开头的 YAML front matter，返回标题、标签、负责人、状态以及所有字段。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `file_path` - 页面路径，相对于知识库根目录
+///
+/// # 返回值
+/// * `Result<PageMetadata, String>` - 成功时返回 `Ok(PageMetadata)`
+/// * 失败时返回 `Err(String)`，包含具体错误信息
+#[tauri::command]
+pub async fn get_page_metadata(
+    wiki_name: String,
+    file_path: String,
+) -> Result<PageMetadata, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+    let full_path = wiki.resolve_path(&file_path).map_err(|e| e.to_string())?;
 
-    // 检查是否为文件
-    if !full_path.is_file() {
-        let error_msg = format!("指定的路径不是一个文件: {}", full_path.display());
-        eprintln!("{}", error_msg);
-        return Err(error_msg);
-    }
+    let file = content::read_text(&full_path)
+        .map_err(|e| format!("读取文件失败 {}: {}", full_path.display(), e))?;
+    let page =
+        Page::parse(&file.content).map_err(|e| format!("解析页面 {} 失败: {}", file_path, e))?;
 
-    // 读取文件内容
-    std::fs::read_to_string(&full_path)
-        .map_err(|e| {
-            let error_msg = format!("读取文件失败 {}: {}", full_path.display(), e);
-            eprintln!("{}", error_msg);
-            return error_msg;
-        })
-        .map(|content| {
-            // 输出文件内容长度（避免内容过长时输出过多）
-            eprintln!("文件内容读取成功，长度: {} 字符", content.len());
-            // 如果内容较短，可以输出前100个字符进行检查
-            if content.len() <= 100 {
-                eprintln!("文件内容: {}", content);
-            } else {
-                eprintln!("文件内容（前100字符）: {}", &content[0..100]);
-            }
-            // 返回文件内容
-            content
-        })
+    let file_name = file_path.rsplit('/').next().unwrap_or(&file_path);
+    Ok(page.metadata(file_name))
 }
 
-/// 写入文件
+/// 更新页面的 front matter 字段
 ///
-/// 该函数会将指定内容写入指定知识库中指定路径的文件。
+/// 只修改给定的字段，其他字段和正文保持不变；字段值为 `null` 时删除该字段。
+/// 与 `save_file` 相同，提供版本标识时会检测文件是否已被修改。
 ///
 /// # 参数
 /// * `wiki_name` - 知识库名称
-/// * `file_path` - 文件路径，相对于知识库根目录
+/// * `file_path` - 页面路径，相对于知识库根目录
+/// * `fields` - 需要更新的字段
+/// * `version` - 读取文件时得到的版本标识，为 `None` 时基于磁盘上的当前内容修改
 ///
 /// # 返回值
-/// * `Result<String, String>` - 成功时返回 `Ok(String)`，包含文件内容
-/// * 失败时返回 `Err(String)`，包含具体错误信息
-
+/// * `Result<FileContent, SaveFileError>` - 成功时返回写入后的内容和新的版本信息
+/// * 失败时返回 `Err(SaveFileError)`，包含冲突时的当前内容或具体错误信息
 #[tauri::command]
-pub async fn save_file(
+pub async fn update_page_metadata(
     wiki_name: String,
     file_path: String,
-    content: String,
-) -> Result<(), String> {
-    // 添加详细调试信息
-    eprintln!(
-        "开始保存文件 - wiki_name: {}, file_path: {}, 内容长度: {}",
-        wiki_name,
-        file_path,
-        content.len()
-    );
+    fields: serde_json::Map<String, serde_json::Value>,
+    version: Option<String>,
+) -> Result<FileContent, SaveFileError> {
+    let wiki = service::open_wiki(&wiki_name)?;
+    let full_path = wiki.resolve_path(&file_path).map_err(|e| e.to_string())?;
+
+    let file = content::read_text(&full_path)
+        .map_err(|e| format!("读取文件失败 {}: {}", full_path.display(), e))?;
+    if version.as_ref().is_some_and(|v| *v != file.version) {
+        return Err(SaveFileError::Conflict {
+            current: Some(file),
+        });
+    }
 
-    // 获取知识库路径
-    let wiki = Wiki::from_name(&wiki_name).map_err(|e| {
-        let error_msg = format!("无法打开知识库 {}: {}", wiki_name, e);
-        eprintln!("{}", error_msg);
-        return error_msg;
-    })?;
-
-    eprintln!("知识库路径: {}", wiki.path);
-
-    // 构建文件的完整路径（使用更健壮的路径处理）
-    let mut full_path = std::path::PathBuf::from(&wiki.path);
-    if !file_path.is_empty() && file_path != "/" {
-        // 标准化路径分隔符
-        let normalized_path = file_path.replace('/', &std::path::MAIN_SEPARATOR.to_string());
-        // 如果路径以分隔符开头，移除它
-        let normalized_path = if normalized_path.starts_with(std::path::MAIN_SEPARATOR) {
-            &normalized_path[1..]
-        } else {
-            &normalized_path
-        };
-        full_path = full_path.join(normalized_path);
+    let mut page =
+        Page::parse(&file.content).map_err(|e| format!("解析页面 {} 失败: {}", file_path, e))?;
+    for (key, value) in &fields {
+        page.set_field(key, value)
+            .map_err(|e| format!("更新字段 {} 失败: {}", key, e))?;
     }
+    let updated = page
+        .render()
+        .map_err(|e| format!("生成页面 {} 失败: {}", file_path, e))?;
+
+    let saved =
+        content::write_text(&full_path, &updated, Some(&file.version)).map_err(|e| match e {
+            crate::wiki::Error::Conflict { current, .. } => SaveFileError::Conflict { current },
+            e => SaveFileError::Failed {
+                message: format!("保存文件失败 {}: {}", full_path.display(), e),
+            },
+        })?;
+    service::update_tag_index(&wiki, &file_path, &saved.content);
+    Ok(saved)
+}
 
-    // 打印完整路径（调试用）
-    eprintln!("保存文件完整路径: {:?}", full_path);
+/// 列出知识库中的所有标签及使用数量
+///
+/// 标签来自页面 front matter 中的 `tags` 字段和正文中的 `#标签`。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<Vec<TagCount>, String>` - 成功时返回按名称排序的标签列表
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn list_tags(wiki_name: String) -> Result<Vec<TagCount>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
 
-    // 确保父目录存在
-    if let Some(parent_dir) = full_path.parent() {
-        eprintln!("确保父目录存在: {:?}", parent_dir);
-        std::fs::create_dir_all(parent_dir).map_err(|e| {
-            let error_msg = format!("创建父目录失败 {}: {}", parent_dir.display(), e);
-            eprintln!("{}", error_msg);
-            return error_msg;
-        })?;
-    }
+    TagIndex::for_wiki(&wiki)
+        .map(|index| index.tags())
+        .map_err(|e| format!("读取标签索引失败: {}", e))
+}
+
+/// 列出使用指定标签的页面，包括使用其下级标签（如 `项目/归档`）的页面
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `tag` - 标签名称
+///
+/// # 返回值
+/// * `Result<Vec<String>, String>` - 成功时返回页面相对路径
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn get_pages_for_tag(wiki_name: String, tag: String) -> Result<Vec<String>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
 
-    // 写入文件内容
-    std::fs::write(&full_path, content).map_err(|e| {
-        let error_msg = format!("保存文件失败 {}: {}", full_path.display(), e);
-        eprintln!("{}", error_msg);
-        return error_msg;
-    })
+    TagIndex::for_wiki(&wiki)
+        .map(|index| index.pages_for(&tag))
+        .map_err(|e| format!("读取标签索引失败: {}", e))
+}
+
+/// 在所有页面中重命名标签
+///
+/// 修改的页面会作为一次单独的提交记录下来，工作区中的其他修改不会被一起提交。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `from` - 原标签名称
+/// * `to` - 新标签名称
+///
+/// # 返回值
+/// * `Result<Vec<String>, String>` - 成功时返回被修改的页面相对路径
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn rename_tag(
+    wiki_name: String,
+    from: String,
+    to: String,
+) -> Result<Vec<String>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let mut index = TagIndex::for_wiki(&wiki).map_err(|e| format!("读取标签索引失败: {}", e))?;
+    index
+        .rename(&from, &to, &format!("将标签 {} 重命名为 {}", from, to))
+        .map_err(|e| format!("重命名标签失败: {}", e))
 }
-// 添加这行导入（放在文件顶部，其他导入之后）
-use crate::git::Repository;
-use std::path::PathBuf;
 
 /// Git 同步操作
 ///
 /// 执行完整的 Git 同步流程：提交本地修改 → 获取远程更新 → 合并 → 推送
 /// 只支持无冲突情况下的同步
 ///
+/// 同步期间会暂停该知识库的文件监视，完成后通知界面重新加载文件树
+///
 /// # 参数
 /// * `wiki_name` - 知识库名称
 ///
@@ -465,22 +493,28 @@ use std::path::PathBuf;
 /// * `Result<(), String>` - 成功时返回 `Ok(())`
 /// * 失败时返回 `Err(String)`，包含具体错误信息
 #[tauri::command]
-pub async fn git_sync(wiki_name: String) -> Result<(), String> {
+pub async fn git_sync(
+    app: tauri::AppHandle,
+    watchers: tauri::State<'_, WatcherRegistry>,
+    wiki_name: String,
+) -> Result<(), String> {
     // 获取知识库路径
-    let wiki = crate::wiki::Wiki::from_name(&wiki_name)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 打开 Git 仓库
     let mut repo = Repository::open(&PathBuf::from(&wiki.path))
         .map_err(|e| format!("无法打开 Git 仓库: {}", e))?;
 
-    // 执行同步
-    repo.sync().map_err(|e| format!("同步失败: {}", e))
+    // 执行同步，检出期间暂停文件监视
+    watchers.pause(&wiki_name);
+    let result = repo.sync().map_err(|e| format!("同步失败: {}", e));
+    watchers.resume(&wiki_name, |event| emit_watch_event(&app, event));
+    result
 }
 
 /// Git 提交并同步
 ///
-/// 先提交本地修改，然后执行同步
+/// 先提交本地修改，然后执行同步。同步期间会暂停该知识库的文件监视
 ///
 /// # 参数
 /// * `wiki_name` - 知识库名称
@@ -490,10 +524,14 @@ pub async fn git_sync(wiki_name: String) -> Result<(), String> {
 /// * `Result<(), String>` - 成功时返回 `Ok(())`
 /// * 失败时返回 `Err(String)`，包含具体错误信息
 #[tauri::command]
-pub async fn git_commit_and_sync(wiki_name: String, message: String) -> Result<(), String> {
+pub async fn git_commit_and_sync(
+    app: tauri::AppHandle,
+    watchers: tauri::State<'_, WatcherRegistry>,
+    wiki_name: String,
+    message: String,
+) -> Result<(), String> {
     // 获取知识库路径
-    let wiki = crate::wiki::Wiki::from_name(&wiki_name)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 打开 Git 仓库
     let mut repo = Repository::open(&PathBuf::from(&wiki.path))
@@ -515,8 +553,72 @@ pub async fn git_commit_and_sync(wiki_name: String, message: String) -> Result<(
     repo.commit(&message)
         .map_err(|e| format!("提交失败: {}", e))?;
 
-    // 执行同步
-    repo.sync().map_err(|e| format!("同步失败: {}", e))
+    // 执行同步，检出期间暂停文件监视
+    watchers.pause(&wiki_name);
+    let result = repo.sync().map_err(|e| format!("同步失败: {}", e));
+    watchers.resume(&wiki_name, |event| emit_watch_event(&app, event));
+    result
+}
+
+/// 生成用于离线同步的 Git bundle
+///
+/// bundle 是包含提交历史的单个文件，可以通过 U 盘等方式带到无法联网的机器上，
+/// 再用 `git_sync_bundle` 同步。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `output_path` - bundle 文件的保存路径
+/// * `since` - 起点，只打包该提交之后的提交，可以是提交 ID、分支或标签；为空时打包完整历史
+///
+/// # 返回值
+/// * `Result<String, String>` - 成功时返回 bundle 文件路径
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn git_create_bundle(
+    wiki_name: String,
+    output_path: String,
+    since: Option<String>,
+) -> Result<String, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+    let repo = Repository::open(&PathBuf::from(&wiki.path))
+        .map_err(|e| format!("无法打开 Git 仓库: {}", e))?;
+
+    let output = PathBuf::from(&output_path);
+    repo.create_bundle(&output, since.as_deref())
+        .map_err(|e| format!("生成 bundle 失败: {}", e))?;
+    Ok(output.display().to_string())
+}
+
+/// 从 Git bundle 同步
+///
+/// 与 `git_sync` 使用相同的合并逻辑：提交本地修改 → 读取 bundle → 合并，
+/// 只支持无冲突情况下的同步。同步期间会暂停该知识库的文件监视。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `bundle_path` - bundle 文件路径
+///
+/// # 返回值
+/// * `Result<bool, String>` - 本地内容被更新时返回 `true`，已是最新时返回 `false`
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn git_sync_bundle(
+    app: tauri::AppHandle,
+    watchers: tauri::State<'_, WatcherRegistry>,
+    wiki_name: String,
+    bundle_path: String,
+) -> Result<bool, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+    let mut repo = Repository::open(&PathBuf::from(&wiki.path))
+        .map_err(|e| format!("无法打开 Git 仓库: {}", e))?;
+
+    // 执行同步，检出期间暂停文件监视
+    watchers.pause(&wiki_name);
+    let result = repo
+        .sync_bundle(&PathBuf::from(&bundle_path))
+        .map_err(|e| format!("从 bundle 同步失败: {}", e));
+    watchers.resume(&wiki_name, |event| emit_watch_event(&app, event));
+    result
 }
 
 /// 检查是否有未提交的修改
@@ -530,8 +632,7 @@ pub async fn git_commit_and_sync(wiki_name: String, message: String) -> Result<(
 #[tauri::command]
 pub async fn git_check_status(wiki_name: String) -> Result<bool, String> {
     // 获取知识库路径
-    let wiki = crate::wiki::Wiki::from_name(&wiki_name)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 打开 Git 仓库
     let repo = Repository::open(&PathBuf::from(&wiki.path))
@@ -558,8 +659,7 @@ pub async fn git_set_user_config(
     email: String,
 ) -> Result<(), String> {
     // 获取知识库路径
-    let wiki = crate::wiki::Wiki::from_name(&wiki_name)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 打开 Git 仓库
     let repo = Repository::open(&PathBuf::from(&wiki.path))
@@ -580,8 +680,7 @@ pub async fn git_set_user_config(
 #[tauri::command]
 pub async fn git_get_user_config(wiki_name: String) -> Result<(String, String), String> {
     // 获取知识库路径
-    let wiki = crate::wiki::Wiki::from_name(&wiki_name)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 打开 Git 仓库
     let repo = Repository::open(&PathBuf::from(&wiki.path))
@@ -595,6 +694,9 @@ pub async fn git_get_user_config(wiki_name: String) -> Result<(String, String),
 ///
 /// 该函数会为指定的知识库设置远程仓库信息，包括URL、用户名、邮箱和密码
 ///
+/// 远程仓库可以是本地目录或挂载的网络共享上的裸仓库（绝对路径或 `file://` 地址），
+/// 这种情况下不需要密码，也不会修改应用配置中保存的凭据。
+///
 /// # 参数
 /// * `wiki_name` - 知识库名称
 /// * `remote_url` - 远程仓库URL
@@ -614,8 +716,7 @@ pub async fn setup_remote_repo(
     password: String,
 ) -> Result<(), String> {
     // 获取知识库路径
-    let wiki = crate::wiki::Wiki::from_name(&wiki_name)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 打开 Git 仓库
     let repo = Repository::open(&PathBuf::from(&wiki.path))
@@ -625,12 +726,25 @@ pub async fn setup_remote_repo(
     repo.set_user_config(&username, &email)
         .map_err(|e| format!("设置用户配置失败: {}", e))?;
 
+    // 本地目录或网络共享上的远程仓库必须已经存在
+    if let Some(path) = crate::git::local_path(&remote_url) {
+        if !path.exists() {
+            return Err(format!("本地远程仓库不存在: {}", path.display()));
+        }
+    }
+
     // 设置远程仓库
     repo.set_remote("origin", &remote_url)
         .map_err(|e| format!("设置远程仓库失败: {}", e))?;
 
+    // 本地远程仓库不需要凭据，不覆盖为网络远程仓库保存的全局凭据
+    if crate::git::is_local_url(&remote_url) {
+        return Ok(());
+    }
+
     // 保存用户凭据到应用配置
-    let config = crate::config::AppConfig::new(
+    let mut config = AppConfig::load_or_default().map_err(|e| format!("读取配置失败: {}", e))?;
+    config.set_git_credentials(
         &username,
         &email,
         if password.is_empty() {
@@ -644,6 +758,41 @@ pub async fn setup_remote_repo(
     Ok(())
 }
 
+/// 在指定路径创建知识库的裸仓库镜像
+///
+/// 镜像可以放在本地磁盘或挂载的网络共享上，作为局域网内其他机器的远程仓库，
+/// 不需要任何 Git 服务器。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `mirror_path` - 镜像路径，必须不存在或是空目录
+/// * `set_as_remote` - 是否将镜像设置为该知识库的远程仓库，默认为 `false`
+///
+/// # 返回值
+/// * `Result<String, String>` - 成功时返回镜像路径
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn git_create_mirror(
+    wiki_name: String,
+    mirror_path: String,
+    set_as_remote: Option<bool>,
+) -> Result<String, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+    let repo = Repository::open(&PathBuf::from(&wiki.path))
+        .map_err(|e| format!("无法打开 Git 仓库: {}", e))?;
+
+    let path = PathBuf::from(&mirror_path);
+    repo.create_mirror(&path)
+        .map_err(|e| format!("创建镜像失败: {}", e))?;
+    let path = std::path::absolute(&path).unwrap_or(path);
+
+    if set_as_remote.unwrap_or(false) {
+        repo.set_remote("origin", &path.to_string_lossy())
+            .map_err(|e| format!("设置远程仓库失败: {}", e))?;
+    }
+    Ok(path.display().to_string())
+}
+
 /// 获取知识库远程仓库配置信息
 ///
 /// 该函数会获取指定知识库的远程仓库URL和用户配置信息
@@ -659,8 +808,7 @@ pub async fn get_remote_repo_config(
     wiki_name: String,
 ) -> Result<(Option<String>, String, String), String> {
     // 获取知识库路径
-    let wiki = crate::wiki::Wiki::from_name(&wiki_name)
-        .map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))?;
+    let wiki = service::open_wiki(&wiki_name)?;
 
     // 打开 Git 仓库
     let repo = Repository::open(&PathBuf::from(&wiki.path))
@@ -678,3 +826,896 @@ pub async fn get_remote_repo_config(
 
     Ok((remote_url, username, email))
 }
+
+/// 查找链接到指定文件或文件夹的页面
+///
+/// 删除文件或文件夹之前，前端可以调用该函数提示用户哪些页面会出现失效链接。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `path` - 文件或文件夹路径，相对于知识库根目录
+///
+/// # 返回值
+/// * `Result<Vec<String>, String>` - 成功时返回包含链接的页面路径列表
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn get_inbound_links(wiki_name: String, path: String) -> Result<Vec<String>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    crate::wiki::link::find_inbound_links(Path::new(&wiki.path), &path)
+        .map_err(|e| format!("查找链接失败: {}", e))
+}
+
+/// 删除文件或文件夹
+///
+/// 被删除的文件或文件夹会被移动到知识库的回收站中，之后可以通过 `restore_item` 恢复。
+/// 如果有其他页面链接到被删除的内容，除非 `force` 为 `true`，否则拒绝删除并返回这些页面。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `path` - 文件或文件夹路径，相对于知识库根目录
+/// * `force` - 是否忽略指向该路径的链接强制删除
+///
+/// # 返回值
+/// * `Result<TrashEntry, String>` - 成功时返回回收站条目
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn delete_item(
+    wiki_name: String,
+    path: String,
+    force: Option<bool>,
+) -> Result<TrashEntry, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    // 检查路径是否合法
+    wiki.resolve_path(&path).map_err(|e| e.to_string())?;
+
+    // 检查是否有页面链接到被删除的内容
+    if !force.unwrap_or(false) {
+        let pages = crate::wiki::link::find_inbound_links(Path::new(&wiki.path), &path)
+            .map_err(|e| format!("查找链接失败: {}", e))?;
+        if !pages.is_empty() {
+            return Err(format!("以下页面链接到 {}: {}", path, pages.join(", ")));
+        }
+    }
+
+    let trash = Trash::for_wiki(&wiki).map_err(|e| format!("无法打开回收站: {}", e))?;
+    trash
+        .move_to_trash(&path)
+        .map_err(|e| format!("删除失败: {}", e))
+}
+
+/// 列出回收站中的条目
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<Vec<TrashEntry>, String>` - 成功时返回按删除时间倒序排列的条目列表
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn list_trash(wiki_name: String) -> Result<Vec<TrashEntry>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let trash = Trash::for_wiki(&wiki).map_err(|e| format!("无法打开回收站: {}", e))?;
+    trash.list().map_err(|e| format!("读取回收站失败: {}", e))
+}
+
+/// 从回收站恢复文件或文件夹
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `id` - 回收站条目的唯一标识
+///
+/// # 返回值
+/// * `Result<TrashEntry, String>` - 成功时返回被恢复的条目
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn restore_item(wiki_name: String, id: String) -> Result<TrashEntry, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let trash = Trash::for_wiki(&wiki).map_err(|e| format!("无法打开回收站: {}", e))?;
+    trash.restore(&id).map_err(|e| format!("恢复失败: {}", e))
+}
+
+/// 获取知识库的统一存储目录
+///
+/// # 返回值
+/// * `Result<String, String>` - 成功时返回当前使用的存储目录
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn get_wiki_storage_dir() -> Result<String, String> {
+    Wiki::get_wiki_storage_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .map_err(|e| format!("无法打开所有知识库的统一存储目录: {}", e))
+}
+
+/// 设置知识库的统一存储目录
+///
+/// 新的存储目录会保存到应用配置中。如果 `migrate` 为 `true`，
+/// 会先将当前存储目录中的所有知识库移动到新的存储目录。
+///
+/// # 参数
+/// * `storage_dir` - 新的存储目录（必须是绝对路径），为 `None` 时恢复为默认存储目录
+/// * `migrate` - 是否将已有知识库迁移到新的存储目录
+///
+/// # 返回值
+/// * `Result<Vec<String>, String>` - 成功时返回已迁移的知识库名称列表
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn set_wiki_storage_dir(
+    storage_dir: Option<String>,
+    migrate: bool,
+) -> Result<Vec<String>, String> {
+    // 确定新的存储目录
+    let new_dir = match &storage_dir {
+        Some(dir) if !Path::new(dir).is_absolute() => {
+            return Err(format!("存储目录必须是绝对路径: {}", dir));
+        }
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            Wiki::get_default_storage_dir().map_err(|e| format!("获取默认存储目录失败: {}", e))?
+        }
+    };
+
+    // 迁移已有知识库
+    let migrated = if migrate {
+        let current_dir = Wiki::get_wiki_storage_dir()
+            .map_err(|e| format!("无法打开所有知识库的统一存储目录: {}", e))?;
+        Wiki::migrate_storage_dir(&current_dir, &new_dir)
+            .map_err(|e| format!("迁移知识库失败: {}", e))?
+    } else {
+        std::fs::create_dir_all(&new_dir).map_err(|e| format!("创建存储目录失败: {}", e))?;
+        Vec::new()
+    };
+
+    // 保存新的存储目录到配置
+    let mut config = AppConfig::load_or_default().map_err(|e| format!("读取配置失败: {}", e))?;
+    config.wiki_storage_dir = storage_dir;
+    config.save().map_err(|e| format!("保存配置失败: {}", e))?;
+
+    Ok(migrated)
+}
+
+/// 迁移旧版本的知识库
+///
+/// 旧版本将知识库存储在可执行文件所在目录（Android 上为临时目录）下，
+/// 该函数会将其中的知识库移动到当前的统一存储目录。
+///
+/// # 返回值
+/// * `Result<Vec<String>, String>` - 成功时返回已迁移的知识库名称列表，没有旧存储目录时返回空列表
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn migrate_legacy_wikis() -> Result<Vec<String>, String> {
+    let Some(legacy_dir) = Wiki::get_legacy_storage_dir() else {
+        return Ok(Vec::new());
+    };
+
+    let current_dir = Wiki::get_wiki_storage_dir()
+        .map_err(|e| format!("无法打开所有知识库的统一存储目录: {}", e))?;
+    Wiki::migrate_storage_dir(&legacy_dir, &current_dir)
+        .map_err(|e| format!("迁移知识库失败: {}", e))
+}
+
+/// 将已有的 Git 仓库注册为知识库
+///
+/// 仓库会保留在原来的位置，不会被移动到统一存储目录。
+///
+/// # 参数
+/// * `path` - 仓库的绝对路径
+/// * `wiki_name` - 知识库名称，为 `None` 时使用仓库文件夹的名称
+///
+/// # 返回值
+/// * `Result<Wiki, String>` - 成功时返回注册的知识库信息
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn register_wiki(path: String, wiki_name: Option<String>) -> Result<Wiki, String> {
+    Wiki::register(Path::new(&path), wiki_name.as_deref())
+        .map_err(|e| format!("注册知识库失败: {}", e))
+}
+
+/// 将已有的普通文件夹初始化为知识库
+///
+/// 该函数会在文件夹中初始化 Git 仓库，并将其注册为知识库，文件夹中的已有文件保持不变。
+///
+/// # 参数
+/// * `path` - 文件夹的绝对路径
+/// * `wiki_name` - 知识库名称，为 `None` 时使用文件夹的名称
+/// * `username` - Git用户名
+/// * `email` - Git邮箱
+///
+/// # 返回值
+/// * `Result<Wiki, String>` - 成功时返回注册的知识库信息
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn init_wiki_at_path(
+    path: String,
+    wiki_name: Option<String>,
+    username: String,
+    email: String,
+) -> Result<Wiki, String> {
+    Wiki::init_external(Path::new(&path), wiki_name.as_deref(), &username, &email)
+        .map_err(|e| format!("初始化知识库失败: {}", e))
+}
+
+/// 取消注册外部知识库
+///
+/// 只会移除注册信息，不会删除文件夹中的任何内容。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<(), String>` - 成功时返回 `Ok(())`
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn unregister_wiki(wiki_name: String) -> Result<(), String> {
+    Wiki::unregister(&wiki_name).map_err(|e| format!("取消注册知识库失败: {}", e))
+}
+
+/// 获取知识库元数据
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<WikiMeta, String>` - 成功时返回知识库元数据，没有元数据文件时返回默认值
+/// * 失败时返回具体错误信息（如元数据文件格式错误）
+#[tauri::command]
+pub async fn get_wiki_meta(wiki_name: String) -> Result<WikiMeta, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    WikiMeta::load(Path::new(&wiki.path)).map_err(|e| format!("读取知识库元数据失败: {}", e))
+}
+
+/// 更新知识库元数据
+///
+/// 元数据保存在知识库内的 `.markwiki/wiki.toml` 文件中，会随知识库一起提交和同步。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `meta` - 新的元数据
+///
+/// # 返回值
+/// * `Result<(), String>` - 成功时返回 `Ok(())`
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn update_wiki_meta(wiki_name: String, meta: WikiMeta) -> Result<(), String> {
+    let mut wiki = service::open_wiki(&wiki_name)?;
+
+    wiki.update_meta(meta)
+        .map_err(|e| format!("保存知识库元数据失败: {}", e))
+}
+
+/// 自动保存页面的草稿
+///
+/// 编辑器中有未保存的修改时定期调用，草稿保存在工作区之外，页面保存后会被删除。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `file_path` - 页面路径，相对于知识库根目录
+/// * `content` - 编辑器中未保存的内容
+/// * `version` - 读取页面时得到的版本标识
+///
+/// # 返回值
+/// * `Result<(), String>` - 成功时返回 `Ok(())`
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn save_draft(
+    wiki_name: String,
+    file_path: String,
+    content: String,
+    version: Option<String>,
+) -> Result<(), String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let drafts = Drafts::for_wiki(&wiki).map_err(|e| format!("无法打开草稿区: {}", e))?;
+    drafts
+        .save(&file_path, &content, version.as_deref())
+        .map(|_| ())
+        .map_err(|e| format!("保存草稿失败: {}", e))
+}
+
+/// 列出知识库中可恢复的草稿
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<Vec<DraftInfo>, String>` - 成功时返回按保存时间倒序排列的草稿列表，包含草稿的时间和与页面的差异
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn list_drafts(wiki_name: String) -> Result<Vec<DraftInfo>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let drafts = Drafts::for_wiki(&wiki).map_err(|e| format!("无法打开草稿区: {}", e))?;
+    drafts.list().map_err(|e| format!("读取草稿失败: {}", e))
+}
+
+/// 用草稿内容覆盖页面
+///
+/// 页面在列出草稿之后被修改时拒绝恢复，需要重新查看草稿与页面的差异。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `file_path` - 页面路径，相对于知识库根目录
+/// * `page_version` - 列出草稿时页面的版本标识（`DraftInfo.page_version`），页面当时不存在时为 `None`
+///
+/// # 返回值
+/// * `Result<FileContent, String>` - 成功时返回恢复后的页面内容和版本信息
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn restore_draft(
+    wiki_name: String,
+    file_path: String,
+    page_version: Option<String>,
+) -> Result<FileContent, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let drafts = Drafts::for_wiki(&wiki).map_err(|e| format!("无法打开草稿区: {}", e))?;
+    drafts
+        .restore(&file_path, page_version.as_deref())
+        .map_err(|e| match e {
+            crate::draft::Error::Wiki(crate::wiki::Error::Conflict { .. }) => {
+                "页面在此之后已被修改，请重新查看草稿后再恢复".to_string()
+            }
+            e => format!("恢复草稿失败: {}", e),
+        })
+}
+
+/// 丢弃页面的草稿
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `file_path` - 页面路径，相对于知识库根目录
+///
+/// # 返回值
+/// * `Result<(), String>` - 成功时返回 `Ok(())`
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn discard_draft(wiki_name: String, file_path: String) -> Result<(), String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let drafts = Drafts::for_wiki(&wiki).map_err(|e| format!("无法打开草稿区: {}", e))?;
+    drafts
+        .discard(&file_path)
+        .map_err(|e| format!("丢弃草稿失败: {}", e))
+}
+
+/// 从文件导入附件
+///
+/// 将图片、PDF 等文件复制到页面资源文件夹或知识库资源文件夹中，同名文件已存在时自动添加序号。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `page_path` - 插入附件的页面路径，相对于知识库根目录
+/// * `source_path` - 要导入的文件的完整路径
+/// * `location` - 附件的保存位置，默认为页面资源文件夹
+///
+/// # 返回值
+/// * `Result<Attachment, String>` - 成功时返回附件路径和可以插入页面的 Markdown 链接
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn import_attachment(
+    wiki_name: String,
+    page_path: String,
+    source_path: String,
+    location: Option<AssetLocation>,
+) -> Result<Attachment, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    Attachments::for_wiki(&wiki)
+        .import_file(
+            &page_path,
+            Path::new(&source_path),
+            location.unwrap_or_default(),
+        )
+        .map_err(|e| format!("导入附件失败: {}", e))
+}
+
+/// 从数据导入附件
+///
+/// 用于导入从剪贴板粘贴或拖放到编辑器中的图片等数据。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `page_path` - 插入附件的页面路径，相对于知识库根目录
+/// * `file_name` - 附件的文件名
+/// * `data` - 附件内容
+/// * `location` - 附件的保存位置，默认为页面资源文件夹
+///
+/// # 返回值
+/// * `Result<Attachment, String>` - 成功时返回附件路径和可以插入页面的 Markdown 链接
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn import_attachment_bytes(
+    wiki_name: String,
+    page_path: String,
+    file_name: String,
+    data: Vec<u8>,
+    location: Option<AssetLocation>,
+) -> Result<Attachment, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    Attachments::for_wiki(&wiki)
+        .import_bytes(&page_path, &file_name, &data, location.unwrap_or_default())
+        .map_err(|e| format!("导入附件失败: {}", e))
+}
+
+/// 读取附件内容
+///
+/// 附件内容以二进制形式返回，前端可以据此创建 Blob 用于预览。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `path` - 附件路径，相对于知识库根目录
+///
+/// # 返回值
+/// * `Result<tauri::ipc::Response, String>` - 成功时返回附件的原始内容
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn read_attachment(
+    wiki_name: String,
+    path: String,
+) -> Result<tauri::ipc::Response, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    Attachments::for_wiki(&wiki)
+        .read(&path)
+        .map(tauri::ipc::Response::new)
+        .map_err(|e| format!("读取附件失败: {}", e))
+}
+
+/// 检查知识库中的附件
+///
+/// 找出没有被任何页面引用的附件，以及大小超过阈值的附件。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `size_threshold` - 大附件的阈值（字节）；为 `None` 时使用知识库设置
+///   `attachment_size_threshold`，未设置时为 5 MiB
+///
+/// # 返回值
+/// * `Result<AttachmentReport, String>` - 成功时返回检查报告
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn get_attachment_report(
+    wiki_name: String,
+    size_threshold: Option<u64>,
+) -> Result<AttachmentReport, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let size_threshold = size_threshold
+        .or_else(|| {
+            wiki.meta
+                .settings
+                .get(crate::attachment::SIZE_THRESHOLD_SETTING)
+                .and_then(|v| v.as_integer())
+                .and_then(|v| u64::try_from(v).ok())
+        })
+        .unwrap_or(crate::attachment::DEFAULT_SIZE_THRESHOLD);
+
+    Attachments::for_wiki(&wiki)
+        .report(size_threshold)
+        .map_err(|e| format!("检查附件失败: {}", e))
+}
+
+/// 删除知识库中所有未被引用的附件
+///
+/// 附件会被移动到知识库的回收站，可以通过 `restore_item` 恢复。
+/// 删除会作为一次单独的提交记录下来，工作区中的其他修改不会被一起提交。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<Vec<String>, String>` - 成功时返回被删除的附件路径
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn delete_orphan_attachments(wiki_name: String) -> Result<Vec<String>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    Attachments::for_wiki(&wiki)
+        .delete_orphans("删除未被引用的附件")
+        .map_err(|e| format!("删除未被引用的附件失败: {}", e))
+}
+
+/// 下载知识库中尚未下载的 Git LFS 内容
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<Vec<String>, String>` - 成功时返回内容被下载到工作区的文件路径
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn lfs_fetch(wiki_name: String) -> Result<Vec<String>, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let repo = Repository::open(&PathBuf::from(&wiki.path))
+        .map_err(|e| format!("无法打开 Git 仓库: {}", e))?;
+    let client = LfsClient::for_repository(&repo)
+        .map_err(|e| format!("读取 Git LFS 配置失败: {}", e))?
+        .ok_or("无法确定 Git LFS 服务器地址，请配置 HTTP 远程仓库或 lfs.url")?;
+    client
+        .download(&repo)
+        .map_err(|e| format!("下载 Git LFS 内容失败: {}", e))
+}
+
+/// 将本地的 Git LFS 内容上传到服务器
+///
+/// 同步时会自动上传，该命令用于单独上传。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<usize, String>` - 成功时返回实际上传的对象数量
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn lfs_push(wiki_name: String) -> Result<usize, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let repo = Repository::open(&PathBuf::from(&wiki.path))
+        .map_err(|e| format!("无法打开 Git 仓库: {}", e))?;
+    let client = LfsClient::for_repository(&repo)
+        .map_err(|e| format!("读取 Git LFS 配置失败: {}", e))?
+        .ok_or("无法确定 Git LFS 服务器地址，请配置 HTTP 远程仓库或 lfs.url")?;
+    client
+        .upload(&repo)
+        .map_err(|e| format!("上传 Git LFS 内容失败: {}", e))
+}
+
+/// 开始监视知识库的文件变化
+///
+/// 知识库中的文件被外部修改时，会向界面推送 `file-created`、`file-modified`、
+/// `file-deleted` 和 `file-renamed` 事件，事件中的路径为知识库相对路径。
+/// 同步结束后会推送 `file-tree-changed` 事件。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<(), String>` - 成功时返回 `Ok(())`
+/// * 失败时返回 `Err(String)`，包含具体错误信息
+#[tauri::command]
+pub async fn watch_wiki(
+    app: tauri::AppHandle,
+    watchers: tauri::State<'_, WatcherRegistry>,
+    wiki_name: String,
+) -> Result<(), String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    watchers
+        .watch(
+            &wiki_name,
+            Path::new(&wiki.path),
+            &wiki.meta.ignore,
+            move |event| emit_watch_event(&app, event),
+        )
+        .map_err(|e| format!("无法监视知识库 {}: {}", wiki_name, e))
+}
+
+/// 停止监视知识库的文件变化
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<bool, String>` - 成功时返回知识库之前是否处于监视状态
+#[tauri::command]
+pub async fn unwatch_wiki(
+    watchers: tauri::State<'_, WatcherRegistry>,
+    wiki_name: String,
+) -> Result<bool, String> {
+    Ok(watchers.unwatch(&wiki_name))
+}
+
+/// 向界面推送文件变化事件
+fn emit_watch_event(app: &tauri::AppHandle, event: WatchEvent) {
+    use tauri::Emitter;
+
+    if let Err(e) = app.emit(event.event_name(), &event) {
+        eprintln!("推送文件变化事件失败: {}", e);
+    }
+}
+
+/// 将知识库导出为静态网站
+///
+/// 每个页面渲染为 HTML 并带有导航侧边栏，页面链接指向导出的 `.html` 文件，附件按原路径复制，
+/// 同时生成首页和客户端搜索索引。导出目录中已有的同名文件会被覆盖。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `output_dir` - 导出目录，不能位于知识库内部
+///
+/// # 返回值
+/// * `Result<SiteReport, String>` - 成功时返回导出的页面和附件数量
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn export_site(wiki_name: String, output_dir: String) -> Result<SiteReport, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let tree = wiki
+        .file_tree("", None)
+        .map_err(|e| format!("读取文件树失败: {}", e))?;
+    let title = wiki.meta.display_name.as_deref().unwrap_or(&wiki_name);
+
+    crate::export::site::export_site(
+        &PathBuf::from(&wiki.path),
+        &tree,
+        title,
+        &PathBuf::from(&output_dir),
+    )
+    .map_err(|e| format!("导出静态网站失败: {}", e))
+}
+
+/// 将一个页面或文件夹导出为单个文档
+///
+/// 文件夹中的页面按文件树顺序合并。HTML 格式会内嵌样式和图片，可以直接发送给团队以外的人；
+/// PDF 格式不依赖浏览器引擎，图片以文字占位。导出的文件不能位于知识库内部。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `path` - 要导出的页面或文件夹，相对于知识库根目录，空字符串表示整个知识库
+/// * `output_path` - 导出的文件路径
+/// * `format` - 导出格式，默认为 HTML
+/// * `font_path` - 导出 PDF 时使用的 TrueType 字体，为 `None` 时自动选择系统字体
+///
+/// # 返回值
+/// * `Result<DocumentReport, String>` - 成功时返回导出结果，包括无法内嵌的图片和字体的选择情况
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn export_document(
+    wiki_name: String,
+    path: String,
+    output_path: String,
+    format: Option<DocumentFormat>,
+    font_path: Option<String>,
+) -> Result<DocumentReport, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+    let root = PathBuf::from(&wiki.path);
+
+    let node = wiki
+        .file_tree(&path, None)
+        .map_err(|e| format!("读取文件树失败: {}", e))?;
+    let pages = crate::export::document::collect_pages(&root, &node)
+        .map_err(|e| format!("读取页面失败: {}", e))?;
+
+    // 单个页面使用页面标题，文件夹使用文件夹名称
+    let title = if node.is_directory() {
+        if node.path().is_empty() {
+            wiki.meta
+                .display_name
+                .clone()
+                .unwrap_or_else(|| wiki_name.clone())
+        } else {
+            node.name().to_string()
+        }
+    } else {
+        Page::parse(&pages[0].content)
+            .map(|page| page.metadata(node.name()).title)
+            .unwrap_or_else(|_| node.name().to_string())
+    };
+
+    let output = PathBuf::from(&output_path);
+    let output_dir = output
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(std::path::Path::new("."));
+    crate::export::ensure_outside(&root, output_dir)
+        .map_err(|e| format!("导出位置不可用，不能导出到知识库内部: {}", e))?;
+
+    let result = match format.unwrap_or_default() {
+        DocumentFormat::Html => {
+            let tree = wiki
+                .file_tree("", None)
+                .map_err(|e| format!("读取文件树失败: {}", e))?;
+            let mut files = Vec::new();
+            crate::wiki::collect_files(&tree, &mut files);
+            let assets: Vec<&str> = files
+                .iter()
+                .map(|file| file.path())
+                .filter(|path| !crate::wiki::is_page(path))
+                .collect();
+            crate::export::document::export_html(&root, &title, &pages, &assets, &output)
+        }
+        DocumentFormat::Pdf => crate::export::pdf::export_pdf(
+            &title,
+            &pages,
+            font_path.as_deref().map(std::path::Path::new),
+            &output,
+        ),
+    };
+    result.map_err(|e| format!("导出文档失败: {}", e))
+}
+
+/// 从其他工具导入笔记，创建新的本地知识库
+///
+/// 支持 Obsidian 仓库、Notion 导出的 Markdown 和普通的 Markdown 文件夹。
+/// 文件名和链接会被规范化，附件会被复制，导入的内容作为知识库的第一次提交。
+/// 导入失败时会删除已创建的知识库目录。
+///
+/// # 参数
+/// * `wiki_name` - 要创建的知识库名称
+/// * `source_path` - 导入来源文件夹
+/// * `source_type` - 导入来源类型，默认为普通文件夹
+/// * `username` - Git用户名
+/// * `email` - Git邮箱
+///
+/// # 返回值
+/// * `Result<ImportReport, String>` - 成功时返回导入结果，包括被重命名的文件和无法解析的链接
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn import_wiki(
+    wiki_name: String,
+    source_path: String,
+    source_type: Option<ImportSource>,
+    username: String,
+    email: String,
+) -> Result<ImportReport, String> {
+    if Wiki::exists(&wiki_name) {
+        return Err(format!("知识库 {} 已存在", wiki_name));
+    }
+
+    let wiki = Wiki::create_local_wiki(&wiki_name, &username, &email)
+        .map_err(|e| format!("创建本地知识库失败: {}", e))?;
+    let root = PathBuf::from(&wiki.path);
+    let source_type = source_type.unwrap_or_default();
+
+    let result = crate::importer::import_folder(&PathBuf::from(&source_path), source_type, &root)
+        .map_err(|e| format!("导入失败: {}", e))
+        .and_then(|report| {
+            let mut repo =
+                Repository::open(&root).map_err(|e| format!("无法打开 Git 仓库: {}", e))?;
+            repo.add_all()
+                .map_err(|e| format!("添加文件到暂存区失败: {}", e))?;
+            repo.commit(&format!("从 {} 导入", source_path))
+                .map_err(|e| format!("提交失败: {}", e))?;
+            Ok(report)
+        });
+
+    if result.is_err() {
+        if let Err(e) = std::fs::remove_dir_all(&root) {
+            eprintln!("清理知识库目录失败 {}: {}", root.display(), e);
+        }
+    }
+    result
+}
+
+/// 将知识库导出为便于离线交接的归档
+///
+/// 归档包含工作区的所有文件，可以选择同时包含完整的 `.git` 目录或当前分支历史的 Git bundle。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `output_path` - 归档文件的保存路径，扩展名为 `.zip` 时导出 zip 归档，否则导出 `.tar.gz` 归档
+/// * `history` - 归档中包含的 Git 历史，默认只包含工作区文件
+///
+/// # 返回值
+/// * `Result<String, String>` - 成功时返回归档文件路径
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn export_wiki_archive(
+    wiki_name: String,
+    output_path: String,
+    history: Option<ArchiveHistory>,
+) -> Result<String, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+    let output = PathBuf::from(&output_path);
+    wiki.export_archive(&output, history.unwrap_or_default())
+        .map_err(|e| format!("导出归档失败: {}", e))?;
+    Ok(output.display().to_string())
+}
+
+/// 从归档中恢复知识库
+///
+/// 归档中的所有路径都会被校验，包含绝对路径、`..` 或符号链接的归档会被拒绝。
+/// 归档中有 `.git` 目录或 Git bundle 时保留原有历史，否则把所有文件作为第一次提交。
+///
+/// # 参数
+/// * `archive_path` - 归档文件路径，支持 `.zip` 与 `.tar.gz`
+/// * `wiki_name` - 知识库名称，为空时使用归档的顶层目录名称
+/// * `username` - Git用户名
+/// * `email` - Git邮箱
+///
+/// # 返回值
+/// * `Result<Wiki, String>` - 成功时返回恢复的知识库
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn import_wiki_archive(
+    archive_path: String,
+    wiki_name: Option<String>,
+    username: String,
+    email: String,
+) -> Result<Wiki, String> {
+    if let Some(name) = &wiki_name {
+        if Wiki::exists(name) {
+            return Err(format!("知识库 {} 已存在", name));
+        }
+    }
+
+    Wiki::import_archive(
+        &PathBuf::from(&archive_path),
+        wiki_name.as_deref(),
+        &username,
+        &email,
+    )
+    .map_err(|e| format!("导入归档失败: {}", e))
+}
+
+/// 启动只读 HTTP 服务，在局域网内分享知识库
+///
+/// 服务提供渲染后的页面、页面的 Markdown 原文和附件，不会修改知识库。
+/// 如果该知识库已经有运行中的服务，会先停止旧的服务。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `bind_address` - 监听地址，例如 `0.0.0.0:8080`；为空时使用上次的设置，默认只允许本机访问。
+///   指定的地址会保存到应用配置中
+/// * `token` - 访问令牌，为空时不需要令牌
+///
+/// # 返回值
+/// * `Result<ServerInfo, String>` - 成功时返回服务信息，包括实际监听的地址
+/// * 失败时返回具体错误信息
+#[tauri::command]
+pub async fn start_wiki_server(
+    servers: tauri::State<'_, ServerRegistry>,
+    wiki_name: String,
+    bind_address: Option<String>,
+    token: Option<String>,
+) -> Result<ServerInfo, String> {
+    let wiki = service::open_wiki(&wiki_name)?;
+
+    let mut config = AppConfig::load_or_default().map_err(|e| format!("读取配置失败: {}", e))?;
+    let bind_address = match bind_address.filter(|a| !a.trim().is_empty()) {
+        Some(address) => {
+            config.server_bind_address = Some(address.clone());
+            config.save().map_err(|e| format!("保存配置失败: {}", e))?;
+            address
+        }
+        None => config
+            .server_bind_address
+            .unwrap_or_else(|| DEFAULT_BIND_ADDRESS.to_string()),
+    };
+
+    let options = ServerOptions {
+        bind_address,
+        token: token.filter(|t| !t.is_empty()),
+        title: wiki
+            .meta
+            .display_name
+            .clone()
+            .unwrap_or_else(|| wiki_name.clone()),
+        ignore_rules: wiki.meta.ignore.clone(),
+    };
+    servers
+        .start(&wiki_name, Path::new(&wiki.path), options)
+        .map_err(|e| format!("启动服务失败: {}", e))
+}
+
+/// 停止知识库的只读 HTTP 服务
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+///
+/// # 返回值
+/// * `Result<bool, String>` - 成功时返回该知识库之前是否有运行中的服务
+#[tauri::command]
+pub async fn stop_wiki_server(
+    servers: tauri::State<'_, ServerRegistry>,
+    wiki_name: String,
+) -> Result<bool, String> {
+    Ok(servers.stop(&wiki_name))
+}
+
+/// 列出所有运行中的只读 HTTP 服务
+///
+/// # 返回值
+/// * `Result<Vec<ServerInfo>, String>` - 运行中的服务信息
+#[tauri::command]
+pub async fn list_wiki_servers(
+    servers: tauri::State<'_, ServerRegistry>,
+) -> Result<Vec<ServerInfo>, String> {
+    Ok(servers.list())
+}
@@ -1,12 +1,22 @@
 //! 配置管理模块
 //!
-//! 该模块负责处理应用程序的配置，包括保存和读取用户的Git凭据。
+//! 该模块负责处理应用程序的配置，包括保存和读取用户的Git凭据以及知识库的存储位置。
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+use std::sync::OnceLock;
 use thiserror::Error;
 
+/// 应用标识，与 `tauri.conf.json` 中的 `identifier` 保持一致
+const APP_IDENTIFIER: &str = "com.markwiki.app";
+
+/// 用于覆盖应用数据目录的环境变量
+const DATA_DIR_ENV: &str = "MARKWIKI_DATA_DIR";
+
+/// 由 Tauri 在启动时提供的平台应用数据目录
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
 /// 配置操作可能出现的错误类型
 #[derive(Error, Debug)]
 pub enum Error {
@@ -19,69 +29,118 @@ pub enum Error {
     /// 配置文件不存在错误
     #[error("Config file not found")]
     ConfigNotFound,
+    /// 无法确定应用数据目录
+    #[error("Failed to determine application data directory")]
+    DataDir,
 }
 
 /// Git 凭据配置
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct GitCredentials {
     pub username: String,
     pub email: String,
     pub password: Option<String>,
 }
 
+/// 注册的外部知识库
+///
+/// # 字段
+/// * `name` - 知识库名称
+/// * `path` - 知识库所在文件夹的绝对路径
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredWiki {
+    pub name: String,
+    pub path: String,
+}
+
 /// 应用程序配置
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// # 字段
+/// * `git_credentials` - 同步时使用的 Git 凭据
+/// * `wiki_storage_dir` - 自定义的知识库存储目录，为 `None` 时使用应用数据目录下的 `wiki` 目录
+/// * `registered_wikis` - 注册的外部知识库，这些知识库位于统一存储目录之外
+/// * `server_bind_address` - 只读 HTTP 服务上次使用的监听地址，为 `None` 时只监听本机
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AppConfig {
+    #[serde(default)]
     pub git_credentials: GitCredentials,
+    #[serde(default)]
+    pub wiki_storage_dir: Option<String>,
+    #[serde(default)]
+    pub registered_wikis: Vec<RegisteredWiki>,
+    #[serde(default)]
+    pub server_bind_address: Option<String>,
+}
+
+/// 设置平台应用数据目录
+///
+/// 该函数应在应用启动时调用一次，传入 Tauri 解析出的应用数据目录。
+/// 重复调用时保留第一次设置的值。
+pub fn set_data_dir(path: PathBuf) {
+    let _ = DATA_DIR.set(path);
+}
+
+/// 获取应用数据目录
+///
+/// 应用数据目录用于保存配置文件、默认的知识库存储目录和归档等数据。
+/// 如果目录不存在，会自动创建该目录。
+///
+/// # 返回值
+/// * `Result<PathBuf, Error>` - 成功时返回 `Ok(PathBuf)`，包含应用数据目录
+/// * 失败时返回 `Err(Error)`
+///
+/// # 查找顺序
+/// * 环境变量 `MARKWIKI_DATA_DIR` 指定的目录
+/// * 启动时由 Tauri 提供的平台应用数据目录（Android 上为应用私有的数据目录）
+/// * 系统数据目录下以应用标识命名的目录，例如 Linux 下的 `~/.local/share/com.markwiki.app`
+pub fn get_data_dir() -> Result<PathBuf, Error> {
+    let data_dir = if let Some(dir) = std::env::var_os(DATA_DIR_ENV).filter(|d| !d.is_empty()) {
+        PathBuf::from(dir)
+    } else if let Some(dir) = DATA_DIR.get() {
+        dir.clone()
+    } else {
+        dirs::data_dir().ok_or(Error::DataDir)?.join(APP_IDENTIFIER)
+    };
+
+    // 确保数据目录存在
+    if !data_dir.exists() {
+        fs::create_dir_all(&data_dir)?;
+    }
+    Ok(data_dir)
 }
 
 impl AppConfig {
     /// 获取配置文件路径
     pub fn get_config_path() -> Result<PathBuf, Error> {
-        // Android 平台
-        #[cfg(target_os = "android")]
-        {
-            use crate::wiki::Wiki;
-
-            let path = Wiki::get_wiki_storage_dir()
-                .map_err(|_| {
-                    Error::Io(std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "无法获取存储目录",
-                    ))
-                })?
-                .join("config.json");
-            Ok(path)
-        }
-
-        // Linux/Windows 平台
-        #[cfg(not(target_os = "android"))]
-        {
-            use crate::wiki::Wiki;
-
-            let path = Wiki::get_wiki_storage_dir()
-                .map_err(|_| {
-                    Error::Io(std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        "无法获取存储目录",
-                    ))
-                })?
-                .join("config.json");
-            Ok(path)
-        }
+        Ok(get_data_dir()?.join("config.json"))
     }
 
     /// 从文件加载配置
+    ///
+    /// 如果配置文件不存在，会尝试读取旧版本保存在可执行文件旁知识库目录下的配置文件，
+    /// 下次保存时配置会写入新的位置。
     pub fn load() -> Result<Self, Error> {
-        let config_path = Self::get_config_path()?;
+        let mut config_path = Self::get_config_path()?;
         if !config_path.exists() {
-            return Err(Error::ConfigNotFound);
+            config_path = crate::wiki::Wiki::get_legacy_storage_dir()
+                .map(|dir| dir.join("config.json"))
+                .filter(|path| path.exists())
+                .ok_or(Error::ConfigNotFound)?;
         }
 
         let config_content = fs::read_to_string(config_path)?;
         Ok(serde_json::from_str(&config_content)?)
     }
 
+    /// 从文件加载配置，配置文件不存在时返回默认配置
+    pub fn load_or_default() -> Result<Self, Error> {
+        match Self::load() {
+            Ok(config) => Ok(config),
+            Err(Error::ConfigNotFound) => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
     /// 将配置保存到文件
     pub fn save(&self) -> Result<(), Error> {
         let config_path = Self::get_config_path()?;
@@ -90,14 +149,57 @@ impl AppConfig {
         Ok(())
     }
 
-    /// 创建新的配置实例
-    pub fn new(username: &str, email: &str, password: Option<&str>) -> Self {
-        Self {
-            git_credentials: GitCredentials {
-                username: username.to_string(),
-                email: email.to_string(),
-                password: password.map(|p| p.to_string()),
-            },
+    /// 设置 Git 凭据
+    pub fn set_git_credentials(&mut self, username: &str, email: &str, password: Option<&str>) {
+        self.git_credentials = GitCredentials {
+            username: username.to_string(),
+            email: email.to_string(),
+            password: password.map(|p| p.to_string()),
+        };
+    }
+}
+
+/// 将应用数据目录指向新的临时目录，供需要读写配置和知识库存储目录的测试使用
+///
+/// 环境变量对整个测试进程生效，返回的守卫持有一把锁，保证同一时间只有一个测试使用数据目录，
+/// 调用方应在测试结束前一直持有它。守卫被丢弃时恢复环境变量原来的值，再删除临时目录。
+#[cfg(test)]
+pub(crate) fn test_data_dir() -> TestDataDir {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dir = tempfile::TempDir::new().unwrap();
+    let previous = std::env::var_os(DATA_DIR_ENV);
+    std::env::set_var(DATA_DIR_ENV, dir.path());
+    TestDataDir {
+        previous,
+        dir,
+        _lock: lock,
+    }
+}
+
+/// 测试使用的临时数据目录，由 [`test_data_dir`] 创建
+#[cfg(test)]
+pub(crate) struct TestDataDir {
+    previous: Option<std::ffi::OsString>,
+    dir: tempfile::TempDir,
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+impl TestDataDir {
+    /// 获取临时数据目录的路径
+    pub(crate) fn path(&self) -> &std::path::Path {
+        self.dir.path()
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDataDir {
+    fn drop(&mut self) {
+        // 字段在此之后按声明顺序丢弃：先删除临时目录，最后释放锁
+        match self.previous.take() {
+            Some(value) => std::env::set_var(DATA_DIR_ENV, value),
+            None => std::env::remove_var(DATA_DIR_ENV),
         }
     }
 }
@@ -0,0 +1,361 @@
+//! 草稿自动保存模块
+//!
+//! 此模块在编辑器中有未保存的修改时，定期把编辑内容保存为草稿，用于应用崩溃或意外退出后恢复。
+//! 草稿保存在知识库 Git 目录下的草稿区（`.git/markwiki/drafts`），不会出现在工作区和版本管理中。
+//! 页面保存成功后对应的草稿会被删除。
+
+use crate::wiki::content::{self, FileContent};
+use crate::wiki::{self, Wiki};
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// 草稿操作可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON 序列化/反序列化错误
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// 知识库操作错误
+    #[error("Wiki error: {0}")]
+    Wiki(#[from] wiki::Error),
+
+    /// 页面没有草稿
+    #[error("Draft not found: {0}")]
+    NotFound(String),
+}
+
+/// 保存在草稿区中的草稿
+///
+/// # 字段
+/// * `path` - 页面相对于知识库根目录的路径
+/// * `content` - 编辑器中未保存的内容
+/// * `base_version` - 开始编辑时页面的版本标识，页面是新建的时为 `None`
+/// * `saved_at` - 草稿保存时间（Unix 时间戳，单位为秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Draft {
+    pub path: String,
+    pub content: String,
+    pub base_version: Option<String>,
+    pub saved_at: u64,
+}
+
+/// 可恢复的草稿信息
+///
+/// # 字段
+/// * `path` - 页面相对于知识库根目录的路径
+/// * `saved_at` - 草稿保存时间（Unix 时间戳，单位为秒）
+/// * `age` - 草稿距今的时间，单位为秒
+/// * `page_exists` - 页面是否仍然存在
+/// * `page_changed` - 草稿保存后页面是否被修改过（如同步），此时恢复会覆盖这些修改
+/// * `page_version` - 列出草稿时磁盘上页面的版本标识，页面不存在时为 `None`，恢复草稿时传回
+/// * `diff` - 从磁盘上的页面到草稿的统一格式差异
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftInfo {
+    pub path: String,
+    pub saved_at: u64,
+    pub age: u64,
+    pub page_exists: bool,
+    pub page_changed: bool,
+    pub page_version: Option<String>,
+    pub diff: String,
+}
+
+/// 知识库草稿区
+pub struct Drafts {
+    /// 知识库根目录
+    root: PathBuf,
+    /// 草稿目录
+    dir: PathBuf,
+}
+
+impl Drafts {
+    /// 创建草稿区实例
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    /// * `dir` - 草稿目录，不存在时会在首次保存草稿时创建
+    pub fn new(root: &Path, dir: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// 获取指定知识库的草稿区
+    ///
+    /// # 参数
+    /// * `wiki` - 知识库实例
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回 `Ok(Drafts)`
+    /// * 失败时返回 `Err(Error)`，表示无法获取知识库的状态目录
+    pub fn for_wiki(wiki: &Wiki) -> Result<Self, Error> {
+        Ok(Self::new(
+            Path::new(&wiki.path),
+            &wiki.state_dir()?.join("drafts"),
+        ))
+    }
+
+    /// 保存页面的草稿，覆盖该页面之前的草稿
+    ///
+    /// # 参数
+    /// * `relative` - 页面相对于知识库根目录的路径
+    /// * `content` - 编辑器中未保存的内容
+    /// * `base_version` - 开始编辑时页面的版本标识
+    ///
+    /// # 返回值
+    /// * `Result<Draft, Error>` - 成功时返回保存的草稿
+    /// * 失败时返回 `Err(Error)`
+    pub fn save(
+        &self,
+        relative: &str,
+        content: &str,
+        base_version: Option<&str>,
+    ) -> Result<Draft, Error> {
+        let relative = normalize(relative)?;
+        let draft = Draft {
+            path: relative.clone(),
+            content: content.to_string(),
+            base_version: base_version.map(|v| v.to_string()),
+            saved_at: now(),
+        };
+
+        content::write_atomic(
+            &self.draft_file(&relative),
+            serde_json::to_string(&draft)?.as_bytes(),
+        )?;
+        Ok(draft)
+    }
+
+    /// 获取页面的草稿
+    ///
+    /// # 参数
+    /// * `relative` - 页面相对于知识库根目录的路径
+    ///
+    /// # 返回值
+    /// * `Result<Option<Draft>, Error>` - 成功时返回草稿，页面没有草稿时返回 `None`
+    /// * 失败时返回 `Err(Error)`
+    pub fn get(&self, relative: &str) -> Result<Option<Draft>, Error> {
+        let relative = normalize(relative)?;
+        let draft_file = self.draft_file(&relative);
+        if !draft_file.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(
+            draft_file,
+        )?)?))
+    }
+
+    /// 列出所有可恢复的草稿
+    ///
+    /// 与磁盘上的页面内容相同的草稿没有恢复的意义，不会被列出。
+    ///
+    /// # 返回值
+    /// * `Result<Vec<DraftInfo>, Error>` - 成功时返回按保存时间倒序排列的草稿列表
+    /// * 失败时返回 `Err(Error)`
+    pub fn list(&self) -> Result<Vec<DraftInfo>, Error> {
+        let mut drafts = Vec::new();
+        if !self.dir.exists() {
+            return Ok(drafts);
+        }
+
+        let now = now();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            // 跳过损坏的草稿
+            let Ok(text) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(draft) = serde_json::from_str::<Draft>(&text) else {
+                continue;
+            };
+
+            let page = content::read_text(&self.root.join(&draft.path)).ok();
+            if page.as_ref().map(|p| p.content == draft.content) == Some(true) {
+                continue;
+            }
+
+            let disk_content = page.as_ref().map(|p| p.content.as_str()).unwrap_or("");
+            let diff = TextDiff::from_lines(disk_content, draft.content.as_str())
+                .unified_diff()
+                .header(&draft.path, &format!("{} (草稿)", draft.path))
+                .to_string();
+
+            let page_version = page.map(|p| p.version);
+            drafts.push(DraftInfo {
+                page_exists: page_version.is_some(),
+                page_changed: page_version != draft.base_version,
+                page_version,
+                age: now.saturating_sub(draft.saved_at),
+                saved_at: draft.saved_at,
+                path: draft.path,
+                diff,
+            });
+        }
+
+        drafts.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then(a.path.cmp(&b.path)));
+        Ok(drafts)
+    }
+
+    /// 用草稿内容覆盖页面，并删除草稿
+    ///
+    /// 只有页面仍是列出草稿时展示给用户的版本才会被覆盖，
+    /// 在此之后被同步或外部编辑器修改时返回冲突，草稿保持不变。
+    ///
+    /// # 参数
+    /// * `relative` - 页面相对于知识库根目录的路径
+    /// * `page_version` - 列出草稿时页面的版本标识（`DraftInfo.page_version`），`None` 表示页面当时不存在
+    ///
+    /// # 返回值
+    /// * `Result<FileContent, Error>` - 成功时返回恢复后的页面内容和版本信息
+    /// * 失败时返回 `Err(Error)`，如页面没有草稿，或页面已被修改时返回 `wiki::Error::Conflict`
+    pub fn restore(
+        &self,
+        relative: &str,
+        page_version: Option<&str>,
+    ) -> Result<FileContent, Error> {
+        let draft = self
+            .get(relative)?
+            .ok_or_else(|| Error::NotFound(relative.to_string()))?;
+
+        let path = self.root.join(&draft.path);
+        if page_version.is_none() && path.exists() {
+            return Err(wiki::Error::Conflict {
+                path: path.display().to_string(),
+                current: content::read_text(&path).ok(),
+            }
+            .into());
+        }
+        let restored = content::write_text(&path, &draft.content, page_version)?;
+        self.discard(&draft.path)?;
+        Ok(restored)
+    }
+
+    /// 删除页面的草稿，页面没有草稿时不做任何操作
+    ///
+    /// # 参数
+    /// * `relative` - 页面相对于知识库根目录的路径
+    pub fn discard(&self, relative: &str) -> Result<(), Error> {
+        let relative = normalize(relative)?;
+        match fs::remove_file(self.draft_file(&relative)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// 获取页面草稿文件的路径，文件名为页面路径的哈希
+    fn draft_file(&self, relative: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.json", content::sha256_hex(relative.as_bytes())))
+    }
+}
+
+/// 将页面路径统一为使用 `/` 分隔的形式，并拒绝越过知识库根目录的路径和根目录本身
+fn normalize(relative: &str) -> Result<String, Error> {
+    let normalized = wiki::normalize_relative_path(relative)?;
+    if normalized.is_empty() {
+        return Err(wiki::Error::InvalidPath(relative.to_string()).into());
+    }
+    Ok(normalized)
+}
+
+/// 当前时间（Unix 时间戳，单位为秒）
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试草稿的保存、列出、恢复和删除
+    ///
+    /// # 测试目标：
+    /// * 验证草稿保存在草稿目录而不是工作区中
+    /// * 验证列出的草稿包含与磁盘内容的差异，与磁盘内容相同的草稿不被列出
+    /// * 验证恢复草稿会覆盖页面并删除草稿，删除草稿后不再列出
+    /// * 验证新建页面的草稿不被视为页面已修改，页面在列出之后被修改时拒绝恢复
+    #[test]
+    fn test_save_list_restore_discard() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/a.md"), "line1\nline2\n").unwrap();
+        fs::write(root.join("b.md"), "b\n").unwrap();
+        let drafts = Drafts::new(&root, &temp_dir.path().join("drafts"));
+
+        let base = content::read_text(&root.join("docs/a.md")).unwrap();
+        drafts
+            .save("/docs/a.md", "line1\nedited\n", Some(&base.version))
+            .unwrap();
+        drafts.save("b.md", "b\n", None).unwrap();
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+
+        let list = drafts.list().unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].path, "docs/a.md");
+        assert!(list[0].page_exists);
+        assert!(!list[0].page_changed);
+        assert!(list[0].diff.contains("-line2"));
+        assert!(list[0].diff.contains("+edited"));
+
+        // 草稿保存后页面被同步修改
+        fs::write(root.join("docs/a.md"), "synced\n").unwrap();
+        let listed = drafts.list().unwrap().remove(0);
+        assert!(listed.page_changed);
+
+        // 列出之后页面再次被修改时拒绝恢复
+        fs::write(root.join("docs/a.md"), "synced again\n").unwrap();
+        assert!(matches!(
+            drafts.restore("docs/a.md", listed.page_version.as_deref()),
+            Err(Error::Wiki(wiki::Error::Conflict { .. }))
+        ));
+        let listed = drafts.list().unwrap().remove(0);
+
+        let restored = drafts
+            .restore("docs/a.md", listed.page_version.as_deref())
+            .unwrap();
+        assert_eq!(restored.content, "line1\nedited\n");
+        assert_eq!(
+            fs::read_to_string(root.join("docs/a.md")).unwrap(),
+            "line1\nedited\n"
+        );
+        assert!(drafts.get("docs/a.md").unwrap().is_none());
+        assert!(matches!(
+            drafts.restore("docs/a.md", None),
+            Err(Error::NotFound(_))
+        ));
+
+        // 新建且从未保存的页面
+        drafts.save("c.md", "new page", None).unwrap();
+        let listed = drafts.list().unwrap().remove(0);
+        assert!(!listed.page_exists);
+        assert!(!listed.page_changed);
+        assert_eq!(listed.page_version, None);
+        fs::write(root.join("c.md"), "created elsewhere").unwrap();
+        assert!(drafts.restore("c.md", None).is_err());
+        fs::remove_file(root.join("c.md")).unwrap();
+        drafts.restore("c.md", None).unwrap();
+        assert_eq!(fs::read_to_string(root.join("c.md")).unwrap(), "new page");
+
+        drafts.save("c.md", "new page 2", None).unwrap();
+        drafts.discard("c.md").unwrap();
+        assert!(drafts.list().unwrap().is_empty());
+        assert!(drafts.save("../x.md", "x", None).is_err());
+    }
+}
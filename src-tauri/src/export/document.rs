@@ -0,0 +1,296 @@
+//! 单文件文档导出
+//!
+//! 将一个页面，或一个文件夹中按文件树顺序排列的所有页面，导出为单个自包含的 HTML 文件：
+//! * 样式内嵌在文件中，图片转换为 data URI，文件可以直接发送给团队以外的人
+//! * 指向文档内其他页面的链接改为文档内的锚点
+//! * 多个页面时在开头生成目录
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::{escape_html, mime_type, render_markdown, resolve_target, Error, LinkRef, STYLE_CSS};
+use crate::wiki::{collect_files, is_page, FileNode};
+
+/// 文档导出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentFormat {
+    /// 单个自包含的 HTML 文件
+    #[default]
+    Html,
+    /// PDF 文件
+    Pdf,
+}
+
+/// 文档中的一个页面
+///
+/// # 字段
+/// * `path` - 页面相对于知识库根目录的路径
+/// * `content` - 页面的完整内容
+#[derive(Debug, Clone)]
+pub struct DocumentPage {
+    pub path: String,
+    pub content: String,
+}
+
+/// 导出结果
+///
+/// # 字段
+/// * `output_path` - 导出的文件路径
+/// * `pages` - 导出的页面数量
+/// * `missing_images` - 无法内嵌的图片（文件不存在或是内容尚未下载的 Git LFS 文件）
+/// * `image_placeholders` - 以文字占位的图片，仅用于 PDF 格式
+/// * `fallback_font` - 是否因为找不到中文字体而使用了内置的西文字体，仅用于 PDF 格式
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentReport {
+    pub output_path: String,
+    pub pages: usize,
+    pub missing_images: Vec<String>,
+    pub image_placeholders: Vec<String>,
+    pub fallback_font: bool,
+}
+
+/// 按文件树顺序读取要导出的页面
+///
+/// # 参数
+/// * `root` - 知识库根目录
+/// * `node` - 要导出的页面或文件夹的文件树
+///
+/// # 返回值
+/// * `Result<Vec<DocumentPage>, Error>` - 成功时返回页面列表
+/// * 失败时返回 `Err(Error)`，路径中没有页面时返回 `Error::NoPages`
+pub fn collect_pages(root: &Path, node: &FileNode) -> Result<Vec<DocumentPage>, Error> {
+    let mut files = Vec::new();
+    collect_files(node, &mut files);
+
+    let pages = files
+        .into_iter()
+        .filter(|file| is_page(file.path()))
+        .map(|file| {
+            Ok(DocumentPage {
+                path: file.path().to_string(),
+                content: fs::read_to_string(root.join(file.path()))?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if pages.is_empty() {
+        return Err(Error::NoPages(node.path().to_string()));
+    }
+    Ok(pages)
+}
+
+/// 将页面导出为单个自包含的 HTML 文件
+///
+/// # 参数
+/// * `root` - 知识库根目录，用于读取图片
+/// * `title` - 文档标题
+/// * `pages` - 按顺序排列的页面
+/// * `assets` - 知识库中的附件相对路径，用于解析 `![[图片]]` 形式的双链
+/// * `output` - 导出的文件路径
+///
+/// # 返回值
+/// * `Result<DocumentReport, Error>` - 成功时返回导出结果
+/// * 失败时返回 `Err(Error)`
+pub fn export_html(
+    root: &Path,
+    title: &str,
+    pages: &[DocumentPage],
+    assets: &[&str],
+    output: &Path,
+) -> Result<DocumentReport, Error> {
+    let page_paths: Vec<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+    let mut missing_images = Vec::new();
+    let mut toc = String::new();
+    let mut sections = String::new();
+
+    for page in pages {
+        let file_name = page.path.rsplit('/').next().unwrap_or(&page.path);
+        let rendered = render_markdown(&page.content, file_name, |link| {
+            rewrite_link(
+                root,
+                &page.path,
+                link,
+                &page_paths,
+                assets,
+                &mut missing_images,
+            )
+        });
+
+        let anchor = page_anchor(&page.path);
+        toc.push_str(&format!(
+            "<li><a href=\"#{}\">{}</a></li>\n",
+            anchor,
+            escape_html(&rendered.title)
+        ));
+        sections.push_str(&format!(
+            "<section id=\"{}\" class=\"page\">\n{}</section>\n",
+            anchor, rendered.html
+        ));
+    }
+
+    let toc = if pages.len() > 1 {
+        format!(
+            "<nav class=\"toc\">\n<h1>{}</h1>\n<ol>\n{}</ol>\n</nav>\n",
+            escape_html(title),
+            toc
+        )
+    } else {
+        String::new()
+    };
+    let html = format!(
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+{style}
+section.page + section.page {{
+  margin-top: 48px;
+  padding-top: 24px;
+  border-top: 1px solid #d0d7de;
+  break-before: page;
+}}
+</style>
+</head>
+<body>
+<main>
+<article>
+{toc}{sections}</article>
+</main>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        style = STYLE_CSS,
+        toc = toc,
+        sections = sections,
+    );
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output, html)?;
+
+    missing_images.sort();
+    missing_images.dedup();
+    Ok(DocumentReport {
+        output_path: output.display().to_string(),
+        pages: pages.len(),
+        missing_images,
+        image_placeholders: Vec::new(),
+        fallback_font: false,
+    })
+}
+
+/// 改写页面中的链接：图片转换为 data URI，文档内的页面链接改为锚点
+fn rewrite_link(
+    root: &Path,
+    page: &str,
+    link: LinkRef,
+    pages: &[&str],
+    assets: &[&str],
+    missing_images: &mut Vec<String>,
+) -> Option<String> {
+    let target = resolve_target(page, link, pages, assets)?;
+
+    if link.is_image {
+        return match data_uri(&root.join(&target)) {
+            Some(uri) => Some(uri),
+            None => {
+                missing_images.push(target);
+                None
+            }
+        };
+    }
+
+    pages
+        .contains(&target.as_str())
+        .then(|| format!("#{}", page_anchor(&target)))
+}
+
+/// 读取图片并转换为 data URI，文件不存在或是 Git LFS 指针时返回 `None`
+fn data_uri(path: &Path) -> Option<String> {
+    if crate::git::lfs::read_pointer_file(path).is_some() {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    Some(format!(
+        "data:{};base64,{}",
+        mime_type(path),
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// 生成页面在文档中的锚点
+fn page_anchor(path: &str) -> String {
+    let slug: String = path
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("page-{}", slug)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wiki::build_file_tree;
+
+    /// 测试导出单个 HTML 文件
+    ///
+    /// # 测试目标：
+    /// * 验证文件夹中的页面按文件树顺序合并，并生成目录
+    /// * 验证图片被转换为 data URI，文档内的页面链接改为锚点
+    /// * 验证无法读取的图片被记录下来，导出位置不能位于知识库内部
+    #[test]
+    fn test_export_html() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        fs::create_dir_all(root.join("docs/sub")).unwrap();
+        fs::write(root.join("docs/b.md"), "# 第二章\n\n![缺失](none.png)").unwrap();
+        fs::write(
+            root.join("docs/a.md"),
+            "# 第一章\n\n![图](../logo.png) 见 [第二章](b.md)",
+        )
+        .unwrap();
+        fs::write(root.join("docs/sub/c.md"), "# 附录").unwrap();
+        fs::write(root.join("logo.png"), [1u8, 2, 3]).unwrap();
+
+        let tree = build_file_tree(&root).unwrap();
+        let docs = tree
+            .children()
+            .iter()
+            .find(|node| node.path() == "docs")
+            .unwrap();
+        let pages = collect_pages(&root, docs).unwrap();
+        let order: Vec<&str> = pages.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(order, vec!["docs/sub/c.md", "docs/a.md", "docs/b.md"]);
+
+        let output = temp_dir.path().join("out/docs.html");
+        let report = export_html(&root, "文档", &pages, &["logo.png"], &output).unwrap();
+        assert_eq!(report.pages, 3);
+        assert_eq!(report.missing_images, vec!["docs/none.png"]);
+
+        let html = fs::read_to_string(&output).unwrap();
+        assert!(html.contains("src=\"data:image/png;base64,AQID\""));
+        assert!(html.contains("href=\"#page-docs-b-md\""));
+        assert!(html.contains("<section id=\"page-docs-a-md\" class=\"page\">"));
+        assert!(html.contains("<li><a href=\"#page-docs-sub-c-md\">附录</a></li>"));
+
+        // 导出的文件不能位于知识库内部
+        assert!(crate::export::ensure_outside(&root, &temp_dir.path().join("out")).is_ok());
+        assert!(matches!(
+            crate::export::ensure_outside(&root, &root.join("exports")),
+            Err(Error::InvalidOutput(_))
+        ));
+
+        assert!(matches!(
+            collect_pages(&root, &build_file_tree(&root.join("logo.png")).unwrap()),
+            Err(Error::NoPages(_))
+        ));
+    }
+}
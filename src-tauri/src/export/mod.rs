@@ -0,0 +1,335 @@
+//! 导出模块
+//!
+//! 此模块负责将知识库中的 Markdown 页面渲染为 HTML，用于在应用之外发布或分享知识库。
+//! 页面开头的 front matter 不会出现在导出结果中，其中的 `title` 字段作为页面标题。
+
+pub mod document;
+pub mod pdf;
+pub mod site;
+
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::wiki;
+use crate::wiki::link::{is_external, resolve_link, wikilink_matches};
+use crate::wiki::page::Page;
+
+/// 导出操作可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 知识库操作错误
+    #[error("Wiki error: {0}")]
+    Wiki(#[from] wiki::Error),
+
+    /// 导出目录不可用，例如位于知识库内部
+    #[error("Invalid output directory: {0}")]
+    InvalidOutput(String),
+
+    /// 要导出的路径中没有页面
+    #[error("No pages to export: {0}")]
+    NoPages(String),
+
+    /// 生成 PDF 失败
+    #[error("PDF error: {0}")]
+    Pdf(String),
+}
+
+/// 页面共用的样式表
+pub const STYLE_CSS: &str = include_str!("style.css");
+
+/// 页面中的链接或图片
+///
+/// # 字段
+/// * `dest` - 链接的原始地址
+/// * `is_wikilink` - 是否为 `[[页面]]` 形式的双链
+/// * `is_image` - 是否为图片
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkRef<'a> {
+    pub dest: &'a str,
+    pub is_wikilink: bool,
+    pub is_image: bool,
+}
+
+/// 渲染后的页面
+///
+/// # 字段
+/// * `title` - 页面标题
+/// * `html` - 正文渲染得到的 HTML 片段
+/// * `text` - 正文的纯文本，用于建立搜索索引
+#[derive(Debug, Clone)]
+pub struct RenderedPage {
+    pub title: String,
+    pub html: String,
+    pub text: String,
+}
+
+/// 将 Markdown 页面渲染为 HTML
+///
+/// 页面中的每个链接和图片地址都会交给 `rewrite` 处理，返回 `Some` 时使用新的地址，返回 `None` 时保持原样。
+///
+/// # 参数
+/// * `content` - 页面的完整内容，可以包含 front matter
+/// * `file_name` - 页面的文件名，用于在没有标题时作为标题
+/// * `rewrite` - 链接改写函数
+///
+/// # 返回值
+/// * `RenderedPage` - 渲染后的页面
+pub fn render_markdown<F>(content: &str, file_name: &str, mut rewrite: F) -> RenderedPage
+where
+    F: FnMut(LinkRef) -> Option<String>,
+{
+    let page = parse_page(content);
+
+    let mut text = String::new();
+    let events = Parser::new_ext(&page.body, markdown_options()).map(|event| match event {
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => {
+            let dest_url = rewrite_dest(&mut rewrite, dest_url, link_type, false);
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            })
+        }
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => {
+            let dest_url = rewrite_dest(&mut rewrite, dest_url, link_type, true);
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            })
+        }
+        Event::Text(ref s) | Event::Code(ref s) => {
+            text.push_str(s);
+            event
+        }
+        Event::SoftBreak | Event::HardBreak | Event::End(_) => {
+            if !text.ends_with(char::is_whitespace) {
+                text.push(' ');
+            }
+            event
+        }
+        event => event,
+    });
+
+    let mut html_output = String::new();
+    html::push_html(&mut html_output, events);
+
+    RenderedPage {
+        title: page.metadata(file_name).title,
+        html: html_output,
+        text: text.trim().to_string(),
+    }
+}
+
+/// 获取页面标题，规则与 `render_markdown` 相同，但不渲染正文
+///
+/// # 参数
+/// * `content` - 页面的完整内容
+/// * `file_name` - 页面的文件名
+///
+/// # 返回值
+/// * `String` - 页面标题
+pub fn page_title(content: &str, file_name: &str) -> String {
+    parse_page(content).metadata(file_name).title
+}
+
+/// 解析页面，front matter 无法解析时整个页面按正文处理
+fn parse_page(content: &str) -> Page {
+    Page::parse(content).unwrap_or_else(|_| Page {
+        front_matter: None,
+        body: content.to_string(),
+    })
+}
+
+/// 导出时使用的 Markdown 扩展语法
+pub fn markdown_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_WIKILINKS
+}
+
+/// 使用改写函数处理链接地址
+fn rewrite_dest<'a, F>(
+    rewrite: &mut F,
+    dest_url: CowStr<'a>,
+    link_type: pulldown_cmark::LinkType,
+    is_image: bool,
+) -> CowStr<'a>
+where
+    F: FnMut(LinkRef) -> Option<String>,
+{
+    let link = LinkRef {
+        dest: &dest_url,
+        is_wikilink: matches!(link_type, pulldown_cmark::LinkType::WikiLink { .. }),
+        is_image,
+    };
+    match rewrite(link) {
+        Some(url) => CowStr::from(url),
+        None => dest_url,
+    }
+}
+
+/// 获取页面导出后的 HTML 文件路径，例如 `docs/guide.md` 对应 `docs/guide.html`
+pub fn html_path(page: &str) -> String {
+    let stem = page
+        .rsplit_once('.')
+        .filter(|(_, ext)| !ext.contains('/'))
+        .map(|(stem, _)| stem)
+        .unwrap_or(page);
+    format!("{}.html", stem)
+}
+
+/// 将链接目标解析为知识库内文件的相对路径
+///
+/// 双链按页面名称在 `pages` 中查找，找不到时按文件名在 `assets` 中查找；
+/// 普通链接相对于所在页面解析。
+///
+/// # 参数
+/// * `page` - 链接所在页面的相对路径
+/// * `link` - 页面中的链接
+/// * `pages` - 知识库中的页面
+/// * `assets` - 知识库中的附件
+///
+/// # 返回值
+/// * `Option<String>` - 目标文件的相对路径；外部链接或无法解析的双链返回 `None`
+pub fn resolve_target(
+    page: &str,
+    link: LinkRef,
+    pages: &[&str],
+    assets: &[&str],
+) -> Option<String> {
+    if is_external(link.dest) {
+        return None;
+    }
+    if !link.is_wikilink {
+        return resolve_link(page, link.dest);
+    }
+
+    let name = link.dest.split('#').next().unwrap_or("").trim();
+    pages
+        .iter()
+        .find(|path| wikilink_matches(name, path))
+        .or_else(|| {
+            assets
+                .iter()
+                .find(|path| **path == name || path.ends_with(&format!("/{}", name)))
+        })
+        .map(|path| path.to_string())
+}
+
+/// 根据扩展名判断文件的 MIME 类型，用于内嵌图片和本地服务的响应
+pub fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "avif" => "image/avif",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "txt" | "csv" => "text/plain; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 确认导出位置不在知识库内部，以免导出结果被当作知识库内容提交
+///
+/// # 参数
+/// * `root` - 知识库根目录
+/// * `dir` - 导出目录或导出文件所在的目录，不存在时会自动创建
+///
+/// # 返回值
+/// * `Result<(), Error>` - 目录位于知识库内部时返回 `Err(Error::InvalidOutput)`
+pub fn ensure_outside(root: &Path, dir: &Path) -> Result<(), Error> {
+    fs::create_dir_all(dir)?;
+    if dir.canonicalize()?.starts_with(root.canonicalize()?) {
+        return Err(Error::InvalidOutput(dir.display().to_string()));
+    }
+    Ok(())
+}
+
+/// 转义 HTML 中的特殊字符
+pub fn escape_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试 Markdown 渲染
+    ///
+    /// # 测试目标：
+    /// * 验证 front matter 中的标题被使用且不出现在正文中
+    /// * 验证链接、图片与双链都会交给改写函数处理
+    #[test]
+    fn test_render_markdown() {
+        let content =
+            "---\ntitle: 指南\n---\n见 [安装](install.md#步骤) 与 [[首页]]。\n\n![图](a.png)\n";
+        let mut seen = Vec::new();
+        let page = render_markdown(content, "guide.md", |link| {
+            seen.push((link.dest.to_string(), link.is_wikilink, link.is_image));
+            link.dest
+                .strip_suffix(".png")
+                .map(|stem| format!("{}.jpg", stem))
+        });
+
+        assert_eq!(page.title, "指南");
+        assert!(!page.html.contains("title:"));
+        assert!(page.html.contains("src=\"a.jpg\""));
+        assert!(page.text.contains("安装"));
+        assert_eq!(
+            seen,
+            vec![
+                ("install.md#步骤".to_string(), false, false),
+                ("首页".to_string(), true, false),
+                ("a.png".to_string(), false, true),
+            ]
+        );
+
+        assert_eq!(html_path("docs/guide.md"), "docs/guide.html");
+        assert_eq!(html_path("v1.0/readme"), "v1.0/readme.html");
+    }
+}
@@ -0,0 +1,399 @@
+//! PDF 导出
+//!
+//! 使用纯 Rust 的 PDF 库排版，不依赖浏览器引擎。排版只保留文档结构：
+//! 标题、段落、列表、引用、代码块和表格按行输出，图片以 `[图片: 说明]` 的文字占位。
+//! 每个页面从新的一页开始，并在 PDF 书签中列出。
+//!
+//! 显示中文需要包含中文字形的 TrueType 字体。未指定字体时会依次尝试常见的系统字体，
+//! 都不可用时使用 PDF 内置的 Helvetica 字体，此时只能显示西文字符，导出结果的 `fallback_font` 为 `true`。
+
+use printpdf::{
+    BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference,
+    PdfPageIndex,
+};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use super::document::{DocumentPage, DocumentReport};
+use super::{markdown_options, Error};
+use crate::wiki::page::Page;
+
+/// 页面宽度（A4）
+const PAGE_WIDTH: f32 = 210.0;
+/// 页面高度（A4）
+const PAGE_HEIGHT: f32 = 297.0;
+/// 页边距
+const MARGIN: f32 = 20.0;
+/// 每级列表或引用的缩进
+const INDENT: f32 = 6.0;
+/// 正文字号（磅）
+const BODY_SIZE: f32 = 11.0;
+/// 代码字号（磅）
+const CODE_SIZE: f32 = 9.5;
+/// 1 磅对应的毫米数
+const PT_TO_MM: f32 = 0.3528;
+
+/// 未指定字体时依次尝试的系统字体
+const FONT_CANDIDATES: &[&str] = &[
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simhei.ttf",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/System/Library/Fonts/STHeiti Light.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-zenhei.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+];
+
+/// 将页面导出为 PDF 文件
+///
+/// # 参数
+/// * `title` - 文档标题
+/// * `pages` - 按顺序排列的页面
+/// * `font` - TrueType 字体文件路径，为 `None` 时自动选择
+/// * `output` - 导出的文件路径
+///
+/// # 返回值
+/// * `Result<DocumentReport, Error>` - 成功时返回导出结果，`image_placeholders` 中为以文字占位的图片
+/// * 失败时返回 `Err(Error)`
+pub fn export_pdf(
+    title: &str,
+    pages: &[DocumentPage],
+    font: Option<&Path>,
+    output: &Path,
+) -> Result<DocumentReport, Error> {
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    let (font, fallback_font) = load_font(&doc, font)?;
+    let layer = doc.get_page(page).get_layer(layer);
+
+    let mut writer = PdfWriter {
+        doc: &doc,
+        page,
+        layer,
+        font,
+        y: PAGE_HEIGHT - MARGIN,
+        images: Vec::new(),
+    };
+
+    for (i, page) in pages.iter().enumerate() {
+        if i > 0 {
+            writer.new_page();
+        }
+        let file_name = page.path.rsplit('/').next().unwrap_or(&page.path);
+        let parsed = Page::parse(&page.content).unwrap_or_else(|_| Page {
+            front_matter: None,
+            body: page.content.clone(),
+        });
+        writer.bookmark(&parsed.metadata(file_name).title);
+        writer.write_markdown(&parsed.body);
+    }
+
+    let mut images = std::mem::take(&mut writer.images);
+    drop(writer);
+
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    doc.save(&mut BufWriter::new(File::create(output)?))
+        .map_err(|e| Error::Pdf(e.to_string()))?;
+
+    images.sort();
+    images.dedup();
+    Ok(DocumentReport {
+        output_path: output.display().to_string(),
+        pages: pages.len(),
+        missing_images: Vec::new(),
+        image_placeholders: images,
+        fallback_font,
+    })
+}
+
+/// 加载字体，指定的字体无法加载时返回错误
+///
+/// # 返回值
+/// * `Result<(IndirectFontRef, bool), Error>` - 成功时返回字体，以及是否使用了内置的西文字体
+fn load_font(
+    doc: &PdfDocumentReference,
+    font: Option<&Path>,
+) -> Result<(IndirectFontRef, bool), Error> {
+    if let Some(path) = font {
+        return doc
+            .add_external_font(File::open(path)?)
+            .map(|font| (font, false))
+            .map_err(|e| Error::Pdf(format!("无法加载字体 {}: {}", path.display(), e)));
+    }
+
+    for candidate in FONT_CANDIDATES {
+        if let Ok(file) = File::open(candidate) {
+            if let Ok(font) = doc.add_external_font(file) {
+                return Ok((font, false));
+            }
+        }
+    }
+    doc.add_builtin_font(BuiltinFont::Helvetica)
+        .map(|font| (font, true))
+        .map_err(|e| Error::Pdf(e.to_string()))
+}
+
+/// 按行排版的 PDF 写入器
+struct PdfWriter<'a> {
+    doc: &'a PdfDocumentReference,
+    page: PdfPageIndex,
+    layer: PdfLayerReference,
+    font: IndirectFontRef,
+    /// 下一行的基线位置，从页面底部算起
+    y: f32,
+    /// 以文字占位的图片
+    images: Vec<String>,
+}
+
+/// 正在收集的文本块
+#[derive(Default)]
+struct Block {
+    text: String,
+    size: f32,
+    indent: f32,
+    /// 列表项的第一段需要加上项目符号
+    bullet: Option<String>,
+}
+
+impl PdfWriter<'_> {
+    /// 开始新的一页
+    fn new_page(&mut self) {
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        self.page = page;
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// 为当前页添加书签
+    fn bookmark(&self, title: &str) {
+        self.doc.add_bookmark(title, self.page);
+    }
+
+    /// 排版 Markdown 正文
+    fn write_markdown(&mut self, markdown: &str) {
+        let mut block = Block {
+            size: BODY_SIZE,
+            ..Default::default()
+        };
+        let mut depth = 0usize;
+        let mut ordered: Vec<Option<u64>> = Vec::new();
+        let mut in_code = false;
+
+        for event in Parser::new_ext(markdown, markdown_options()) {
+            match event {
+                Event::Start(Tag::Heading { level, .. }) => {
+                    self.flush(&mut block);
+                    block.size = heading_size(level);
+                }
+                Event::End(TagEnd::Heading(_)) => {
+                    self.flush(&mut block);
+                    block.size = BODY_SIZE;
+                }
+                Event::Start(Tag::List(start)) => {
+                    self.flush(&mut block);
+                    ordered.push(start);
+                    depth += 1;
+                }
+                Event::End(TagEnd::List(_)) => {
+                    self.flush(&mut block);
+                    ordered.pop();
+                    depth -= 1;
+                }
+                Event::Start(Tag::Item) => {
+                    self.flush(&mut block);
+                    block.bullet = Some(match ordered.last_mut() {
+                        Some(Some(n)) => {
+                            *n += 1;
+                            format!("{}. ", *n - 1)
+                        }
+                        _ => "• ".to_string(),
+                    });
+                }
+                Event::Start(Tag::BlockQuote(_)) => {
+                    self.flush(&mut block);
+                    depth += 1;
+                }
+                Event::End(TagEnd::BlockQuote(_)) => {
+                    self.flush(&mut block);
+                    depth -= 1;
+                }
+                Event::Start(Tag::CodeBlock(_)) => {
+                    self.flush(&mut block);
+                    in_code = true;
+                }
+                Event::End(TagEnd::CodeBlock) => {
+                    in_code = false;
+                    let code = std::mem::take(&mut block.text);
+                    for line in code.trim_end_matches('\n').lines() {
+                        self.line(line, CODE_SIZE, depth as f32 * INDENT + INDENT);
+                    }
+                    self.y -= CODE_SIZE * PT_TO_MM;
+                }
+                Event::Start(Tag::Image { dest_url, .. }) => {
+                    self.images.push(dest_url.to_string());
+                    block.text.push_str("[图片: ");
+                }
+                Event::End(TagEnd::Image) => block.text.push(']'),
+                Event::End(TagEnd::TableCell) => block.text.push_str(" | "),
+                Event::End(
+                    TagEnd::Paragraph | TagEnd::Item | TagEnd::TableRow | TagEnd::TableHead,
+                ) => self.flush(&mut block),
+                Event::Text(text) | Event::Code(text) => {
+                    block.indent = depth as f32 * INDENT;
+                    block.text.push_str(&text);
+                }
+                Event::SoftBreak => block.text.push(if in_code { '\n' } else { ' ' }),
+                Event::HardBreak => block.text.push('\n'),
+                Event::TaskListMarker(checked) => {
+                    block.text.push_str(if checked { "[x] " } else { "[ ] " })
+                }
+                Event::FootnoteReference(name) => block.text.push_str(&format!("[{}]", name)),
+                Event::Rule => {
+                    self.flush(&mut block);
+                    self.y -= BODY_SIZE * PT_TO_MM;
+                }
+                _ => {}
+            }
+        }
+        self.flush(&mut block);
+    }
+
+    /// 输出收集到的文本块并清空
+    fn flush(&mut self, block: &mut Block) {
+        let text = std::mem::take(&mut block.text);
+        let text = text.trim().trim_end_matches(" |");
+        if text.is_empty() {
+            return;
+        }
+
+        let text = match block.bullet.take() {
+            Some(bullet) => format!("{}{}", bullet, text),
+            None => text.to_string(),
+        };
+        let width = PAGE_WIDTH - 2.0 * MARGIN - block.indent;
+        for line in wrap(&text, width, block.size) {
+            self.line(&line, block.size, block.indent);
+        }
+        self.y -= block.size * PT_TO_MM * 0.6;
+    }
+
+    /// 输出一行文字，空间不足时换页
+    fn line(&mut self, text: &str, size: f32, indent: f32) {
+        let height = size * PT_TO_MM * 1.5;
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+        self.y -= height;
+        self.layer
+            .use_text(text, size, Mm(MARGIN + indent), Mm(self.y), &self.font);
+    }
+}
+
+/// 标题字号
+fn heading_size(level: HeadingLevel) -> f32 {
+    match level {
+        HeadingLevel::H1 => 20.0,
+        HeadingLevel::H2 => 16.0,
+        HeadingLevel::H3 => 14.0,
+        _ => 12.0,
+    }
+}
+
+/// 估算字符宽度（以字号为单位），中日韩等全角字符按一个字宽计算
+fn char_width(c: char) -> f32 {
+    if c as u32 >= 0x2E80 {
+        1.0
+    } else {
+        0.55
+    }
+}
+
+/// 按宽度折行，西文在空格处断开，全角字符可以在任意位置断开
+fn wrap(text: &str, width: f32, size: f32) -> Vec<String> {
+    let em = size * PT_TO_MM;
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0.0;
+        // 当前行中最后一个空格的位置，用于在单词之间断行
+        let mut last_space: Option<usize> = None;
+
+        for c in paragraph.chars() {
+            let w = char_width(c) * em;
+            if line_width + w > width && !line.is_empty() {
+                match last_space.filter(|_| !c.is_whitespace() && char_width(c) < 1.0) {
+                    Some(pos) => {
+                        let rest = line.split_off(pos);
+                        lines.push(line.trim_end().to_string());
+                        line = rest.trim_start().to_string();
+                    }
+                    None => lines.push(std::mem::take(&mut line).trim_end().to_string()),
+                }
+                line_width = line.chars().map(|c| char_width(c) * em).sum();
+                last_space = None;
+            }
+            if c.is_whitespace() {
+                if line.is_empty() {
+                    continue;
+                }
+                last_space = Some(line.len());
+            }
+            line.push(c);
+            line_width += w;
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试 PDF 导出
+    ///
+    /// # 测试目标：
+    /// * 验证折行不超过宽度，并优先在空格处断开
+    /// * 验证生成的文件是 PDF，图片被记录为文字占位，并报告是否使用了内置字体
+    #[test]
+    fn test_export_pdf() {
+        let em = 10.0 * PT_TO_MM;
+        let lines = wrap("hello world again", 12.2 * 0.55 * em, 10.0);
+        assert_eq!(lines, vec!["hello world", "again"]);
+        let lines = wrap("中文内容需要折行", 3.5 * em, 10.0);
+        assert_eq!(lines, vec!["中文内", "容需要", "折行"]);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let output = temp_dir.path().join("doc.pdf");
+        let pages = vec![
+            DocumentPage {
+                path: "a.md".to_string(),
+                content: "# Title\n\n- one\n- two\n\n```\ncode\n```\n\n![logo](logo.png)"
+                    .to_string(),
+            },
+            DocumentPage {
+                path: "b.md".to_string(),
+                content: "Second page".to_string(),
+            },
+        ];
+        let report = export_pdf("Doc", &pages, None, &output).unwrap();
+        assert_eq!(report.pages, 2);
+        assert_eq!(report.image_placeholders, vec!["logo.png"]);
+        assert!(report.missing_images.is_empty());
+        // 沙箱等环境中可能没有中文字体，此时应在结果中说明
+        let has_font = FONT_CANDIDATES.iter().any(|f| Path::new(f).exists());
+        assert_eq!(report.fallback_font, !has_font);
+        assert!(std::fs::read(&output).unwrap().starts_with(b"%PDF"));
+    }
+}
@@ -0,0 +1,35 @@
+// 静态网站的客户端搜索，索引由 search-index.js 提供
+(function () {
+  var input = document.getElementById('search');
+  var results = document.getElementById('search-results');
+  var index = window.MARKWIKI_SEARCH_INDEX || [];
+  var root = document.body.getAttribute('data-root') || '';
+  if (!input || !results) {
+    return;
+  }
+
+  input.addEventListener('input', function () {
+    var terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    results.innerHTML = '';
+    if (terms.length === 0) {
+      return;
+    }
+
+    index
+      .filter(function (entry) {
+        var haystack = (entry.title + '\n' + entry.text).toLowerCase();
+        return terms.every(function (term) {
+          return haystack.indexOf(term) !== -1;
+        });
+      })
+      .slice(0, 50)
+      .forEach(function (entry) {
+        var item = document.createElement('li');
+        var link = document.createElement('a');
+        link.href = root + entry.url;
+        link.textContent = entry.title;
+        item.appendChild(link);
+        results.appendChild(item);
+      });
+  });
+})();
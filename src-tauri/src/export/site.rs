@@ -0,0 +1,363 @@
+//! 静态网站导出
+//!
+//! 将整个知识库导出为不依赖应用即可浏览的静态网站：
+//! * 每个 Markdown 页面渲染为同名的 `.html` 文件，页面之间的链接和双链指向对应的 `.html` 文件
+//! * 每个页面带有根据文件树生成的导航侧边栏和搜索框
+//! * 附件按原有的相对路径复制，内容尚未下载的 Git LFS 文件会被跳过
+//! * 知识库根目录有 `index.md` 时将其作为首页，否则生成列出所有页面的首页
+//! * 搜索索引写入 `search-index.js`，直接打开本地文件时同样可以搜索
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::{
+    ensure_outside, escape_html, html_path, render_markdown, resolve_target, Error, LinkRef,
+    RenderedPage, STYLE_CSS,
+};
+use crate::wiki::link::relative_link;
+use crate::wiki::{collect_files, is_page, FileNode};
+
+/// 客户端搜索脚本
+pub const SEARCH_JS: &str = include_str!("search.js");
+
+/// 导出结果
+///
+/// # 字段
+/// * `output_dir` - 导出目录
+/// * `pages` - 导出的页面数量
+/// * `assets` - 复制的附件数量
+/// * `skipped` - 被跳过的文件（内容尚未下载的 Git LFS 文件或无法读取的页面）
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteReport {
+    pub output_dir: String,
+    pub pages: usize,
+    pub assets: usize,
+    pub skipped: Vec<String>,
+}
+
+/// 搜索索引中的一项
+#[derive(Serialize)]
+struct SearchEntry<'a> {
+    title: &'a str,
+    url: String,
+    text: &'a str,
+}
+
+/// 将知识库导出为静态网站
+///
+/// 导出目录中已有的同名文件会被覆盖，其他文件保持不变。
+///
+/// # 参数
+/// * `root` - 知识库根目录
+/// * `tree` - 知识库的文件树，只有其中的文件会被导出
+/// * `site_title` - 网站标题
+/// * `output` - 导出目录，不存在时会自动创建，不能位于知识库内部
+///
+/// # 返回值
+/// * `Result<SiteReport, Error>` - 成功时返回导出结果
+/// * 失败时返回 `Err(Error)`
+pub fn export_site(
+    root: &Path,
+    tree: &FileNode,
+    site_title: &str,
+    output: &Path,
+) -> Result<SiteReport, Error> {
+    ensure_outside(root, output)?;
+
+    let mut files = Vec::new();
+    collect_files(tree, &mut files);
+    let (pages, assets): (Vec<&FileNode>, Vec<&FileNode>) =
+        files.into_iter().partition(|node| is_page(node.path()));
+    let page_paths: Vec<&str> = pages.iter().map(|node| node.path()).collect();
+    let asset_paths: Vec<&str> = assets.iter().map(|node| node.path()).collect();
+
+    let mut report = SiteReport {
+        output_dir: output.display().to_string(),
+        pages: 0,
+        assets: 0,
+        skipped: Vec::new(),
+    };
+
+    // 先渲染所有页面，侧边栏需要用到每个页面的标题
+    let mut rendered: BTreeMap<&str, RenderedPage> = BTreeMap::new();
+    for node in &pages {
+        let page = node.path();
+        let content = match fs::read_to_string(root.join(page)) {
+            Ok(content) => content,
+            Err(_) => {
+                report.skipped.push(page.to_string());
+                continue;
+            }
+        };
+        let rendered_page = render_markdown(&content, node.name(), |link| {
+            rewrite_link(page, link, &page_paths, &asset_paths)
+        });
+        rendered.insert(page, rendered_page);
+    }
+    let titles: BTreeMap<&str, &str> = rendered
+        .iter()
+        .map(|(path, page)| (*path, page.title.as_str()))
+        .collect();
+
+    for (page, rendered_page) in &rendered {
+        let sidebar = render_sidebar(tree, page, &titles);
+        let html = page_html(
+            site_title,
+            page,
+            &rendered_page.title,
+            &sidebar,
+            &rendered_page.html,
+        );
+        write_output(output, &html_path(page), html.as_bytes())?;
+        report.pages += 1;
+    }
+
+    if !rendered.contains_key("index.md") {
+        let html = index_html(tree, site_title, &titles);
+        write_output(output, "index.html", html.as_bytes())?;
+    }
+
+    for node in &assets {
+        if node.is_lfs_pointer() {
+            report.skipped.push(node.path().to_string());
+            continue;
+        }
+        let target = output.join(node.path());
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(root.join(node.path()), target)?;
+        report.assets += 1;
+    }
+
+    write_output(
+        output,
+        "search-index.js",
+        search_index_js(&rendered)?.as_bytes(),
+    )?;
+    write_output(output, "search.js", SEARCH_JS.as_bytes())?;
+    write_output(output, "style.css", STYLE_CSS.as_bytes())?;
+
+    Ok(report)
+}
+
+/// 生成列出所有页面的首页
+///
+/// # 参数
+/// * `tree` - 知识库的文件树
+/// * `site_title` - 网站标题
+/// * `titles` - 页面相对路径到页面标题的映射
+///
+/// # 返回值
+/// * `String` - 完整的首页 HTML
+pub fn index_html(tree: &FileNode, site_title: &str, titles: &BTreeMap<&str, &str>) -> String {
+    let list: String = titles
+        .iter()
+        .map(|(path, title)| {
+            format!(
+                "<li><a href=\"{}\">{}</a> <span class=\"path\">{}</span></li>\n",
+                escape_html(&relative_link("", &html_path(path))),
+                escape_html(title),
+                escape_html(path)
+            )
+        })
+        .collect();
+    let content = format!(
+        "<h1>{}</h1>\n<ul class=\"page-list\">\n{}</ul>\n",
+        escape_html(site_title),
+        list
+    );
+    let sidebar = render_sidebar(tree, "index.md", titles);
+    page_html(site_title, "index.md", site_title, &sidebar, &content)
+}
+
+/// 生成搜索索引脚本 `search-index.js` 的内容
+///
+/// # 参数
+/// * `rendered` - 页面相对路径到渲染结果的映射
+///
+/// # 返回值
+/// * `Result<String, Error>` - 成功时返回脚本内容
+pub fn search_index_js(rendered: &BTreeMap<&str, RenderedPage>) -> Result<String, Error> {
+    let index: Vec<SearchEntry> = rendered
+        .iter()
+        .map(|(path, page)| SearchEntry {
+            title: &page.title,
+            url: relative_link("", &html_path(path)),
+            text: &page.text,
+        })
+        .collect();
+    let index = serde_json::to_string(&index).map_err(std::io::Error::from)?;
+    Ok(format!("window.MARKWIKI_SEARCH_INDEX = {};\n", index))
+}
+
+/// 改写页面中的链接：页面链接指向导出的 `.html` 文件，双链解析为实际的页面或附件
+pub fn rewrite_link(page: &str, link: LinkRef, pages: &[&str], assets: &[&str]) -> Option<String> {
+    let target = resolve_target(page, link, pages, assets)?;
+    let fragment = link.dest.find('#').map(|i| &link.dest[i..]).unwrap_or("");
+
+    if is_page(&target) {
+        Some(format!(
+            "{}{}",
+            relative_link(page, &html_path(&target)),
+            fragment
+        ))
+    } else {
+        Some(relative_link(page, &target))
+    }
+}
+
+/// 生成导航侧边栏，只包含页面以及含有页面的目录，当前页面所在的目录默认展开
+pub fn render_sidebar(node: &FileNode, current: &str, titles: &BTreeMap<&str, &str>) -> String {
+    let mut items = String::new();
+    for child in node.children() {
+        if child.is_directory() {
+            let inner = render_sidebar(child, current, titles);
+            if inner.is_empty() {
+                continue;
+            }
+            let open = if current.starts_with(&format!("{}/", child.path())) {
+                " open"
+            } else {
+                ""
+            };
+            items.push_str(&format!(
+                "<li><details{}><summary>{}</summary>{}</details></li>\n",
+                open,
+                escape_html(child.name()),
+                inner
+            ));
+        } else if let Some(title) = titles.get(child.path()) {
+            let class = if child.path() == current {
+                " class=\"current\""
+            } else {
+                ""
+            };
+            items.push_str(&format!(
+                "<li><a href=\"{}\"{}>{}</a></li>\n",
+                escape_html(&relative_link(current, &html_path(child.path()))),
+                class,
+                escape_html(title)
+            ));
+        }
+    }
+
+    if items.is_empty() {
+        items
+    } else {
+        format!("<ul>\n{}</ul>", items)
+    }
+}
+
+/// 生成完整的页面
+pub fn page_html(
+    site_title: &str,
+    page: &str,
+    title: &str,
+    sidebar: &str,
+    content: &str,
+) -> String {
+    // 从页面所在目录返回网站根目录的相对路径
+    let root = "../".repeat(page.matches('/').count());
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} - {site_title}</title>
+<link rel="stylesheet" href="{root}style.css">
+</head>
+<body data-root="{root}">
+<nav class="sidebar">
+<a class="site-title" href="{root}index.html">{site_title}</a>
+<input id="search" type="search" placeholder="搜索页面" autocomplete="off">
+<ul id="search-results"></ul>
+{sidebar}
+</nav>
+<main>
+<article>
+{content}
+</article>
+</main>
+<script src="{root}search-index.js"></script>
+<script src="{root}search.js"></script>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        site_title = escape_html(site_title),
+        root = root,
+        sidebar = sidebar,
+        content = content,
+    )
+}
+
+/// 写入导出目录中的文件，自动创建父目录
+fn write_output(output: &Path, relative: &str, bytes: &[u8]) -> Result<(), Error> {
+    let path = output.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wiki::build_file_tree;
+
+    /// 测试静态网站导出
+    ///
+    /// # 测试目标：
+    /// * 验证页面被渲染为 HTML，`.md` 链接与双链指向对应的 `.html` 文件
+    /// * 验证附件被复制，首页和搜索索引被生成
+    /// * 验证导出目录不能位于知识库内部
+    #[test]
+    fn test_export_site() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        fs::create_dir_all(root.join("docs/img")).unwrap();
+        fs::write(
+            root.join("home.md"),
+            "# 首页\n\n[指南](docs/guide.md#install) 与 [[guide|指南]]",
+        )
+        .unwrap();
+        fs::write(
+            root.join("docs/guide.md"),
+            "---\ntitle: 使用指南\n---\n![图](img/a.png) [返回](../home.md)",
+        )
+        .unwrap();
+        fs::write(root.join("docs/img/a.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+        let tree = build_file_tree(&root).unwrap();
+        let output = temp_dir.path().join("site");
+        let report = export_site(&root, &tree, "测试知识库", &output).unwrap();
+        assert_eq!((report.pages, report.assets), (2, 1));
+
+        let home = fs::read_to_string(output.join("home.html")).unwrap();
+        assert!(home.contains("href=\"docs/guide.html#install\""));
+        assert!(home.contains("href=\"docs/guide.html\">指南</a>"));
+        assert!(home.contains("<a href=\"home.html\" class=\"current\">首页</a>"));
+
+        let guide = fs::read_to_string(output.join("docs/guide.html")).unwrap();
+        assert!(guide.contains("<title>使用指南 - 测试知识库</title>"));
+        assert!(guide.contains("src=\"img/a.png\""));
+        assert!(guide.contains("href=\"../home.html\""));
+        assert!(guide.contains("href=\"../style.css\""));
+        assert!(output.join("docs/img/a.png").exists());
+
+        let index = fs::read_to_string(output.join("index.html")).unwrap();
+        assert!(index.contains("使用指南"));
+        let search = fs::read_to_string(output.join("search-index.js")).unwrap();
+        assert!(search.contains("\"url\":\"docs/guide.html\""));
+
+        assert!(matches!(
+            export_site(&root, &tree, "测试知识库", &root.join("site")),
+            Err(Error::InvalidOutput(_))
+        ));
+    }
+}
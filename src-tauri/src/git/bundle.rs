@@ -0,0 +1,370 @@
+//! Git bundle 支持
+//!
+//! bundle 是把提交历史打包成单个文件的 Git 标准格式，可以不经过远程仓库传递历史，
+//! 生成的文件可以直接用 `git clone` 或 `git fetch` 读取。
+//! `libgit2` 不支持 bundle，这里按照 v2 格式自行读写：
+//!
+//! ```text
+//! # v2 git bundle
+//! -<前置提交> <说明>
+//! <提交> <引用名称>
+//!
+//! <packfile>
+//! ```
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use super::{Error, Repository};
+
+/// v2 格式的文件头
+const SIGNATURE_V2: &str = "# v2 git bundle";
+/// v3 格式的文件头，v3 只是在引用之前增加了以 `@` 开头的能力声明
+const SIGNATURE_V3: &str = "# v3 git bundle";
+
+/// 解析后的 bundle 文件
+///
+/// # 字段
+/// * `prerequisites` - 前置提交，读取 bundle 的仓库中必须已经存在这些提交
+/// * `references` - bundle 中包含的引用及其指向的提交
+/// * `pack` - packfile 数据
+struct Bundle<'a> {
+    prerequisites: Vec<git2::Oid>,
+    references: Vec<(String, git2::Oid)>,
+    pack: &'a [u8],
+}
+
+impl<'a> Bundle<'a> {
+    /// 解析 bundle 文件内容
+    fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        let invalid = |message: &str| Error::InvalidBundle(message.to_string());
+
+        let mut bundle = Bundle {
+            prerequisites: Vec::new(),
+            references: Vec::new(),
+            pack: &[],
+        };
+        let mut offset = 0;
+        let mut first = true;
+        loop {
+            let end = data[offset..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .ok_or_else(|| invalid("文件头不完整"))?;
+            let line = std::str::from_utf8(&data[offset..offset + end])
+                .map_err(|_| invalid("文件头不是有效的 UTF-8"))?;
+            offset += end + 1;
+
+            if first {
+                if line != SIGNATURE_V2 && line != SIGNATURE_V3 {
+                    return Err(invalid("不支持的 bundle 版本"));
+                }
+                first = false;
+                continue;
+            }
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(capability) = line.strip_prefix('@') {
+                if capability != "object-format=sha1" && !capability.starts_with("filter=") {
+                    return Err(Error::InvalidBundle(format!(
+                        "不支持的能力: {}",
+                        capability
+                    )));
+                }
+            } else if let Some(rest) = line.strip_prefix('-') {
+                let oid = rest.split(' ').next().unwrap_or("");
+                bundle
+                    .prerequisites
+                    .push(git2::Oid::from_str(oid).map_err(|_| invalid("前置提交格式错误"))?);
+            } else {
+                let (oid, name) = line
+                    .split_once(' ')
+                    .ok_or_else(|| invalid("引用格式错误"))?;
+                let oid = git2::Oid::from_str(oid).map_err(|_| invalid("引用格式错误"))?;
+                bundle.references.push((name.to_string(), oid));
+            }
+        }
+
+        bundle.pack = &data[offset..];
+        if !bundle.pack.starts_with(b"PACK") {
+            return Err(invalid("缺少 packfile 数据"));
+        }
+        Ok(bundle)
+    }
+
+    /// 获取 bundle 中主分支指向的提交，没有主分支时依次使用 `HEAD` 和第一个引用
+    fn head(&self) -> Option<git2::Oid> {
+        ["refs/heads/master", "HEAD"]
+            .iter()
+            .find_map(|name| {
+                self.references
+                    .iter()
+                    .find(|(reference, _)| reference == name)
+            })
+            .or_else(|| self.references.first())
+            .map(|(_, oid)| *oid)
+    }
+}
+
+impl Repository {
+    /// 将当前分支的历史打包为 bundle
+    ///
+    /// 指定 `since` 时只打包该提交之后的提交，`since` 作为前置提交写入文件头，
+    /// 接收方的仓库中必须已经有这个提交。
+    ///
+    /// # 参数
+    /// * `since` - 起点，可以是提交 ID、分支或标签等任何 Git 能解析的引用，为 `None` 时打包完整历史
+    ///
+    /// # 返回值
+    /// * `Result<Vec<u8>, Error>` - 成功时返回 bundle 文件内容
+    /// * 失败时返回 `Err(Error)`，如仓库中还没有任何提交，或起点之后没有新的提交
+    pub fn bundle(&self, since: Option<&str>) -> Result<Vec<u8>, Error> {
+        let head = self
+            .repo
+            .head()
+            .map_err(Error::FindReference)?
+            .peel_to_commit()
+            .map_err(Error::FindReference)?
+            .id();
+
+        let mut walk = self.repo.revwalk().map_err(Error::RevWalk)?;
+        walk.push(head).map_err(Error::RevWalk)?;
+        let mut header = format!("{}\n", SIGNATURE_V2);
+        if let Some(since) = since {
+            let base = self
+                .repo
+                .revparse_single(since)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(Error::FindReference)?;
+            if base.id() == head
+                || self
+                    .repo
+                    .graph_descendant_of(base.id(), head)
+                    .map_err(Error::RevWalk)?
+            {
+                return Err(Error::EmptyBundle);
+            }
+            walk.hide(base.id()).map_err(Error::RevWalk)?;
+            header.push_str(&format!(
+                "-{} {}\n",
+                base.id(),
+                base.summary().unwrap_or("")
+            ));
+        }
+
+        let mut builder = self.repo.packbuilder().map_err(Error::Bundle)?;
+        builder.insert_walk(&mut walk).map_err(Error::Bundle)?;
+        let mut pack = git2::Buf::new();
+        builder.write_buf(&mut pack).map_err(Error::Bundle)?;
+
+        header.push_str(&format!("{} refs/heads/master\n{} HEAD\n\n", head, head));
+        let mut data = header.into_bytes();
+        data.extend_from_slice(&pack);
+        Ok(data)
+    }
+
+    /// 将当前分支的历史写入 bundle 文件
+    ///
+    /// # 参数
+    /// * `path` - bundle 文件的保存路径
+    /// * `since` - 起点，为 `None` 时写入完整历史
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error)`
+    pub fn create_bundle(&self, path: &Path, since: Option<&str>) -> Result<(), Error> {
+        let data = self.bundle(since)?;
+        fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// 将 bundle 中的对象写入仓库
+    ///
+    /// 只写入对象，不修改任何引用。
+    ///
+    /// # 参数
+    /// * `data` - bundle 文件内容
+    ///
+    /// # 返回值
+    /// * `Result<git2::Oid, Error>` - 成功时返回 bundle 中主分支指向的提交
+    /// * 失败时返回 `Err(Error)`，如格式错误或仓库中缺少前置提交
+    fn unbundle(&self, data: &[u8]) -> Result<git2::Oid, Error> {
+        let bundle = Bundle::parse(data)?;
+        let head = bundle
+            .head()
+            .ok_or_else(|| Error::InvalidBundle("没有包含任何引用".to_string()))?;
+
+        let odb = self.repo.odb().map_err(Error::Bundle)?;
+        if let Some(missing) = bundle.prerequisites.iter().find(|oid| !odb.exists(**oid)) {
+            return Err(Error::InvalidBundle(format!("缺少前置提交: {}", missing)));
+        }
+
+        let mut writer = odb.packwriter().map_err(Error::Bundle)?;
+        writer.write_all(bundle.pack)?;
+        writer.commit().map_err(Error::Bundle)?;
+
+        self.repo.find_commit(head).map_err(Error::Bundle)?;
+        Ok(head)
+    }
+
+    /// 用 bundle 中的历史恢复仓库
+    ///
+    /// 主分支指向 bundle 中的提交，暂存区重置为该提交，工作区的文件保持不变。
+    /// 用于从只包含工作区和 bundle 的归档中恢复知识库。
+    ///
+    /// # 参数
+    /// * `path` - bundle 文件路径
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error)`
+    pub fn restore_bundle(&self, path: &Path) -> Result<(), Error> {
+        let data = fs::read(path)?;
+        let head = self.unbundle(&data)?;
+
+        self.repo
+            .reference("refs/heads/master", head, true, "restore from bundle")
+            .map_err(Error::Bundle)?;
+        self.repo
+            .set_head("refs/heads/master")
+            .map_err(Error::Bundle)?;
+        let commit = self.repo.find_commit(head).map_err(Error::Bundle)?;
+        self.repo
+            .reset(commit.as_object(), git2::ResetType::Mixed, None)
+            .map_err(Error::Bundle)?;
+        Ok(())
+    }
+
+    /// 从 bundle 文件同步（无冲突情况下）
+    ///
+    /// 与 `sync` 使用相同的流程，只是用 bundle 代替远程仓库：
+    /// 先提交本地修改，再读取 bundle 中的提交并合并到本地分支。
+    /// bundle 不会被推送到任何地方，Git LFS 对象也不会上传。
+    ///
+    /// # 参数
+    /// * `path` - bundle 文件路径
+    ///
+    /// # 返回值
+    /// * `Result<bool, Error>` - 本地分支被更新时返回 `Ok(true)`，已是最新时返回 `Ok(false)`
+    /// * 失败时返回 `Err(Error)`，如缺少前置提交或需要手动合并
+    pub fn sync_bundle(&mut self, path: &Path) -> Result<bool, Error> {
+        // 1. 检查并提交本地修改
+        if self.has_uncommitted_changes()? {
+            self.add_all()?;
+            self.commit("自动提交")?;
+        }
+
+        // 2. 读取 bundle 中的提交
+        let data = fs::read(path)?;
+        let head = self.unbundle(&data)?;
+        let fetch_commit = self
+            .repo
+            .find_annotated_commit(head)
+            .map_err(Error::FindReference)?;
+
+        // 3. 合并（无冲突情况）
+        self.merge_commit(&fetch_commit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试 bundle 的生成与恢复
+    ///
+    /// # 测试目标：
+    /// * 验证生成的 bundle 可以被解析，并包含主分支和 `HEAD`
+    /// * 验证从 bundle 恢复的仓库历史完整且工作区没有未提交的修改
+    /// * 验证格式错误的 bundle 被拒绝
+    #[test]
+    fn test_bundle_roundtrip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("source");
+        let mut repo =
+            Repository::init(&source, Some("tester"), Some("tester@example.com")).unwrap();
+        fs::write(source.join("a.md"), "a").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("first").unwrap();
+        fs::write(source.join("b.md"), "b").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("second").unwrap();
+
+        let bundle_path = temp_dir.path().join("wiki.bundle");
+        repo.create_bundle(&bundle_path, None).unwrap();
+        let data = fs::read(&bundle_path).unwrap();
+        let bundle = Bundle::parse(&data).unwrap();
+        let names: Vec<&str> = bundle.references.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["refs/heads/master", "HEAD"]);
+
+        let target = temp_dir.path().join("target");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("a.md"), "a").unwrap();
+        fs::write(target.join("b.md"), "b").unwrap();
+        let restored = Repository::init(&target, None, None).unwrap();
+        restored.restore_bundle(&bundle_path).unwrap();
+        assert_eq!(restored.ahead_count().unwrap(), 2);
+        assert!(!restored.has_uncommitted_changes().unwrap());
+
+        assert!(matches!(
+            Bundle::parse(b"# v9 git bundle\n\nPACK"),
+            Err(Error::InvalidBundle(_))
+        ));
+    }
+
+    /// 测试基于 bundle 的增量同步
+    ///
+    /// # 测试目标：
+    /// * 验证增量 bundle 只包含起点之后的提交，并以起点作为前置提交
+    /// * 验证同步后本地分支快速前进到 bundle 中的提交，工作区被更新
+    /// * 验证缺少前置提交时同步失败，起点之后没有提交时拒绝生成 bundle
+    #[test]
+    fn test_sync_bundle() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source = temp_dir.path().join("source");
+        let mut repo =
+            Repository::init(&source, Some("tester"), Some("tester@example.com")).unwrap();
+        fs::write(source.join("a.md"), "a").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("first").unwrap();
+
+        // 接收方通过完整 bundle 获得第一次提交
+        let full = temp_dir.path().join("full.bundle");
+        repo.create_bundle(&full, None).unwrap();
+        let target = temp_dir.path().join("target");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("a.md"), "a").unwrap();
+        let mut other =
+            Repository::init(&target, Some("tester"), Some("tester@example.com")).unwrap();
+        other.restore_bundle(&full).unwrap();
+
+        let first = repo.repo.head().unwrap().target().unwrap().to_string();
+        assert!(matches!(repo.bundle(Some(&first)), Err(Error::EmptyBundle)));
+
+        fs::write(source.join("b.md"), "b").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("second").unwrap();
+        let incremental = temp_dir.path().join("incremental.bundle");
+        repo.create_bundle(&incremental, Some(&first)).unwrap();
+        let data = fs::read(&incremental).unwrap();
+        let bundle = Bundle::parse(&data).unwrap();
+        assert_eq!(
+            bundle.prerequisites,
+            vec![git2::Oid::from_str(&first).unwrap()]
+        );
+
+        assert!(other.sync_bundle(&incremental).unwrap());
+        assert_eq!(fs::read_to_string(target.join("b.md")).unwrap(), "b");
+        assert!(!other.sync_bundle(&incremental).unwrap());
+
+        let empty = temp_dir.path().join("empty");
+        let mut empty_repo = Repository::init(&empty, None, None).unwrap();
+        assert!(matches!(
+            empty_repo.sync_bundle(&incremental),
+            Err(Error::InvalidBundle(_))
+        ));
+    }
+}
@@ -0,0 +1,723 @@
+//! Git LFS 支持
+//!
+//! 被 `.gitattributes` 标记为 `filter=lfs` 的文件在仓库中只保存一个指针文件，
+//! 真正的内容保存在 `.git/lfs/objects` 中，并通过 LFS 服务器的 HTTP 批量接口上传和下载。
+//!
+//! `git2` 不会运行 LFS 过滤器，因此本模块在暂存时把这些文件转换为指针（clean），
+//! 在内容已下载时把工作区中的指针替换为真正的内容（smudge），
+//! 并在判断工作区是否有修改时忽略这种差异。
+
+use super::{Error, Repository};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// LFS 指针文件的版本标识
+pub const POINTER_VERSION: &str = "https://git-lfs.github.com/spec/v1";
+
+/// LFS 批量接口使用的媒体类型
+const MEDIA_TYPE: &str = "application/vnd.git-lfs+json";
+
+/// 指针文件的最大长度，超过该长度的文件不会被当作指针解析
+const MAX_POINTER_SIZE: u64 = 1024;
+
+/// LFS 指针
+///
+/// # 字段
+/// * `oid` - 内容的 SHA-256 哈希（十六进制）
+/// * `size` - 内容的大小（字节）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pointer {
+    pub oid: String,
+    pub size: u64,
+}
+
+impl Pointer {
+    /// 计算内容对应的指针
+    pub fn from_content(content: &[u8]) -> Self {
+        Self {
+            oid: crate::wiki::content::sha256_hex(content),
+            size: content.len() as u64,
+        }
+    }
+
+    /// 解析指针文件的内容，内容不是有效的指针时返回 `None`
+    pub fn parse(content: &[u8]) -> Option<Self> {
+        if content.len() as u64 > MAX_POINTER_SIZE {
+            return None;
+        }
+        let text = std::str::from_utf8(content).ok()?;
+        let mut lines = text.lines();
+        if lines.next()?.strip_prefix("version ")? != POINTER_VERSION {
+            return None;
+        }
+
+        let mut oid = None;
+        let mut size = None;
+        for line in lines {
+            if let Some(value) = line.strip_prefix("oid sha256:") {
+                oid = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("size ") {
+                size = value.parse().ok();
+            }
+        }
+
+        let oid = oid.filter(|o| is_valid_oid(o))?;
+        Some(Self { oid, size: size? })
+    }
+
+    /// 生成指针文件的内容
+    pub fn encode(&self) -> String {
+        format!(
+            "version {}\noid sha256:{}\nsize {}\n",
+            POINTER_VERSION, self.oid, self.size
+        )
+    }
+}
+
+/// 判断对象标识是否为合法的 SHA-256 哈希（64 个十六进制字符）
+///
+/// 对象标识会被用于拼接本地对象库中的路径，不合法的标识可能指向对象库之外的文件。
+pub fn is_valid_oid(oid: &str) -> bool {
+    oid.len() == 64 && oid.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// 读取工作区中的指针文件
+///
+/// # 参数
+/// * `path` - 文件的完整路径
+///
+/// # 返回值
+/// * `Option<Pointer>` - 文件是尚未下载内容的 LFS 指针时返回指针，否则返回 `None`
+pub fn read_pointer_file(path: &Path) -> Option<Pointer> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_POINTER_SIZE {
+        return None;
+    }
+    Pointer::parse(&fs::read(path).ok()?)
+}
+
+impl Repository {
+    /// 判断路径是否由 Git LFS 管理（`.gitattributes` 中设置了 `filter=lfs`）
+    ///
+    /// # 参数
+    /// * `path` - 相对于工作区根目录的路径
+    pub fn is_lfs_tracked(&self, path: &Path) -> bool {
+        matches!(
+            self.repo
+                .get_attr(path, "filter", git2::AttrCheckFlags::default()),
+            Ok(Some("lfs"))
+        )
+    }
+
+    /// 获取 LFS 对象在本地对象库中的路径
+    ///
+    /// # 返回值
+    /// * `Result<PathBuf, Error>` - 成功时返回对象文件的路径
+    /// * 对象标识不是 64 个十六进制字符时返回 `Err(Error::Lfs)`
+    pub fn lfs_object_path(&self, oid: &str) -> Result<PathBuf, Error> {
+        if !is_valid_oid(oid) {
+            return Err(Error::Lfs(format!("无效的 LFS 对象标识: {}", oid)));
+        }
+        Ok(self
+            .git_dir()
+            .join("lfs/objects")
+            .join(&oid[0..2])
+            .join(&oid[2..4])
+            .join(oid))
+    }
+
+    /// 判断 LFS 对象是否已保存在本地对象库中
+    fn has_lfs_object(&self, oid: &str) -> bool {
+        self.lfs_object_path(oid)
+            .map(|path| path.exists())
+            .unwrap_or(false)
+    }
+
+    /// 将内容保存到本地 LFS 对象库
+    ///
+    /// # 返回值
+    /// * `Result<Pointer, Error>` - 成功时返回内容对应的指针
+    pub fn store_lfs_object(&self, content: &[u8]) -> Result<Pointer, Error> {
+        let pointer = Pointer::from_content(content);
+        let path = self.lfs_object_path(&pointer.oid)?;
+        if !path.exists() {
+            let dir = path.parent().unwrap_or(self.git_dir());
+            fs::create_dir_all(dir).map_err(|e| Error::Lfs(e.to_string()))?;
+            // 先写入临时文件再重命名，避免留下不完整的对象；
+            // 临时文件名包含进程号和时间，同时保存同一对象的写入不会互相覆盖
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            let temp = dir.join(format!(
+                "{}.{}-{}.tmp",
+                pointer.oid,
+                std::process::id(),
+                nanos
+            ));
+            let result = fs::write(&temp, content).and_then(|_| fs::rename(&temp, &path));
+            if let Err(e) = result {
+                let _ = fs::remove_file(&temp);
+                // 并发的写入已经保存了相同的对象
+                if !path.exists() {
+                    return Err(Error::Lfs(e.to_string()));
+                }
+            }
+        }
+        Ok(pointer)
+    }
+
+    /// 将暂存区中由 LFS 管理、但保存的是原始内容的文件替换为指针
+    ///
+    /// 原始内容会被保存到本地 LFS 对象库，等待同步时上传。
+    pub(super) fn lfs_clean_index(&self, index: &mut git2::Index) -> Result<(), Error> {
+        let entries: Vec<git2::IndexEntry> = index.iter().collect();
+        for mut entry in entries {
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            if !self.is_lfs_tracked(Path::new(&path)) {
+                continue;
+            }
+
+            let blob = self.repo.find_blob(entry.id).map_err(Error::AddToIndex)?;
+            if Pointer::parse(blob.content()).is_some() {
+                continue;
+            }
+
+            let pointer = self.store_lfs_object(blob.content())?;
+            let encoded = pointer.encode();
+            entry.id = self
+                .repo
+                .blob(encoded.as_bytes())
+                .map_err(Error::AddToIndex)?;
+            entry.file_size = encoded.len() as u32;
+            index.add(&entry).map_err(Error::AddToIndex)?;
+        }
+        Ok(())
+    }
+
+    /// 判断工作区中的文件与暂存区的差异是否只是 LFS 内容已下载
+    ///
+    /// # 参数
+    /// * `path` - 相对于工作区根目录、使用 `/` 分隔的路径
+    pub(super) fn is_lfs_smudged(&self, path: &str) -> bool {
+        if !self.is_lfs_tracked(Path::new(path)) {
+            return false;
+        }
+        let Some(staged) = self.staged_pointer(path) else {
+            return false;
+        };
+        let Some(workdir) = self.repo.workdir() else {
+            return false;
+        };
+
+        // 先比较大小，避免读取无关的大文件
+        match fs::metadata(workdir.join(path)) {
+            Ok(metadata) if metadata.len() == staged.size => {}
+            _ => return false,
+        }
+        fs::read(workdir.join(path))
+            .map(|content| Pointer::from_content(&content) == staged)
+            .unwrap_or(false)
+    }
+
+    /// 获取暂存区中保存为指针的文件
+    fn staged_pointer(&self, path: &str) -> Option<Pointer> {
+        let index = self.repo.index().ok()?;
+        let entry = index.get_path(Path::new(path), 0)?;
+        let blob = self.repo.find_blob(entry.id).ok()?;
+        Pointer::parse(blob.content())
+    }
+
+    /// 列出暂存区中所有由 LFS 管理的指针文件
+    ///
+    /// # 返回值
+    /// * `Result<Vec<(String, Pointer)>, Error>` - 成功时返回文件路径和对应的指针
+    pub fn lfs_pointers(&self) -> Result<Vec<(String, Pointer)>, Error> {
+        let index = self.repo.index().map_err(Error::AddToIndex)?;
+        let mut pointers = Vec::new();
+        for entry in index.iter() {
+            // 指针文件很小，跳过大文件可以避免读取无关的内容
+            if entry.file_size as u64 > MAX_POINTER_SIZE {
+                continue;
+            }
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            if !self.is_lfs_tracked(Path::new(&path)) {
+                continue;
+            }
+            if let Some(pointer) = self
+                .repo
+                .find_blob(entry.id)
+                .ok()
+                .and_then(|blob| Pointer::parse(blob.content()))
+            {
+                pointers.push((path, pointer));
+            }
+        }
+        Ok(pointers)
+    }
+
+    /// 将工作区中内容已在本地对象库中的指针文件替换为真正的内容
+    ///
+    /// # 返回值
+    /// * `Result<Vec<String>, Error>` - 成功时返回被替换的文件路径
+    pub fn lfs_checkout(&self) -> Result<Vec<String>, Error> {
+        let Some(workdir) = self.repo.workdir().map(Path::to_path_buf) else {
+            return Ok(Vec::new());
+        };
+
+        let mut replaced = Vec::new();
+        for (path, pointer) in self.lfs_pointers()? {
+            let target = workdir.join(&path);
+            if read_pointer_file(&target).as_ref() != Some(&pointer) {
+                continue;
+            }
+            let object = self.lfs_object_path(&pointer.oid)?;
+            if !object.exists() {
+                continue;
+            }
+            fs::copy(&object, &target).map_err(|e| Error::Lfs(e.to_string()))?;
+            replaced.push(path);
+        }
+        Ok(replaced)
+    }
+
+    /// 获取 LFS 服务器的地址
+    ///
+    /// 优先使用 Git 配置 `lfs.url`，否则根据 `origin` 远程仓库的 HTTP 地址推导，
+    /// 例如 `https://example.com/team/wiki.git` 对应 `https://example.com/team/wiki.git/info/lfs`。
+    ///
+    /// # 返回值
+    /// * `Result<Option<String>, Error>` - 成功时返回服务器地址，无法确定时返回 `None`
+    pub fn lfs_endpoint(&self) -> Result<Option<String>, Error> {
+        let config = self.repo.config().map_err(Error::Config)?;
+        if let Ok(url) = config.get_string("lfs.url") {
+            return Ok(Some(url.trim_end_matches('/').to_string()));
+        }
+
+        let Some(url) = self.get_remote_url("origin")? else {
+            return Ok(None);
+        };
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Ok(None);
+        }
+
+        let url = url.trim_end_matches('/');
+        Ok(Some(if url.ends_with(".git") {
+            format!("{}/info/lfs", url)
+        } else {
+            format!("{}.git/info/lfs", url)
+        }))
+    }
+}
+
+/// 批量接口请求
+#[derive(Serialize)]
+struct BatchRequest<'a> {
+    operation: &'a str,
+    transfers: [&'a str; 1],
+    objects: &'a [Pointer],
+}
+
+/// 批量接口响应
+#[derive(Deserialize)]
+struct BatchResponse {
+    #[serde(default)]
+    objects: Vec<BatchObject>,
+}
+
+/// 批量接口响应中的单个对象
+#[derive(Deserialize)]
+struct BatchObject {
+    oid: String,
+    size: u64,
+    #[serde(default)]
+    actions: HashMap<String, Action>,
+    error: Option<ObjectError>,
+}
+
+/// 对象的传输操作
+#[derive(Deserialize)]
+struct Action {
+    href: String,
+    #[serde(default)]
+    header: HashMap<String, String>,
+}
+
+/// 对象级别的错误
+#[derive(Deserialize)]
+struct ObjectError {
+    code: i64,
+    message: String,
+}
+
+/// LFS 服务器客户端，使用批量接口和 `basic` 传输方式
+pub struct LfsClient {
+    /// 服务器地址，批量接口为 `<endpoint>/objects/batch`
+    endpoint: String,
+    /// HTTP 基本认证使用的用户名和密码
+    credentials: Option<(String, String)>,
+}
+
+impl LfsClient {
+    /// 创建 LFS 客户端
+    ///
+    /// # 参数
+    /// * `endpoint` - 服务器地址
+    /// * `credentials` - HTTP 基本认证使用的用户名和密码
+    pub fn new(endpoint: &str, credentials: Option<(String, String)>) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            credentials,
+        }
+    }
+
+    /// 为仓库创建 LFS 客户端，使用应用配置中保存的 Git 凭据
+    ///
+    /// # 返回值
+    /// * `Result<Option<Self>, Error>` - 成功时返回客户端，无法确定服务器地址时返回 `None`
+    pub fn for_repository(repo: &Repository) -> Result<Option<Self>, Error> {
+        let credentials = crate::config::AppConfig::load().ok().and_then(|config| {
+            let credentials = config.git_credentials;
+            credentials
+                .password
+                .map(|password| (credentials.username, password))
+        });
+        Ok(repo
+            .lfs_endpoint()?
+            .map(|endpoint| Self::new(&endpoint, credentials)))
+    }
+
+    /// 下载工作区中尚未下载的 LFS 对象，并替换对应的指针文件
+    ///
+    /// # 返回值
+    /// * `Result<Vec<String>, Error>` - 成功时返回内容被下载到工作区的文件路径
+    pub fn download(&self, repo: &Repository) -> Result<Vec<String>, Error> {
+        let mut missing: Vec<Pointer> = repo
+            .lfs_pointers()?
+            .into_iter()
+            .map(|(_, pointer)| pointer)
+            .filter(|pointer| !repo.has_lfs_object(&pointer.oid))
+            .collect();
+        missing.sort_by(|a, b| a.oid.cmp(&b.oid));
+        missing.dedup();
+
+        if !missing.is_empty() {
+            for object in self.batch("download", &missing)? {
+                let action = object.actions.get("download").ok_or_else(|| {
+                    Error::Lfs(format!("服务器没有提供对象 {} 的下载地址", object.oid))
+                })?;
+
+                let mut response = self
+                    .prepare(ureq::get(&action.href), &action.header)
+                    .call()
+                    .map_err(|e| Error::Lfs(e.to_string()))?;
+                let mut content = Vec::new();
+                response
+                    .body_mut()
+                    .as_reader()
+                    .read_to_end(&mut content)
+                    .map_err(|e| Error::Lfs(e.to_string()))?;
+
+                // 校验下载内容，避免把错误的内容写入工作区
+                let pointer = Pointer::from_content(&content);
+                if pointer.oid != object.oid || pointer.size != object.size {
+                    return Err(Error::Lfs(format!("对象 {} 的内容校验失败", object.oid)));
+                }
+                repo.store_lfs_object(&content)?;
+            }
+        }
+
+        repo.lfs_checkout()
+    }
+
+    /// 将本地对象库中的 LFS 对象上传到服务器
+    ///
+    /// 服务器已有的对象不会重复上传。只会上传请求中列出的本地对象，
+    /// 服务器响应中的对象标识与请求不符时返回错误，避免读取并上传对象库之外的文件。
+    ///
+    /// # 返回值
+    /// * `Result<usize, Error>` - 成功时返回实际上传的对象数量
+    pub fn upload(&self, repo: &Repository) -> Result<usize, Error> {
+        let mut local: Vec<Pointer> = repo
+            .lfs_pointers()?
+            .into_iter()
+            .map(|(_, pointer)| pointer)
+            .filter(|pointer| repo.has_lfs_object(&pointer.oid))
+            .collect();
+        local.sort_by(|a, b| a.oid.cmp(&b.oid));
+        local.dedup();
+        if local.is_empty() {
+            return Ok(0);
+        }
+
+        let mut uploaded = 0;
+        for object in self.batch("upload", &local)? {
+            // 没有上传操作表示服务器已有该对象
+            let Some(action) = object.actions.get("upload") else {
+                continue;
+            };
+
+            // 只使用请求中已校验过的指针，不信任服务器返回的对象标识
+            let pointer = local
+                .iter()
+                .find(|pointer| pointer.oid == object.oid && pointer.size == object.size)
+                .ok_or_else(|| {
+                    Error::Lfs(format!("服务器返回了未请求上传的对象: {}", object.oid))
+                })?;
+            let content = fs::read(repo.lfs_object_path(&pointer.oid)?)
+                .map_err(|e| Error::Lfs(e.to_string()))?;
+            self.prepare(ureq::put(&action.href), &action.header)
+                .header("Content-Type", "application/octet-stream")
+                .send(&content[..])
+                .map_err(|e| Error::Lfs(e.to_string()))?;
+
+            if let Some(verify) = object.actions.get("verify") {
+                let body = serde_json::to_vec(pointer).map_err(|e| Error::Lfs(e.to_string()))?;
+                self.prepare(ureq::post(&verify.href), &verify.header)
+                    .header("Content-Type", MEDIA_TYPE)
+                    .send(&body[..])
+                    .map_err(|e| Error::Lfs(e.to_string()))?;
+            }
+            uploaded += 1;
+        }
+        Ok(uploaded)
+    }
+
+    /// 调用批量接口，返回服务器对每个对象的响应
+    fn batch(&self, operation: &str, objects: &[Pointer]) -> Result<Vec<BatchObject>, Error> {
+        let body = serde_json::to_vec(&BatchRequest {
+            operation,
+            transfers: ["basic"],
+            objects,
+        })
+        .map_err(|e| Error::Lfs(e.to_string()))?;
+
+        let url = format!("{}/objects/batch", self.endpoint);
+        let response: BatchResponse = self
+            .prepare(ureq::post(&url), &HashMap::new())
+            .header("Content-Type", MEDIA_TYPE)
+            .send(&body[..])
+            .and_then(|mut response| response.body_mut().read_json())
+            .map_err(|e| Error::Lfs(e.to_string()))?;
+
+        if let Some((oid, error)) = response
+            .objects
+            .iter()
+            .find_map(|o| o.error.as_ref().map(|e| (&o.oid, e)))
+        {
+            return Err(Error::Lfs(format!(
+                "对象 {} 传输失败（{}）: {}",
+                oid, error.code, error.message
+            )));
+        }
+        Ok(response.objects)
+    }
+
+    /// 为请求添加认证信息和操作指定的请求头
+    ///
+    /// 服务器在操作中提供了 `Authorization` 请求头时，不再使用保存的凭据。
+    fn prepare<B>(
+        &self,
+        mut request: ureq::RequestBuilder<B>,
+        headers: &HashMap<String, String>,
+    ) -> ureq::RequestBuilder<B> {
+        request = request.header("Accept", MEDIA_TYPE);
+        let has_authorization = headers
+            .keys()
+            .any(|k| k.eq_ignore_ascii_case("authorization"));
+        if let (Some((username, password)), false) = (&self.credentials, has_authorization) {
+            let token = STANDARD.encode(format!("{}:{}", username, password));
+            request = request.header("Authorization", format!("Basic {}", token));
+        }
+        for (key, value) in headers {
+            request = request.header(key.as_str(), value.as_str());
+        }
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    /// 服务器桩保存的对象，键为对象的哈希
+    type ObjectStore = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+    /// 在本地启动一个 LFS 服务器桩，返回服务器地址和服务器保存的对象
+    fn start_stub_server() -> (String, ObjectStore) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let objects = Arc::new(Mutex::new(HashMap::new()));
+
+        let store = objects.clone();
+        let base = endpoint.clone();
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = Vec::new();
+                request.as_reader().read_to_end(&mut body).unwrap();
+                let url = request.url().to_string();
+                let oid = url.rsplit('/').next().unwrap_or("").to_string();
+
+                let response = match request.method() {
+                    tiny_http::Method::Post if url == "/objects/batch" => {
+                        let batch: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                        let store = store.lock().unwrap();
+                        let objects: Vec<serde_json::Value> = batch["objects"]
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|object| {
+                                let oid = object["oid"].as_str().unwrap();
+                                let href = format!("{}/objects/{}", base, oid);
+                                let actions =
+                                    match (batch["operation"].as_str(), store.contains_key(oid)) {
+                                        (Some("download"), true) => {
+                                            json!({ "download": { "href": href } })
+                                        }
+                                        (Some("upload"), false) => json!({
+                                            "upload": { "href": href, "header": { "Authorization": "Bearer token" } }
+                                        }),
+                                        _ => json!({}),
+                                    };
+                                json!({ "oid": oid, "size": object["size"], "actions": actions })
+                            })
+                            .collect();
+                        tiny_http::Response::from_data(
+                            serde_json::to_vec(&json!({ "objects": objects })).unwrap(),
+                        )
+                    }
+                    tiny_http::Method::Put => {
+                        store.lock().unwrap().insert(oid, body);
+                        tiny_http::Response::from_data(Vec::new())
+                    }
+                    tiny_http::Method::Get => match store.lock().unwrap().get(&oid) {
+                        Some(data) => tiny_http::Response::from_data(data.clone()),
+                        None => tiny_http::Response::from_data(Vec::new()).with_status_code(404),
+                    },
+                    _ => tiny_http::Response::from_data(Vec::new()).with_status_code(405),
+                };
+                let _ = request.respond(response);
+            }
+        });
+
+        (endpoint, objects)
+    }
+
+    /// 测试 LFS 文件的提交、上传和下载
+    ///
+    /// # 测试目标：
+    /// * 验证由 LFS 管理的文件只以指针形式提交，内容保存在本地对象库中
+    /// * 验证内容已下载的文件不被视为未提交的修改
+    /// * 验证对象能通过批量接口上传到服务器，服务器已有的对象不重复上传
+    /// * 验证工作区中的指针文件能被识别为未下载，并在下载后替换为真正的内容
+    #[test]
+    fn test_lfs_commit_upload_and_download() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let mut repo = Repository::init(root, Some("test"), Some("test@example.com")).unwrap();
+        fs::write(
+            root.join(".gitattributes"),
+            "*.png filter=lfs diff=lfs merge=lfs -text\n",
+        )
+        .unwrap();
+        let image: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        fs::write(root.join("image.png"), &image).unwrap();
+        fs::write(root.join("page.md"), "![](image.png)").unwrap();
+
+        repo.add_all().unwrap();
+        repo.commit("add image").unwrap();
+
+        // 提交中只有指针
+        let tree = repo.repo.head().unwrap().peel_to_tree().unwrap();
+        let blob = tree
+            .get_path(Path::new("image.png"))
+            .unwrap()
+            .to_object(&repo.repo)
+            .unwrap()
+            .peel_to_blob()
+            .unwrap();
+        let pointer = Pointer::parse(blob.content()).unwrap();
+        assert_eq!(pointer, Pointer::from_content(&image));
+        assert!(repo.has_lfs_object(&pointer.oid));
+        assert!(!repo.has_uncommitted_changes().unwrap());
+
+        // 上传
+        let (endpoint, server_objects) = start_stub_server();
+        repo.set_config("lfs.url", &endpoint).unwrap();
+        let client = LfsClient::for_repository(&repo).unwrap().unwrap();
+        assert_eq!(client.upload(&repo).unwrap(), 1);
+        assert_eq!(server_objects.lock().unwrap()[&pointer.oid], image);
+        assert_eq!(client.upload(&repo).unwrap(), 0);
+
+        // 模拟新克隆的仓库：本地没有对象，工作区中是指针文件
+        fs::remove_dir_all(repo.git_dir().join("lfs")).unwrap();
+        fs::write(root.join("image.png"), pointer.encode()).unwrap();
+        assert_eq!(read_pointer_file(&root.join("image.png")), Some(pointer));
+        assert!(read_pointer_file(&root.join("page.md")).is_none());
+
+        assert_eq!(client.download(&repo).unwrap(), vec!["image.png"]);
+        assert_eq!(fs::read(root.join("image.png")).unwrap(), image);
+        assert!(!repo.has_uncommitted_changes().unwrap());
+    }
+
+    /// 测试服务器返回不合法或未请求的对象标识
+    ///
+    /// # 测试目标：
+    /// * 验证对象标识不是 64 个十六进制字符时无法得到对象库中的路径，且不会崩溃
+    /// * 验证服务器在上传响应中返回其他对象标识（如本地文件的绝对路径）时拒绝上传，
+    ///   不会读取或发送对象库之外的任何文件
+    #[test]
+    fn test_upload_rejects_bogus_oid() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        let mut repo = Repository::init(&root, Some("test"), Some("test@example.com")).unwrap();
+        for oid in ["ab", "/etc/passwd", "中中", &"../".repeat(22)] {
+            assert!(repo.lfs_object_path(oid).is_err());
+        }
+
+        fs::write(root.join(".gitattributes"), "*.png filter=lfs -text\n").unwrap();
+        fs::write(root.join("image.png"), "image").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("add image").unwrap();
+        let secret = temp_dir.path().join("secret.txt");
+        fs::write(&secret, "secret").unwrap();
+
+        // 服务器桩在批量接口中返回本地文件的路径作为对象标识，并记录收到的上传内容
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let uploads = Arc::new(Mutex::new(Vec::new()));
+        let received = uploads.clone();
+        let href = format!("{}/objects/upload", endpoint);
+        let bogus_oid = secret.to_string_lossy().to_string();
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = Vec::new();
+                request.as_reader().read_to_end(&mut body).unwrap();
+                let response = if request.url() == "/objects/batch" {
+                    json!({ "objects": [{
+                        "oid": bogus_oid,
+                        "size": 6,
+                        "actions": { "upload": { "href": href } }
+                    }] })
+                } else {
+                    received.lock().unwrap().push(body);
+                    json!({})
+                };
+                let _ = request.respond(tiny_http::Response::from_data(
+                    serde_json::to_vec(&response).unwrap(),
+                ));
+            }
+        });
+
+        let client = LfsClient::new(&endpoint, None);
+        assert!(matches!(client.upload(&repo), Err(Error::Lfs(_))));
+        assert!(uploads.lock().unwrap().is_empty());
+    }
+}
@@ -3,7 +3,10 @@
 //! 此模块封装了 Git 仓库的基本操作。
 //! 它基于 `git2` 库提供的功能，为上层应用提供更简洁的 Git 操作接口。
 
-use std::path::Path;
+pub mod bundle;
+pub mod lfs;
+
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Git 操作可能出现的错误类型
@@ -73,6 +76,10 @@ pub enum Error {
     #[error("There are uncommitted changes")]
     UncommittedChanges,
 
+    /// 遍历提交历史失败
+    #[error("Failed to walk history: {0}")]
+    RevWalk(#[source] git2::Error),
+
     /// 没有配置远程仓库
     #[error("No remote repository configured")]
     NoRemote,
@@ -84,6 +91,83 @@ pub enum Error {
     /// 空提交
     #[error("Nothing to commit")]
     NothingToCommit,
+
+    /// Git LFS 操作失败
+    #[error("Git LFS error: {0}")]
+    Lfs(String),
+
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 指定的起点之后没有新的提交，无法生成 bundle
+    #[error("No commits to bundle")]
+    EmptyBundle,
+
+    /// 读写 bundle 失败
+    #[error("Failed to process bundle: {0}")]
+    Bundle(#[source] git2::Error),
+
+    /// bundle 文件格式错误或无法应用到当前仓库
+    #[error("Invalid bundle: {0}")]
+    InvalidBundle(String),
+}
+
+/// 判断远程仓库地址是否指向本地目录
+///
+/// `file://` 地址、绝对路径以及 Windows 网络共享路径（`\\server\share`）都视为本地目录，
+/// 这类远程仓库不需要用户名和密码。
+///
+/// # 参数
+/// * `url` - 远程仓库地址
+///
+/// # 返回值
+/// * `bool` - 是否为本地目录
+pub fn is_local_url(url: &str) -> bool {
+    url.starts_with("file://") || url.starts_with("\\\\") || Path::new(url).is_absolute()
+}
+
+/// 获取本地远程仓库地址对应的目录
+///
+/// # 参数
+/// * `url` - 远程仓库地址
+///
+/// # 返回值
+/// * `Option<PathBuf>` - 本地目录路径，网络地址返回 `None`
+pub fn local_path(url: &str) -> Option<PathBuf> {
+    if !is_local_url(url) {
+        return None;
+    }
+    let path = url.strip_prefix("file://").unwrap_or(url);
+    // Windows 上的 `file:///C:/wiki` 对应 `C:/wiki`
+    let path = match path.strip_prefix('/') {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest,
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// 创建访问远程仓库时使用的回调
+///
+/// 网络地址使用应用配置中保存的凭据，本地目录不需要凭据。
+fn remote_callbacks(url: &str) -> git2::RemoteCallbacks<'static> {
+    let mut callbacks = git2::RemoteCallbacks::new();
+
+    // 尝试加载保存的凭据
+    if !is_local_url(url) {
+        if let Ok(config) = crate::config::AppConfig::load() {
+            let username = config.git_credentials.username.clone();
+            let password = config.git_credentials.password.clone();
+
+            callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
+                git2::Cred::userpass_plaintext(&username, password.as_deref().unwrap_or(""))
+            });
+        }
+    }
+
+    // 在所有平台上添加证书验证处理
+    callbacks.certificate_check(|_cert, _host| Ok(git2::CertificateCheckStatus::CertificateOk));
+    callbacks
 }
 
 /// Git 仓库的封装结构体
@@ -235,6 +319,29 @@ impl Repository {
         }
     }
 
+    /// 获取仓库的 Git 目录（通常为工作区下的 `.git` 目录）
+    pub fn git_dir(&self) -> &Path {
+        self.repo.path()
+    }
+
+    /// 判断路径是否被忽略规则（`.gitignore` 等）忽略
+    ///
+    /// # 参数
+    /// * `path` - 相对于工作区根目录的路径，目录应以 `/` 结尾
+    pub fn is_path_ignored(&self, path: &Path) -> Result<bool, Error> {
+        self.repo.is_path_ignored(path).map_err(Error::Status)
+    }
+
+    /// 为当前打开的仓库实例添加临时的忽略规则
+    ///
+    /// 规则只在内存中生效，不会写入 `.gitignore` 或其他文件。
+    ///
+    /// # 参数
+    /// * `rules` - 忽略规则，多条规则之间使用换行分隔，语法与 `.gitignore` 相同
+    pub fn add_ignore_rules(&self, rules: &str) -> Result<(), Error> {
+        self.repo.add_ignore_rule(rules).map_err(Error::Config)
+    }
+
     /// 检查仓库是否配置了远程仓库
     ///
     /// # 返回值
@@ -246,9 +353,37 @@ impl Repository {
     }
 
     /// 检查是否有未提交的修改
+    ///
+    /// 由 Git LFS 管理的文件在内容下载后与暂存区中的指针不同，这种差异不视为修改。
     pub fn has_uncommitted_changes(&self) -> Result<bool, Error> {
         let statuses = self.repo.statuses(None).map_err(Error::Status)?;
-        Ok(!statuses.is_empty())
+        Ok(statuses.iter().any(|entry| {
+            entry.status() != git2::Status::WT_MODIFIED
+                || !entry.path().is_some_and(|path| self.is_lfs_smudged(path))
+        }))
+    }
+
+    /// 统计本地尚未推送到远程的提交数量
+    ///
+    /// 以 `refs/remotes/origin/master` 作为远程分支的位置，统计本地 `HEAD` 领先它的提交数。
+    /// 如果从未与远程同步过（或没有配置远程仓库），本地的所有提交都视为未推送。
+    ///
+    /// # 返回值
+    /// * `Result<usize, Error>` - 成功时返回未推送的提交数量，仓库还没有任何提交时返回 0
+    /// * 失败时返回具体的错误信息
+    pub fn ahead_count(&self) -> Result<usize, Error> {
+        // 仓库还没有任何提交
+        let Some(head) = self.repo.head().ok().and_then(|head| head.target()) else {
+            return Ok(0);
+        };
+
+        let mut revwalk = self.repo.revwalk().map_err(Error::RevWalk)?;
+        revwalk.push(head).map_err(Error::RevWalk)?;
+        if let Ok(upstream) = self.repo.refname_to_id("refs/remotes/origin/master") {
+            revwalk.hide(upstream).map_err(Error::RevWalk)?;
+        }
+
+        Ok(revwalk.count())
     }
 
     /// 获取用户配置（用户名和邮箱）
@@ -303,10 +438,61 @@ impl Repository {
         index
             .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
             .map_err(Error::AddToIndex)?;
+        // 由 Git LFS 管理的文件只提交指针
+        self.lfs_clean_index(&mut index)?;
         index.write().map_err(Error::AddToIndex)?;
         Ok(())
     }
 
+    /// 检查文件在工作区或暂存区中是否有未提交的修改（包括尚未跟踪的新文件）
+    ///
+    /// # 参数
+    /// * `path` - 相对于工作区根目录、使用 `/` 分隔的路径
+    pub fn is_path_modified(&self, path: &str) -> Result<bool, Error> {
+        let status = self
+            .repo
+            .status_file(Path::new(path))
+            .map_err(Error::Status)?;
+        Ok(!(status.is_empty() || status == git2::Status::IGNORED))
+    }
+
+    /// 将指定文件的修改添加到暂存区，用于只提交这些文件
+    ///
+    /// # 参数
+    /// * `paths` - 相对于工作区根目录、使用 `/` 分隔的路径
+    pub fn add_paths(&mut self, paths: &[String]) -> Result<(), Error> {
+        let mut index = self.repo.index().map_err(Error::AddToIndex)?;
+        for path in paths {
+            index.add_path(Path::new(path)).map_err(Error::AddToIndex)?;
+        }
+        self.lfs_clean_index(&mut index)?;
+        index.write().map_err(Error::AddToIndex)?;
+        Ok(())
+    }
+
+    /// 将已删除的文件从暂存区中移除，用于只提交这些删除
+    ///
+    /// # 参数
+    /// * `paths` - 相对于工作区根目录、使用 `/` 分隔的路径，未被跟踪的路径会被跳过
+    ///
+    /// # 返回值
+    /// * `Result<usize, Error>` - 成功时返回实际从暂存区移除的文件数量
+    /// * 失败时返回具体的错误信息
+    pub fn remove_from_index(&mut self, paths: &[String]) -> Result<usize, Error> {
+        let mut index = self.repo.index().map_err(Error::AddToIndex)?;
+        let mut removed = 0;
+        for path in paths {
+            if index.get_path(Path::new(path), 0).is_some() {
+                index
+                    .remove_path(Path::new(path))
+                    .map_err(Error::AddToIndex)?;
+                removed += 1;
+            }
+        }
+        index.write().map_err(Error::AddToIndex)?;
+        Ok(removed)
+    }
+
     /// 提交暂存区的修改
     pub fn commit(&self, message: &str) -> Result<(), Error> {
         // 检查是否有暂存的内容
@@ -366,23 +552,8 @@ impl Repository {
             .map_err(|_| Error::NoRemote)?;
 
         // 设置fetch选项
-        let mut callbacks = git2::RemoteCallbacks::new();
-
-        // 尝试加载保存的凭据
-        if let Ok(config) = crate::config::AppConfig::load() {
-            let username = config.git_credentials.username.clone();
-            let password = config.git_credentials.password.clone();
-
-            callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
-                git2::Cred::userpass_plaintext(&username, password.as_deref().unwrap_or(""))
-            });
-        }
-
-        // 在所有平台上添加证书验证处理
-        callbacks.certificate_check(|_cert, _host| Ok(git2::CertificateCheckStatus::CertificateOk));
-
         let mut fetch_opts = git2::FetchOptions::new();
-        fetch_opts.remote_callbacks(callbacks);
+        fetch_opts.remote_callbacks(remote_callbacks(remote.url().unwrap_or("")));
 
         // 执行fetch
         remote
@@ -395,19 +566,39 @@ impl Repository {
     /// 合并远程分支到本地分支（无冲突版本）
     pub fn merge(&self) -> Result<bool, Error> {
         // 获取远程分支引用
-        let fetch_head = self
-            .repo
-            .find_reference("FETCH_HEAD")
-            .map_err(Error::FindReference)?;
+        let fetch_head = match self.repo.find_reference("FETCH_HEAD") {
+            Ok(reference) => reference,
+            // 远程仓库还没有主分支（例如新建的空裸仓库）时 FETCH_HEAD 为空，无需合并
+            Err(_)
+                if std::fs::read(self.repo.path().join("FETCH_HEAD"))
+                    .is_ok_and(|content| content.iter().all(u8::is_ascii_whitespace)) =>
+            {
+                return Ok(false);
+            }
+            Err(e) => return Err(Error::FindReference(e)),
+        };
         let fetch_commit = self
             .repo
             .reference_to_annotated_commit(&fetch_head)
             .map_err(Error::FindReference)?;
 
+        self.merge_commit(&fetch_commit)
+    }
+
+    /// 将指定提交合并到本地分支（无冲突版本）
+    ///
+    /// 只支持快速前进合并，需要真正合并时返回 `Error::MergeConflict`。
+    ///
+    /// # 参数
+    /// * `fetch_commit` - 要合并的提交
+    ///
+    /// # 返回值
+    /// * `Result<bool, Error>` - 本地分支被更新时返回 `Ok(true)`，已是最新时返回 `Ok(false)`
+    fn merge_commit(&self, fetch_commit: &git2::AnnotatedCommit) -> Result<bool, Error> {
         // 分析合并
         let analysis = self
             .repo
-            .merge_analysis(&[&fetch_commit])
+            .merge_analysis(&[fetch_commit])
             .map_err(Error::Merge)?;
 
         // 处理不同的合并情况
@@ -428,6 +619,8 @@ impl Repository {
             self.repo
                 .checkout_head(Some(git2::build::CheckoutBuilder::default().force()))
                 .map_err(Error::Merge)?;
+            // 检出会写入 LFS 指针，恢复本地已有的内容
+            self.lfs_checkout()?;
             return Ok(true);
         } else if analysis.0.is_normal() {
             // 正常合并（无冲突情况）
@@ -447,23 +640,8 @@ impl Repository {
             .map_err(|_| Error::NoRemote)?;
 
         // 设置推送选项
-        let mut callbacks = git2::RemoteCallbacks::new();
-
-        // 尝试加载保存的凭据
-        if let Ok(config) = crate::config::AppConfig::load() {
-            let username = config.git_credentials.username.clone();
-            let password = config.git_credentials.password.clone();
-
-            callbacks.credentials(move |_url, _username_from_url, _allowed_types| {
-                git2::Cred::userpass_plaintext(&username, password.as_deref().unwrap_or(""))
-            });
-        }
-
-        // 在所有平台上添加证书验证处理
-        callbacks.certificate_check(|_cert, _host| Ok(git2::CertificateCheckStatus::CertificateOk));
-
         let mut push_opts = git2::PushOptions::new();
-        push_opts.remote_callbacks(callbacks);
+        push_opts.remote_callbacks(remote_callbacks(remote.url().unwrap_or("")));
 
         // 执行推送
         remote
@@ -475,6 +653,65 @@ impl Repository {
 
         Ok(())
     }
+
+    /// 在指定路径创建知识库的裸仓库镜像
+    ///
+    /// 镜像包含本地主分支的完整历史，可以放在本地磁盘或挂载的网络共享上，
+    /// 作为其他机器的远程仓库使用。推送失败时会删除已创建的裸仓库。
+    ///
+    /// # 参数
+    /// * `path` - 镜像路径，必须不存在或是空目录
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error)`，如路径不是空目录或本地仓库还没有任何提交
+    pub fn create_mirror(&self, path: &Path) -> Result<(), Error> {
+        if path
+            .read_dir()
+            .is_ok_and(|mut entries| entries.next().is_some())
+        {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("镜像路径不是空目录: {}", path.display()),
+            )));
+        }
+        let existed = path.exists();
+        git2::Repository::init_bare(path).map_err(Error::InitRepository)?;
+
+        let url = path.to_string_lossy();
+        let result = self
+            .repo
+            .remote_anonymous(&url)
+            .map_err(Error::Remote)
+            .and_then(|mut remote| {
+                let mut push_opts = git2::PushOptions::new();
+                push_opts.remote_callbacks(remote_callbacks(&url));
+                remote
+                    .push(
+                        &["refs/heads/master:refs/heads/master"],
+                        Some(&mut push_opts),
+                    )
+                    .map_err(Error::Push)
+            });
+
+        if result.is_err() {
+            // 删除创建的裸仓库，原本存在的空目录保留
+            if existed {
+                for entry in std::fs::read_dir(path)?.flatten() {
+                    let entry_path = entry.path();
+                    let _ = if entry_path.is_dir() {
+                        std::fs::remove_dir_all(&entry_path)
+                    } else {
+                        std::fs::remove_file(&entry_path)
+                    };
+                }
+            } else {
+                let _ = std::fs::remove_dir_all(path);
+            }
+        }
+        result
+    }
+
     /// 检查是否有名为 "origin" 的远程仓库
     pub fn has_remote(&self) -> Result<bool, Error> {
         match self.repo.find_remote("origin") {
@@ -564,9 +801,107 @@ impl Repository {
             Err(e) => return Err(e),
         }
 
-        // 5. 推送本地修改
+        // 5. 上传 Git LFS 对象（推送引用前服务器上必须已有这些对象）
+        if let Some(client) = lfs::LfsClient::for_repository(self)? {
+            client.upload(self)?;
+        }
+
+        // 6. 推送本地修改
         self.push()?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试 `ahead_count` 函数
+    ///
+    /// # 测试目标：
+    /// * 验证空仓库的未推送提交数为 0
+    /// * 验证未与远程同步时所有本地提交都视为未推送
+    /// * 验证推送后未推送提交数归零
+    #[test]
+    fn test_ahead_count() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("wiki");
+        let mut repo = Repository::init(&path, Some("tester"), Some("tester@example.com")).unwrap();
+        assert_eq!(repo.ahead_count().unwrap(), 0);
+
+        std::fs::write(path.join("a.md"), "a").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("first").unwrap();
+        std::fs::write(path.join("b.md"), "b").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("second").unwrap();
+        assert_eq!(repo.ahead_count().unwrap(), 2);
+
+        // 模拟已推送到远程的状态
+        let head = repo.repo.head().unwrap().target().unwrap();
+        repo.repo
+            .reference("refs/remotes/origin/master", head, true, "test")
+            .unwrap();
+        assert_eq!(repo.ahead_count().unwrap(), 0);
+    }
+
+    /// 测试以本地裸仓库作为远程仓库的同步
+    ///
+    /// # 测试目标：
+    /// * 验证 `create_mirror` 创建的裸仓库可以被克隆
+    /// * 验证 `sync` 可以通过本地路径和 `file://` 地址完成 fetch、merge 与 push
+    /// * 验证空的裸仓库也可以作为远程仓库接收第一次推送
+    /// * 验证推送失败时 `create_mirror` 删除已创建的裸仓库
+    #[test]
+    fn test_local_remote_sync() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let first = temp_dir.path().join("first");
+        let mut repo =
+            Repository::init(&first, Some("tester"), Some("tester@example.com")).unwrap();
+        std::fs::write(first.join("a.md"), "a").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("first").unwrap();
+
+        let mirror = temp_dir.path().join("mirror.git");
+        repo.create_mirror(&mirror).unwrap();
+        assert!(repo.create_mirror(&mirror).is_err());
+        let mirror_url = mirror.to_string_lossy().to_string();
+        assert!(is_local_url(&mirror_url));
+        assert_eq!(local_path(&mirror_url), Some(mirror.clone()));
+
+        // 第二台机器克隆镜像，修改后同步
+        let second = temp_dir.path().join("second");
+        let mut other = Repository::clone(&mirror_url, &second, None, None, None).unwrap();
+        other.set_user_config("other", "other@example.com").unwrap();
+        std::fs::write(second.join("b.md"), "b").unwrap();
+        other.sync().unwrap();
+
+        // 第一台机器通过 file:// 地址同步，快速前进到第二台机器的提交
+        repo.set_remote("origin", &format!("file://{}", mirror_url))
+            .unwrap();
+        repo.sync().unwrap();
+        assert_eq!(std::fs::read_to_string(first.join("b.md")).unwrap(), "b");
+        assert_eq!(repo.ahead_count().unwrap(), 0);
+
+        // 空的裸仓库
+        let empty = temp_dir.path().join("empty.git");
+        git2::Repository::init_bare(&empty).unwrap();
+        repo.set_remote("origin", &empty.to_string_lossy()).unwrap();
+        repo.sync().unwrap();
+        let pushed = git2::Repository::open_bare(&empty).unwrap();
+        assert!(pushed.find_reference("refs/heads/master").is_ok());
+
+        // 没有任何提交时推送失败，已创建的裸仓库被删除，原本存在的空目录保留
+        let blank = Repository::init(&temp_dir.path().join("blank"), None, None).unwrap();
+        let failed = temp_dir.path().join("failed.git");
+        assert!(blank.create_mirror(&failed).is_err());
+        assert!(!failed.exists());
+        std::fs::create_dir(&failed).unwrap();
+        assert!(blank.create_mirror(&failed).is_err());
+        assert_eq!(std::fs::read_dir(&failed).unwrap().count(), 0);
+
+        assert!(!is_local_url("https://example.com/wiki.git"));
+        assert_eq!(local_path("git@example.com:wiki.git"), None);
+    }
+}
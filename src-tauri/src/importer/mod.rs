@@ -0,0 +1,516 @@
+//! 导入模块
+//!
+//! 此模块把其他工具中的笔记导入为新的知识库，支持以下来源：
+//! * Obsidian 仓库：跳过 `.obsidian` 等隐藏目录，`[[页面]]`、`![[图片]]` 双链转换为标准 Markdown 链接
+//! * Notion 导出的 Markdown：去除文件名和目录名末尾的 32 位页面 ID，并更新指向它们的链接
+//! * 普通的 Markdown 文件夹
+//!
+//! 导入时文件名中不适合作为文件名或会破坏链接语法的字符会被替换，`.markdown` 后缀统一为 `.md`，
+//! 重名的文件会加上序号。页面中指向被重命名文件的链接会同步更新，附件按新的路径复制。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::wiki::link::{is_external, percent_encode, relative_link, resolve_link};
+use crate::wiki::{self, is_page};
+
+/// 导入操作可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 知识库操作错误
+    #[error("Wiki error: {0}")]
+    Wiki(#[from] wiki::Error),
+
+    /// 导入来源不存在或不是文件夹
+    #[error("Source folder not found: {0}")]
+    SourceNotFound(String),
+}
+
+/// 导入来源
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    /// 普通的 Markdown 文件夹
+    #[default]
+    Folder,
+    /// Obsidian 仓库
+    Obsidian,
+    /// Notion 导出的 Markdown
+    Notion,
+}
+
+/// 导入结果
+///
+/// # 字段
+/// * `pages` - 导入的页面数量
+/// * `attachments` - 复制的附件数量
+/// * `renamed` - 名称被规范化的文件，键为原路径，值为新路径
+/// * `unresolved_links` - 无法解析的双链，格式为 `页面: 链接目标`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub pages: usize,
+    pub attachments: usize,
+    pub renamed: BTreeMap<String, String>,
+    pub unresolved_links: Vec<String>,
+}
+
+/// 将文件夹中的笔记导入到知识库目录
+///
+/// # 参数
+/// * `source_dir` - 导入来源文件夹
+/// * `source` - 导入来源类型
+/// * `target_root` - 知识库根目录
+///
+/// # 返回值
+/// * `Result<ImportReport, Error>` - 成功时返回导入结果
+/// * 失败时返回 `Err(Error)`
+pub fn import_folder(
+    source_dir: &Path,
+    source: ImportSource,
+    target_root: &Path,
+) -> Result<ImportReport, Error> {
+    if !source_dir.is_dir() {
+        return Err(Error::SourceNotFound(source_dir.display().to_string()));
+    }
+
+    // 原路径到新路径的映射
+    let mut paths = BTreeMap::new();
+    let mut taken = HashSet::new();
+    plan_dir(source_dir, "", "", source, &mut paths, &mut taken)?;
+
+    let mut report = ImportReport::default();
+    for (old, new) in &paths {
+        if old != new {
+            report.renamed.insert(old.clone(), new.clone());
+        }
+
+        let target = target_root.join(new);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // 无法作为文本读取的页面按附件原样复制
+        let content = if is_page(new) {
+            fs::read_to_string(source_dir.join(old)).ok()
+        } else {
+            None
+        };
+        match content {
+            Some(content) => {
+                let rewritten = rewrite_links(&content, old, new, &paths, &mut report);
+                fs::write(&target, rewritten)?;
+                report.pages += 1;
+            }
+            None => {
+                fs::copy(source_dir.join(old), &target)?;
+                report.attachments += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// 遍历来源文件夹，为每个文件计算规范化后的新路径
+fn plan_dir(
+    dir: &Path,
+    old_prefix: &str,
+    new_prefix: &str,
+    source: ImportSource,
+    paths: &mut BTreeMap<String, String>,
+    taken: &mut HashSet<String>,
+) -> Result<(), Error> {
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file_type = entry.file_type()?;
+        // 跳过隐藏文件（如 .obsidian、.git）和符号链接
+        if name.starts_with('.') || file_type.is_symlink() {
+            continue;
+        }
+
+        let is_dir = file_type.is_dir();
+        let old = join(old_prefix, &name);
+        let new = unique_path(
+            new_prefix,
+            &normalize_name(&name, is_dir, source),
+            is_dir,
+            taken,
+        );
+
+        if is_dir {
+            plan_dir(&entry.path(), &old, &new, source, paths, taken)?;
+        } else {
+            paths.insert(old, new);
+        }
+    }
+    Ok(())
+}
+
+/// 规范化文件名或目录名
+///
+/// Notion 导出的名称末尾的页面 ID 会被去除，不适合出现在文件名或链接中的字符替换为 `-`，
+/// 连续的空白合并为一个空格，`.markdown` 后缀统一为 `.md`。
+fn normalize_name(name: &str, is_dir: bool, source: ImportSource) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !is_dir && !stem.is_empty() => {
+            (stem, Some(extension.to_ascii_lowercase()))
+        }
+        _ => (name, None),
+    };
+
+    let stem = match source {
+        ImportSource::Notion => strip_notion_id(stem.trim()),
+        _ => stem.trim(),
+    };
+    let stem: String = stem
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' | '\\' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    let stem = stem.split_whitespace().collect::<Vec<_>>().join(" ");
+    let stem = stem.trim_start_matches('.').trim_end_matches(['.', ' ']);
+    let stem = if stem.is_empty() { "untitled" } else { stem };
+
+    match extension.as_deref() {
+        Some("markdown") => format!("{}.md", stem),
+        Some(extension) => format!("{}.{}", stem, extension),
+        None => stem.to_string(),
+    }
+}
+
+/// 去除 Notion 名称末尾以空格分隔的 32 位十六进制页面 ID
+fn strip_notion_id(stem: &str) -> &str {
+    match stem.rsplit_once(' ') {
+        Some((name, id))
+            if id.len() == 32 && id.chars().all(|c| c.is_ascii_hexdigit()) && !name.is_empty() =>
+        {
+            name.trim_end()
+        }
+        _ => stem,
+    }
+}
+
+/// 在目录中选择不重名的路径，文件系统可能不区分大小写，因此按小写比较
+fn unique_path(prefix: &str, name: &str, is_dir: bool, taken: &mut HashSet<String>) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !is_dir => (stem, format!(".{}", extension)),
+        _ => (name, String::new()),
+    };
+
+    let mut candidate = join(prefix, name);
+    let mut n = 2;
+    while !taken.insert(candidate.to_lowercase()) {
+        candidate = join(prefix, &format!("{} {}{}", stem, n, extension));
+        n += 1;
+    }
+    candidate
+}
+
+/// 拼接相对路径
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
+}
+
+/// 改写页面中的链接，代码块中的内容保持不变
+///
+/// # 参数
+/// * `content` - 页面内容
+/// * `old_page` - 页面在来源文件夹中的路径
+/// * `new_page` - 页面在知识库中的路径
+/// * `paths` - 原路径到新路径的映射
+/// * `report` - 导入结果，用于记录无法解析的双链
+fn rewrite_links(
+    content: &str,
+    old_page: &str,
+    new_page: &str,
+    paths: &BTreeMap<String, String>,
+    report: &mut ImportReport,
+) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut fence: Option<&str> = None;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            out.push_str(line);
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            out.push_str(line);
+            continue;
+        }
+        rewrite_line(line, old_page, new_page, paths, report, &mut out);
+    }
+    out
+}
+
+/// 改写一行中的链接
+fn rewrite_line(
+    line: &str,
+    old_page: &str,
+    new_page: &str,
+    paths: &BTreeMap<String, String>,
+    report: &mut ImportReport,
+    out: &mut String,
+) {
+    let mut rest = line;
+    while let Some(pos) = rest.find(['[', ']', '`']) {
+        let after = &rest[pos..];
+
+        // 行内代码原样保留
+        if let Some(code) = after.strip_prefix('`') {
+            let end = code.find('`').map(|i| i + 2).unwrap_or(after.len());
+            out.push_str(&rest[..pos + end]);
+            rest = &after[end..];
+            continue;
+        }
+
+        // 双链 [[页面]]、[[页面|别名]]、[[页面#标题]] 以及嵌入 ![[图片]]
+        if let Some(inner) = after.strip_prefix("[[") {
+            if let Some(end) = inner.find("]]") {
+                let embed = rest[..pos].ends_with('!');
+                let prefix = if embed {
+                    &rest[..pos - 1]
+                } else {
+                    &rest[..pos]
+                };
+                out.push_str(prefix);
+                match convert_wikilink(&inner[..end], embed, old_page, new_page, paths) {
+                    Some(link) => out.push_str(&link),
+                    None => {
+                        report
+                            .unresolved_links
+                            .push(format!("{}: {}", new_page, &inner[..end]));
+                        out.push_str(&rest[prefix.len()..pos + 2 + end + 2]);
+                    }
+                }
+                rest = &inner[end + 2..];
+                continue;
+            }
+        }
+
+        // 标准链接 [文本](目标)
+        if let Some(inner) = after.strip_prefix("](") {
+            out.push_str(&rest[..pos + 2]);
+            let (start, target) = match inner.strip_prefix('<') {
+                Some(angled) => (1, angled.split('>').next().unwrap_or("")),
+                None => (0, inner.split([' ', ')']).next().unwrap_or("")),
+            };
+            match convert_link(target, old_page, new_page, paths) {
+                Some(link) => {
+                    out.push_str(&inner[..start]);
+                    out.push_str(&link);
+                    rest = &inner[start + target.len()..];
+                }
+                None => rest = inner,
+            }
+            continue;
+        }
+
+        out.push_str(&rest[..pos + 1]);
+        rest = &after[1..];
+    }
+    out.push_str(rest);
+}
+
+/// 将标准链接的目标改为新的相对路径，目标未被重命名或移动时返回 `None`
+fn convert_link(
+    target: &str,
+    old_page: &str,
+    new_page: &str,
+    paths: &BTreeMap<String, String>,
+) -> Option<String> {
+    if target.is_empty() || is_external(target) {
+        return None;
+    }
+    let new_target = paths.get(&resolve_link(old_page, target)?)?;
+    let fragment = target.find('#').map(|i| &target[i..]).unwrap_or("");
+    let link = format!("{}{}", relative_link(new_page, new_target), fragment);
+    (link != target).then_some(link)
+}
+
+/// 将双链转换为标准 Markdown 链接，无法解析时返回 `None`
+///
+/// 与 Obsidian 相同，双链可以只写文件名，同名文件有多个时优先选择与页面在同一目录、路径最短的文件。
+fn convert_wikilink(
+    inner: &str,
+    embed: bool,
+    old_page: &str,
+    new_page: &str,
+    paths: &BTreeMap<String, String>,
+) -> Option<String> {
+    let (target, alias) = match inner.split_once('|') {
+        Some((target, alias)) => (target.trim(), Some(alias.trim())),
+        None => (inner.trim(), None),
+    };
+    let (name, heading) = match target.split_once('#') {
+        Some((name, heading)) => (name.trim(), Some(heading.trim())),
+        None => (target, None),
+    };
+
+    let old_target = if name.is_empty() {
+        // [[#标题]] 指向页面自身
+        old_page.to_string()
+    } else {
+        find_wikilink_target(name, old_page, paths)?
+    };
+    let new_target = &paths[&old_target];
+
+    let mut url = if old_target == old_page {
+        String::new()
+    } else {
+        relative_link(new_page, new_target)
+    };
+    if let Some(heading) = heading {
+        url.push('#');
+        url.push_str(&percent_encode(heading));
+    }
+
+    let display = alias.unwrap_or(target);
+    if embed && !is_page(new_target) {
+        // 图片嵌入的别名是尺寸（如 ![[a.png|300]]），不作为说明文字
+        let stem = name.rsplit('/').next().unwrap_or(name);
+        let alt = stem.rsplit_once('.').map(|(s, _)| s).unwrap_or(stem);
+        Some(format!("![{}]({})", alt, url))
+    } else {
+        Some(format!("[{}]({})", display, url))
+    }
+}
+
+/// 按 Obsidian 的规则查找双链指向的文件，返回其在来源文件夹中的路径
+fn find_wikilink_target(
+    name: &str,
+    old_page: &str,
+    paths: &BTreeMap<String, String>,
+) -> Option<String> {
+    let name = name.trim_start_matches('/');
+    let with_ext = format!("{}.md", name);
+    let page_dir = old_page.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+    paths
+        .keys()
+        .filter(|path| {
+            *path == name
+                || **path == with_ext
+                || path.ends_with(&format!("/{}", name))
+                || path.ends_with(&format!("/{}", with_ext))
+        })
+        .min_by_key(|path| {
+            let dir = path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+            (dir != page_dir, path.len())
+        })
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试文件名规范化
+    ///
+    /// # 测试目标：
+    /// * 验证 Notion 页面 ID 被去除，非法字符被替换，`.markdown` 后缀被统一
+    /// * 验证重名文件会加上序号
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(
+            normalize_name(
+                "会议记录 0123456789abcdef0123456789ABCDEF.md",
+                false,
+                ImportSource::Notion
+            ),
+            "会议记录.md"
+        );
+        assert_eq!(
+            normalize_name("Q&A: 常见问题?.markdown", false, ImportSource::Folder),
+            "Q&A- 常见问题-.md"
+        );
+        assert_eq!(
+            normalize_name(" 目录  名. ", true, ImportSource::Folder),
+            "目录 名"
+        );
+
+        let mut taken = HashSet::new();
+        assert_eq!(unique_path("a", "b.md", false, &mut taken), "a/b.md");
+        assert_eq!(unique_path("a", "B.md", false, &mut taken), "a/B 2.md");
+    }
+
+    /// 测试导入 Obsidian 仓库与 Notion 导出
+    ///
+    /// # 测试目标：
+    /// * 验证双链与嵌入被转换为标准链接，隐藏目录被跳过
+    /// * 验证 Notion 文件名中的 ID 被去除，指向它们的链接被同步更新
+    /// * 验证代码块中的内容与无法解析的双链保持不变
+    #[test]
+    fn test_import_folder() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let vault = temp_dir.path().join("vault");
+        fs::create_dir_all(vault.join(".obsidian")).unwrap();
+        fs::create_dir_all(vault.join("notes/img")).unwrap();
+        fs::write(vault.join(".obsidian/app.json"), "{}").unwrap();
+        fs::write(
+            vault.join("Home.md"),
+            "见 [[Guide|指南]] 和 [[Guide#安装 步骤]]\n![[logo.png|200]] [[缺失]]\n```\n[[Guide]]\n```\n",
+        )
+        .unwrap();
+        fs::write(vault.join("notes/Guide.md"), "[[Home]] `[[Home]]`").unwrap();
+        fs::write(vault.join("notes/img/logo.png"), [1u8]).unwrap();
+
+        let wiki = temp_dir.path().join("wiki");
+        let report = import_folder(&vault, ImportSource::Obsidian, &wiki).unwrap();
+        assert_eq!((report.pages, report.attachments), (2, 1));
+        assert!(!wiki.join(".obsidian").exists());
+        assert_eq!(report.unresolved_links, vec!["Home.md: 缺失"]);
+
+        let home = fs::read_to_string(wiki.join("Home.md")).unwrap();
+        assert_eq!(
+            home,
+            "见 [指南](notes/Guide.md) 和 [Guide#安装 步骤](notes/Guide.md#安装%20步骤)\n\
+             ![logo](notes/img/logo.png) [[缺失]]\n```\n[[Guide]]\n```\n"
+        );
+        assert_eq!(
+            fs::read_to_string(wiki.join("notes/Guide.md")).unwrap(),
+            "[Home](../Home.md) `[[Home]]`"
+        );
+
+        let export = temp_dir.path().join("notion");
+        let id = "0123456789abcdef0123456789abcdef";
+        fs::create_dir_all(export.join(format!("项目 {}", id))).unwrap();
+        fs::write(
+            export.join(format!("项目 {}.md", id)),
+            format!("[计划](%E9%A1%B9%E7%9B%AE%20{id}/%E8%AE%A1%E5%88%92%20{id}.md)"),
+        )
+        .unwrap();
+        fs::write(export.join(format!("项目 {}/计划 {}.md", id, id)), "# 计划").unwrap();
+
+        let wiki = temp_dir.path().join("wiki2");
+        let report = import_folder(&export, ImportSource::Notion, &wiki).unwrap();
+        assert_eq!(report.renamed.len(), 2);
+        assert_eq!(
+            fs::read_to_string(wiki.join("项目.md")).unwrap(),
+            "[计划](项目/计划.md)"
+        );
+        assert!(wiki.join("项目/计划.md").exists());
+    }
+}
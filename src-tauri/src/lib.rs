@@ -1,34 +1,56 @@
 // src/lib.rs
-mod command;
-mod config;
-mod git;
-mod wiki;
+// 模块对外公开，供 `markwiki-cli` 等二进制程序复用；
+// 依赖 Tauri 的命令和应用入口只在启用 `gui` 特性时编译
+pub mod archive;
+pub mod attachment;
+#[cfg(feature = "gui")]
+pub mod command;
+pub mod config;
+pub mod draft;
+pub mod export;
+pub mod git;
+pub mod importer;
+pub mod server;
+pub mod service;
+pub mod trash;
+pub mod watcher;
+pub mod wiki;
 
 // 导入命令
+#[cfg(feature = "gui")]
 use command::*;
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 修复 Linux 下的白屏问题
     #[cfg(target_os = "linux")]
     std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
 
-    let builder = tauri::Builder::default();
+    let builder = tauri::Builder::default()
+        .manage(watcher::WatcherRegistry::default())
+        .manage(server::ServerRegistry::default())
+        .setup(|app| {
+            use tauri::Manager;
 
-    #[cfg(debug_assertions)]
-    let builder = builder.setup(|app| {
-        use tauri::Manager;
-        if let Some(window) = app.get_webview_window("main") {
-            window.open_devtools();
-        }
-        Ok(())
-    });
+            // 使用平台的应用数据目录保存配置和知识库
+            if let Ok(data_dir) = app.path().app_data_dir() {
+                config::set_data_dir(data_dir);
+            }
+
+            #[cfg(debug_assertions)]
+            if let Some(window) = app.get_webview_window("main") {
+                window.open_devtools();
+            }
+            Ok(())
+        });
 
     builder
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
             // Wiki 命令
             get_wiki_file_structure,
+            get_wiki_directory,
             get_wiki_list,
             create_local_wiki,
             create_remote_wiki,
@@ -43,7 +65,50 @@ pub fn run() {
             git_set_user_config,
             git_get_user_config,
             setup_remote_repo,
-            get_remote_repo_config
+            get_remote_repo_config,
+            get_inbound_links,
+            delete_item,
+            list_trash,
+            restore_item,
+            get_wiki_storage_dir,
+            set_wiki_storage_dir,
+            migrate_legacy_wikis,
+            register_wiki,
+            init_wiki_at_path,
+            unregister_wiki,
+            get_wiki_meta,
+            update_wiki_meta,
+            watch_wiki,
+            unwatch_wiki,
+            save_draft,
+            list_drafts,
+            restore_draft,
+            discard_draft,
+            import_attachment,
+            import_attachment_bytes,
+            read_attachment,
+            get_attachment_report,
+            delete_orphan_attachments,
+            lfs_fetch,
+            lfs_push,
+            list_templates,
+            search_wiki,
+            get_page_metadata,
+            update_page_metadata,
+            list_tags,
+            get_pages_for_tag,
+            rename_tag,
+            export_site,
+            export_document,
+            import_wiki,
+            export_wiki_archive,
+            import_wiki_archive,
+            git_create_bundle,
+            git_sync_bundle,
+            git_create_mirror,
+            start_wiki_server,
+            stop_wiki_server,
+            list_wiki_servers
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
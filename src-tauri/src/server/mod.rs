@@ -0,0 +1,880 @@
+//! 只读 HTTP 服务模块
+//!
+//! 此模块提供一个内嵌的 HTTP 服务，用于在局域网内临时分享知识库（例如会议中投屏或让同事查看）。
+//! 服务只读，不会修改知识库中的任何文件，访问的地址与静态网站导出的结构相同：
+//! * `/docs/guide.html` - 渲染后的页面，带有导航侧边栏和搜索框
+//! * `/docs/guide.md` - 页面的原始 Markdown
+//! * `/docs/img/a.png` - 附件，内容尚未下载的 Git LFS 文件返回 404
+//!   （HTML 和 SVG 附件只允许下载，脚本附件作为纯文本返回）
+//! * `/`、`/index.html` - 知识库根目录的 `index.md`，没有时为列出所有页面的首页
+//!
+//! 被隐藏的路径（`.git`、被 `.gitignore` 或知识库元数据忽略的文件）以及指向知识库之外的符号链接无法访问。
+//! 设置访问令牌后，请求需要通过 `?token=`、`Authorization: Bearer` 请求头或 Cookie 携带令牌，
+//! 通过 `?token=` 访问一次后浏览器会保存 Cookie，之后页面之间的跳转不需要再携带令牌。
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::SystemTime;
+use thiserror::Error;
+
+use crate::export::site::{
+    index_html, page_html, render_sidebar, rewrite_link, search_index_js, SEARCH_JS,
+};
+use crate::export::{html_path, mime_type, page_title, render_markdown, STYLE_CSS};
+use crate::wiki::link::percent_decode;
+use crate::wiki::{
+    build_file_tree_with, collect_files, is_page, normalize_relative_path, FileNode, TreeFilter,
+};
+
+/// 默认的监听地址，只允许本机访问
+pub const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:8080";
+
+/// 保存访问令牌的 Cookie 名称
+const TOKEN_COOKIE: &str = "markwiki_token";
+
+/// 所有响应的内容安全策略
+///
+/// 页面只加载本站的样式和脚本，Markdown 中的内联脚本和事件属性不会执行；
+/// 图片允许外部地址，与在编辑器中预览时一致。
+const CONTENT_SECURITY_POLICY: &str = "default-src 'self'; script-src 'self'; \
+     style-src 'self' 'unsafe-inline'; img-src 'self' data: https:; \
+     object-src 'none'; base-uri 'none'; form-action 'none'; frame-ancestors 'none'";
+
+/// HTTP 服务可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// 无法监听指定地址，例如地址格式错误或端口已被占用
+    #[error("Failed to bind {0}: {1}")]
+    Bind(String, String),
+}
+
+/// 运行中的服务信息
+///
+/// # 字段
+/// * `wiki_name` - 知识库名称
+/// * `address` - 实际监听的地址，监听端口 0 时为系统分配的端口
+/// * `token_required` - 是否需要访问令牌
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerInfo {
+    pub wiki_name: String,
+    pub address: String,
+    pub token_required: bool,
+}
+
+/// 服务启动选项
+///
+/// # 字段
+/// * `bind_address` - 监听地址，例如 `0.0.0.0:8080` 表示允许局域网内的其他机器访问
+/// * `token` - 访问令牌，为 `None` 时不需要令牌
+/// * `title` - 网站标题，显示在侧边栏和浏览器标签页上
+/// * `ignore_rules` - 额外的忽略规则（知识库元数据中的 `ignore` 字段）
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    pub bind_address: String,
+    pub token: Option<String>,
+    pub title: String,
+    pub ignore_rules: Vec<String>,
+}
+
+/// 一个知识库的只读 HTTP 服务
+///
+/// 服务在后台线程中处理请求，调用 `stop` 或被丢弃时停止。
+pub struct WikiServer {
+    server: Arc<tiny_http::Server>,
+    handle: Option<JoinHandle<()>>,
+    info: ServerInfo,
+}
+
+impl WikiServer {
+    /// 启动服务
+    ///
+    /// # 参数
+    /// * `wiki_name` - 知识库名称
+    /// * `root` - 知识库根目录
+    /// * `options` - 启动选项
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回运行中的服务
+    /// * 失败时返回 `Err(Error::Bind)`，表示无法监听指定地址
+    pub fn start(wiki_name: &str, root: &Path, options: ServerOptions) -> Result<Self, Error> {
+        let server = tiny_http::Server::http(options.bind_address.as_str())
+            .map_err(|e| Error::Bind(options.bind_address.clone(), e.to_string()))?;
+        let address = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.to_string())
+            .unwrap_or_else(|| options.bind_address.clone());
+        let server = Arc::new(server);
+
+        let info = ServerInfo {
+            wiki_name: wiki_name.to_string(),
+            address,
+            token_required: options.token.is_some(),
+        };
+        let site = Site::new(root, options);
+        let worker = server.clone();
+        let handle = std::thread::spawn(move || {
+            for request in worker.incoming_requests() {
+                let reply = catch_panic(|| {
+                    site.respond(request.method(), request.url(), request.headers())
+                });
+                if let Err(e) = request.respond(reply.into_response()) {
+                    eprintln!("发送响应失败: {}", e);
+                }
+            }
+        });
+
+        Ok(WikiServer {
+            server,
+            handle: Some(handle),
+            info,
+        })
+    }
+
+    /// 获取服务信息
+    pub fn info(&self) -> &ServerInfo {
+        &self.info
+    }
+
+    /// 停止服务，等待正在处理的请求完成
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for WikiServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// 所有运行中的服务
+///
+/// 该结构体作为应用的全局状态，按知识库名称管理服务，每个知识库同时只有一个服务。
+#[derive(Default)]
+pub struct ServerRegistry {
+    servers: Mutex<HashMap<String, WikiServer>>,
+}
+
+impl ServerRegistry {
+    /// 启动知识库的服务，如果该知识库已经有运行中的服务，会先停止旧的服务
+    ///
+    /// # 参数
+    /// * `wiki_name` - 知识库名称
+    /// * `root` - 知识库根目录
+    /// * `options` - 启动选项
+    ///
+    /// # 返回值
+    /// * `Result<ServerInfo, Error>` - 成功时返回服务信息
+    pub fn start(
+        &self,
+        wiki_name: &str,
+        root: &Path,
+        options: ServerOptions,
+    ) -> Result<ServerInfo, Error> {
+        let mut servers = self.servers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(old) = servers.remove(wiki_name) {
+            old.stop();
+        }
+        let server = WikiServer::start(wiki_name, root, options)?;
+        let info = server.info().clone();
+        servers.insert(wiki_name.to_string(), server);
+        Ok(info)
+    }
+
+    /// 停止知识库的服务
+    ///
+    /// # 返回值
+    /// * `bool` - 该知识库是否有运行中的服务
+    pub fn stop(&self, wiki_name: &str) -> bool {
+        let server = self
+            .servers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(wiki_name);
+        match server {
+            Some(server) => {
+                server.stop();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 列出所有运行中的服务
+    pub fn list(&self) -> Vec<ServerInfo> {
+        let servers = self.servers.lock().unwrap_or_else(|e| e.into_inner());
+        let mut list: Vec<ServerInfo> = servers.values().map(|s| s.info().clone()).collect();
+        list.sort_by(|a, b| a.wiki_name.cmp(&b.wiki_name));
+        list
+    }
+}
+
+/// 一次请求的响应
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+    set_cookie: Option<String>,
+    /// 是否要求浏览器下载而不是直接打开
+    download: bool,
+}
+
+impl Reply {
+    fn new(status: u16, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Reply {
+            status,
+            content_type,
+            body: body.into(),
+            set_cookie: None,
+            download: false,
+        }
+    }
+
+    fn text(status: u16, message: &str) -> Self {
+        Reply::new(status, "text/plain; charset=utf-8", message)
+    }
+
+    fn into_response(self) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+        let mut response = tiny_http::Response::from_data(self.body).with_status_code(self.status);
+        let headers = [
+            ("Content-Type", Some(self.content_type.to_string())),
+            ("X-Content-Type-Options", Some("nosniff".to_string())),
+            ("Cache-Control", Some("no-cache".to_string())),
+            (
+                "Content-Security-Policy",
+                Some(CONTENT_SECURITY_POLICY.to_string()),
+            ),
+            (
+                "Content-Disposition",
+                self.download.then(|| "attachment".to_string()),
+            ),
+            ("Set-Cookie", self.set_cookie),
+        ];
+        for (name, value) in headers {
+            if let Some(Ok(header)) =
+                value.map(|v| tiny_http::Header::from_bytes(name.as_bytes(), v.as_bytes()))
+            {
+                response.add_header(header);
+            }
+        }
+        response
+    }
+}
+
+/// 处理请求的知识库站点
+struct Site {
+    root: PathBuf,
+    /// 解析符号链接后的知识库根目录，用于判断文件是否位于知识库内
+    canonical_root: PathBuf,
+    options: ServerOptions,
+    /// 页面标题的缓存，键为页面相对路径
+    titles: Mutex<HashMap<String, CachedTitle>>,
+    /// 文件树的缓存
+    tree: Mutex<Option<CachedTree>>,
+    /// 搜索索引脚本的缓存
+    search_index: Mutex<Option<CachedSearchIndex>>,
+}
+
+/// 文件或目录的修改时间，键为路径
+type Stamps = Vec<(PathBuf, Option<SystemTime>)>;
+
+/// 缓存的文件树，目录结构和 `.gitignore` 文件都没有变化时直接使用
+struct CachedTree {
+    stamps: Stamps,
+    tree: Arc<FileNode>,
+}
+
+/// 缓存的搜索索引脚本，所有页面的修改时间和大小都不变时直接使用
+struct CachedSearchIndex {
+    pages: Vec<(String, Option<SystemTime>, u64)>,
+    script: String,
+}
+
+/// 缓存的页面标题，页面的修改时间和大小不变时直接使用
+struct CachedTitle {
+    modified: Option<SystemTime>,
+    size: u64,
+    title: String,
+}
+
+impl Site {
+    fn new(root: &Path, options: ServerOptions) -> Self {
+        Site {
+            root: root.to_path_buf(),
+            canonical_root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            options,
+            titles: Mutex::new(HashMap::new()),
+            tree: Mutex::new(None),
+            search_index: Mutex::new(None),
+        }
+    }
+
+    /// 处理一次请求
+    fn respond(
+        &self,
+        method: &tiny_http::Method,
+        url: &str,
+        headers: &[tiny_http::Header],
+    ) -> Reply {
+        if !matches!(method, tiny_http::Method::Get | tiny_http::Method::Head) {
+            return Reply::text(405, "只支持 GET 请求");
+        }
+
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let query_token = query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .map(percent_decode);
+
+        let mut set_cookie = None;
+        if let Some(token) = &self.options.token {
+            let header = |name: &str| {
+                headers
+                    .iter()
+                    .filter(|h| h.field.as_str().as_str().eq_ignore_ascii_case(name))
+                    .map(|h| h.value.as_str())
+                    .collect::<Vec<_>>()
+            };
+            let bearer = header("Authorization")
+                .into_iter()
+                .filter_map(|value| value.strip_prefix("Bearer ").map(str::trim))
+                .any(|value| token_eq(value, token));
+            let cookie = header("Cookie")
+                .into_iter()
+                .flat_map(|value| value.split(';'))
+                .filter_map(|pair| pair.trim().strip_prefix(TOKEN_COOKIE)?.strip_prefix('='))
+                .any(|value| token_eq(&percent_decode(value), token));
+            let query = query_token.as_deref().is_some_and(|v| token_eq(v, token));
+
+            if !(bearer || cookie || query) {
+                return Reply::text(401, "需要访问令牌");
+            }
+            if query && !cookie {
+                set_cookie = Some(format!(
+                    "{}={}; Path=/; HttpOnly; SameSite=Strict",
+                    TOKEN_COOKIE,
+                    crate::wiki::link::percent_encode(token)
+                ));
+            }
+        }
+
+        let mut reply = match normalize_relative_path(&percent_decode(path)) {
+            Ok(relative) => self.serve(&relative).unwrap_or_else(|e| {
+                eprintln!("处理请求失败 {}: {}", url, e);
+                Reply::text(500, "服务器内部错误")
+            }),
+            Err(_) => Reply::text(400, "非法的路径"),
+        };
+        reply.set_cookie = set_cookie;
+        reply
+    }
+
+    /// 根据相对路径返回页面、Markdown 原文或附件
+    fn serve(&self, relative: &str) -> Result<Reply, Error> {
+        match relative {
+            "style.css" => return Ok(Reply::new(200, "text/css; charset=utf-8", STYLE_CSS)),
+            "search.js" => return Ok(Reply::new(200, "text/javascript; charset=utf-8", SEARCH_JS)),
+            _ => {}
+        }
+
+        let tree = self.file_tree()?;
+        let mut files = Vec::new();
+        collect_files(&tree, &mut files);
+        let (pages, assets): (Vec<&FileNode>, Vec<&FileNode>) =
+            files.into_iter().partition(|node| is_page(node.path()));
+        let page_paths: Vec<&str> = pages.iter().map(|node| node.path()).collect();
+
+        if relative == "search-index.js" {
+            return Ok(Reply::new(
+                200,
+                "text/javascript; charset=utf-8",
+                self.search_index(&pages)?,
+            ));
+        }
+
+        let relative = if relative.is_empty() {
+            "index.html"
+        } else {
+            relative
+        };
+        let page = pages
+            .iter()
+            .find(|node| html_path(node.path()) == relative)
+            .copied();
+        if page.is_none() && relative != "index.html" {
+            let Some(node) = pages.iter().chain(&assets).find(|n| n.path() == relative) else {
+                return Ok(Reply::text(404, "页面不存在"));
+            };
+            if node.is_lfs_pointer() {
+                return Ok(Reply::text(404, "文件内容尚未下载"));
+            }
+            let Some(full_path) = self.resolve(node.path()) else {
+                return Ok(Reply::text(404, "页面不存在"));
+            };
+            let body = fs::read(full_path)?;
+            let content_type = if is_page(node.path()) {
+                "text/markdown; charset=utf-8"
+            } else {
+                mime_type(Path::new(node.path()))
+            };
+            // 附件中的脚本作为文本返回，页面无法通过 <script> 引用；
+            // 网页和 SVG 可以执行脚本，只允许下载，SVG 仍可以作为图片显示
+            let content_type = match content_type {
+                "text/javascript; charset=utf-8" => "text/plain; charset=utf-8",
+                content_type => content_type,
+            };
+            let mut reply = Reply::new(200, content_type, body);
+            reply.download = matches!(content_type, "text/html; charset=utf-8" | "image/svg+xml");
+            return Ok(reply);
+        }
+
+        let html_page = match page {
+            Some(node) => match self.resolve(node.path()) {
+                Some(full_path) => Some((node, fs::read_to_string(full_path)?)),
+                None => return Ok(Reply::text(404, "页面不存在")),
+            },
+            None => None,
+        };
+
+        // 侧边栏需要每个页面的标题
+        let titles = self.page_titles(&pages);
+        let titles: BTreeMap<&str, &str> = titles.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+        let html = match html_page {
+            Some((node, content)) => {
+                let path = node.path();
+                let asset_paths: Vec<&str> = assets.iter().map(|node| node.path()).collect();
+                let rendered = render_markdown(&content, node.name(), |link| {
+                    rewrite_link(path, link, &page_paths, &asset_paths)
+                });
+                let sidebar = render_sidebar(&tree, path, &titles);
+                page_html(
+                    &self.options.title,
+                    path,
+                    &rendered.title,
+                    &sidebar,
+                    &rendered.html,
+                )
+            }
+            None => index_html(&tree, &self.options.title, &titles),
+        };
+        Ok(Reply::new(200, "text/html; charset=utf-8", html))
+    }
+
+    /// 将相对路径解析为完整路径，文件不存在或符号链接指向知识库之外时返回 `None`
+    fn resolve(&self, relative: &str) -> Option<PathBuf> {
+        let path = self.root.join(relative).canonicalize().ok()?;
+        path.starts_with(&self.canonical_root).then_some(path)
+    }
+
+    /// 获取文件树，只在目录结构或 `.gitignore` 文件发生变化时重新构建
+    fn file_tree(&self) -> Result<Arc<FileNode>, Error> {
+        let stamps = self.tree_stamps();
+        let mut cache = self.tree.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.stamps == stamps) {
+            return Ok(cached.tree.clone());
+        }
+
+        let filter = TreeFilter::new(&self.root, &self.options.ignore_rules);
+        let tree = Arc::new(
+            build_file_tree_with(&self.root, &filter, None)
+                .map_err(|e| std::io::Error::other(e.to_string()))?,
+        );
+        *cache = Some(CachedTree {
+            stamps,
+            tree: tree.clone(),
+        });
+        Ok(tree)
+    }
+
+    /// 收集所有目录和 `.gitignore` 文件的修改时间
+    ///
+    /// 新增、删除或重命名文件会改变所在目录的修改时间，只读取元数据，不判断忽略规则。
+    fn tree_stamps(&self) -> Stamps {
+        let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+        let mut stamps = Vec::new();
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            stamps.push((dir.clone(), modified(&dir)));
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if entry.file_name() == ".gitignore" {
+                    stamps.push((path.clone(), modified(&path)));
+                }
+                if entry.file_name() != ".git" && entry.file_type().is_ok_and(|t| t.is_dir()) {
+                    dirs.push(path);
+                }
+            }
+        }
+        stamps.sort();
+        stamps
+    }
+
+    /// 获取搜索索引脚本，所有页面的修改时间和大小都不变时使用缓存
+    fn search_index(&self, pages: &[&FileNode]) -> Result<String, Error> {
+        let mut resolved = Vec::new();
+        for node in pages {
+            let Some(full_path) = self.resolve(node.path()) else {
+                continue;
+            };
+            let Ok(metadata) = fs::metadata(&full_path) else {
+                continue;
+            };
+            resolved.push((node, full_path, metadata.modified().ok(), metadata.len()));
+        }
+        let stamps: Vec<(String, Option<SystemTime>, u64)> = resolved
+            .iter()
+            .map(|(node, _, modified, size)| (node.path().to_string(), *modified, *size))
+            .collect();
+
+        let mut cache = self.search_index.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache.as_ref().filter(|cached| cached.pages == stamps) {
+            return Ok(cached.script.clone());
+        }
+
+        let mut rendered = BTreeMap::new();
+        for (node, full_path, _, _) in &resolved {
+            if let Ok(content) = fs::read_to_string(full_path) {
+                rendered.insert(
+                    node.path(),
+                    render_markdown(&content, node.name(), |_| None),
+                );
+            }
+        }
+        let script =
+            search_index_js(&rendered).map_err(|e| std::io::Error::other(e.to_string()))?;
+        *cache = Some(CachedSearchIndex {
+            pages: stamps,
+            script: script.clone(),
+        });
+        Ok(script)
+    }
+
+    /// 获取页面标题，只重新读取修改时间或大小发生变化的页面
+    fn page_titles<'a>(&self, pages: &[&'a FileNode]) -> BTreeMap<&'a str, String> {
+        let mut cache = self.titles.lock().unwrap_or_else(|e| e.into_inner());
+        let mut titles = BTreeMap::new();
+        for node in pages {
+            let Some(full_path) = self.resolve(node.path()) else {
+                continue;
+            };
+            let Ok(metadata) = fs::metadata(&full_path) else {
+                continue;
+            };
+            let modified = metadata.modified().ok();
+            let size = metadata.len();
+            let title = match cache.get(node.path()) {
+                Some(cached) if cached.modified == modified && cached.size == size => {
+                    cached.title.clone()
+                }
+                _ => {
+                    let Ok(content) = fs::read_to_string(&full_path) else {
+                        continue;
+                    };
+                    let title = page_title(&content, node.name());
+                    cache.insert(
+                        node.path().to_string(),
+                        CachedTitle {
+                            modified,
+                            size,
+                            title: title.clone(),
+                        },
+                    );
+                    title
+                }
+            };
+            titles.insert(node.path(), title);
+        }
+        cache.retain(|path, _| titles.contains_key(path.as_str()));
+        titles
+    }
+}
+
+/// 处理一次请求，处理过程中的 panic 转换为 500 响应
+///
+/// 服务只有一个处理请求的线程，panic 会结束该线程，服务仍被列为运行中却不再响应。
+fn catch_panic(respond: impl FnOnce() -> Reply) -> Reply {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(respond))
+        .unwrap_or_else(|_| Reply::text(500, "服务器内部错误"))
+}
+
+/// 比较访问令牌，比较时间与令牌内容无关
+fn token_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    fn header(name: &str, value: &str) -> tiny_http::Header {
+        tiny_http::Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+    }
+
+    fn test_site(root: &Path, token: Option<&str>) -> Site {
+        Site::new(
+            root,
+            ServerOptions {
+                bind_address: DEFAULT_BIND_ADDRESS.to_string(),
+                token: token.map(str::to_string),
+                title: "测试知识库".to_string(),
+                ignore_rules: vec!["private".to_string()],
+            },
+        )
+    }
+
+    /// 测试请求路由
+    ///
+    /// # 测试目标：
+    /// * 验证页面被渲染为 HTML，`.md` 返回原文，附件按扩展名返回
+    /// * 验证没有 `index.md` 时生成首页，页面修改后首页中的标题随之更新，生成的样式表和搜索索引可以访问
+    /// * 验证越权路径、被忽略的路径、不存在的路径和非法的百分号编码无法访问
+    #[test]
+    fn test_routes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        crate::git::Repository::init(root, None, None).unwrap();
+        fs::create_dir_all(root.join("docs/img")).unwrap();
+        fs::create_dir_all(root.join("private")).unwrap();
+        fs::write(root.join("docs/my guide.md"), "# 指南\n\n![图](img/a.png)").unwrap();
+        fs::write(root.join("docs/img/a.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        fs::write(root.join("private/secret.md"), "secret").unwrap();
+
+        let site = test_site(root, None);
+        let get = |url: &str| site.respond(&tiny_http::Method::Get, url, &[]);
+
+        let page = get("/docs/my%20guide.html");
+        assert_eq!(page.status, 200);
+        let html = String::from_utf8(page.body).unwrap();
+        assert!(html.contains("<title>指南 - 测试知识库</title>"));
+        assert!(html.contains("src=\"img/a.png\""));
+
+        let raw = get("/docs/my%20guide.md");
+        assert_eq!(raw.content_type, "text/markdown; charset=utf-8");
+        assert!(raw.body.starts_with("# 指南".as_bytes()));
+        assert_eq!(get("/docs/img/a.png").content_type, "image/png");
+
+        let index = String::from_utf8(get("/").body).unwrap();
+        assert!(index.contains("docs/my%20guide.html"));
+        assert_eq!(get("/style.css").status, 200);
+        assert!(String::from_utf8(get("/search-index.js").body)
+            .unwrap()
+            .contains("\"title\":\"指南\""));
+
+        // 页面修改后标题和搜索索引的缓存失效
+        fs::write(root.join("docs/my guide.md"), "# 新的指南").unwrap();
+        let index = String::from_utf8(get("/").body).unwrap();
+        assert!(index.contains("新的指南"));
+        assert!(String::from_utf8(get("/search-index.js").body)
+            .unwrap()
+            .contains("\"title\":\"新的指南\""));
+
+        // 新增页面后文件树的缓存失效
+        fs::write(root.join("docs/new.md"), "# 新页面").unwrap();
+        assert_eq!(get("/docs/new.html").status, 200);
+        assert!(String::from_utf8(get("/").body).unwrap().contains("新页面"));
+
+        assert_eq!(get("/../etc/passwd").status, 400);
+        assert_eq!(get("/%中").status, 404);
+        assert_eq!(get("/private/secret.md").status, 404);
+        assert_eq!(get("/.git/config").status, 404);
+        assert_eq!(get("/missing.html").status, 404);
+        assert_eq!(site.respond(&tiny_http::Method::Post, "/", &[]).status, 405);
+    }
+
+    /// 测试响应头
+    ///
+    /// # 测试目标：
+    /// * 验证所有响应都带有内容安全策略，页面仍可以加载本站的 `search.js`
+    #[test]
+    fn test_content_security_policy() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.md"), "# A").unwrap();
+        let site = test_site(temp_dir.path(), None);
+
+        for url in ["/a.html", "/missing.html"] {
+            let response = site
+                .respond(&tiny_http::Method::Get, url, &[])
+                .into_response();
+            let csp = response
+                .headers()
+                .iter()
+                .find(|h| h.field.equiv("Content-Security-Policy"))
+                .map(|h| h.value.to_string())
+                .unwrap();
+            assert!(csp.contains("default-src 'self'"));
+            assert!(csp.contains("script-src 'self';"));
+        }
+    }
+
+    /// 测试可以执行脚本的附件
+    ///
+    /// # 测试目标：
+    /// * 验证 HTML 和 SVG 附件以下载的方式返回，图片等普通附件直接打开
+    /// * 验证脚本附件作为纯文本返回
+    #[test]
+    fn test_active_attachments() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("a.html"), "<script>alert(1)</script>").unwrap();
+        fs::write(root.join("b.svg"), "<svg><script>alert(1)</script></svg>").unwrap();
+        fs::write(root.join("c.js"), "alert(1)").unwrap();
+        fs::write(root.join("d.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        let site = test_site(root, None);
+        let get = |url: &str| site.respond(&tiny_http::Method::Get, url, &[]);
+
+        let html = get("/a.html");
+        assert_eq!(html.status, 200);
+        assert!(html.download);
+        let response = html.into_response();
+        assert!(response
+            .headers()
+            .iter()
+            .any(|h| h.field.equiv("Content-Disposition") && h.value.as_str() == "attachment"));
+
+        let svg = get("/b.svg");
+        assert_eq!(svg.content_type, "image/svg+xml");
+        assert!(svg.download);
+
+        let script = get("/c.js");
+        assert_eq!(script.content_type, "text/plain; charset=utf-8");
+        assert!(!script.download);
+        assert!(!get("/d.png").download);
+    }
+
+    /// 测试访问令牌
+    ///
+    /// # 测试目标：
+    /// * 验证没有令牌或令牌错误时返回 401
+    /// * 验证通过查询参数访问后设置 Cookie，Cookie 和 Bearer 请求头都可以通过验证
+    #[test]
+    fn test_token() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.md"), "# A").unwrap();
+        let site = test_site(temp_dir.path(), Some("s3cret"));
+        let get = |url: &str, headers: &[tiny_http::Header]| {
+            site.respond(&tiny_http::Method::Get, url, headers)
+        };
+
+        assert_eq!(get("/a.html", &[]).status, 401);
+        assert_eq!(get("/a.html?token=wrong", &[]).status, 401);
+        assert_eq!(get("/a.html?token=%中", &[]).status, 401);
+
+        let reply = get("/a.html?token=s3cret", &[]);
+        assert_eq!(reply.status, 200);
+        let cookie = reply.set_cookie.unwrap();
+        assert!(cookie.starts_with("markwiki_token=s3cret;"));
+
+        let with_cookie = [header("Cookie", "theme=dark; markwiki_token=s3cret")];
+        assert_eq!(get("/a.html", &with_cookie).status, 200);
+        let with_bearer = [header("Authorization", "Bearer s3cret")];
+        assert_eq!(get("/a.md", &with_bearer).status, 200);
+    }
+
+    /// 测试指向知识库之外的符号链接
+    ///
+    /// # 测试目标：
+    /// * 验证通过符号链接无法读取知识库之外的页面和附件，标题和搜索索引中也不包含其内容
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_outside_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret.md"), "# 机密").unwrap();
+        fs::write(outside.join("secret.png"), "secret").unwrap();
+        fs::write(root.join("a.md"), "# A").unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.md"), root.join("leak.md")).unwrap();
+        std::os::unix::fs::symlink(outside.join("secret.png"), root.join("leak.png")).unwrap();
+
+        let site = test_site(&root, None);
+        let get = |url: &str| site.respond(&tiny_http::Method::Get, url, &[]);
+        assert_eq!(get("/a.html").status, 200);
+        assert_eq!(get("/leak.md").status, 404);
+        assert_eq!(get("/leak.html").status, 404);
+        assert_eq!(get("/leak.png").status, 404);
+        assert!(!String::from_utf8(get("/").body).unwrap().contains("机密"));
+        assert!(!String::from_utf8(get("/search-index.js").body)
+            .unwrap()
+            .contains("机密"));
+    }
+
+    /// 测试服务的启动与停止
+    ///
+    /// # 测试目标：
+    /// * 验证监听端口 0 时使用系统分配的端口，并能通过 HTTP 访问页面
+    /// * 验证处理请求时的 panic 和非法的请求路径不会使服务停止响应
+    /// * 验证停止后端口不再接受请求，注册表中不再有该服务
+    #[test]
+    fn test_start_and_stop() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("index.md"), "# 首页").unwrap();
+
+        let registry = ServerRegistry::default();
+        let info = registry
+            .start(
+                "wiki",
+                temp_dir.path(),
+                ServerOptions {
+                    bind_address: "127.0.0.1:0".to_string(),
+                    token: None,
+                    title: "wiki".to_string(),
+                    ignore_rules: Vec::new(),
+                },
+            )
+            .unwrap();
+        assert!(!info.address.ends_with(":0"));
+        assert_eq!(registry.list().len(), 1);
+
+        let url = format!("http://{}/", info.address);
+        let body = ureq::get(&url)
+            .call()
+            .unwrap()
+            .body_mut()
+            .read_to_string()
+            .unwrap();
+        assert!(body.contains("首页"));
+
+        // 处理请求时的 panic 和非法的百分号编码都不会中断服务线程
+        assert_eq!(catch_panic(|| panic!("处理请求失败")).status, 500);
+        let mut stream = std::net::TcpStream::connect(&info.address).unwrap();
+        stream
+            .write_all(b"GET /%zz%E4%4 HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 404"));
+        assert_eq!(ureq::get(&url).call().unwrap().status(), 200);
+
+        assert!(registry.stop("wiki"));
+        assert!(!registry.stop("wiki"));
+        assert!(registry.list().is_empty());
+        assert!(ureq::get(&url).call().is_err());
+    }
+}
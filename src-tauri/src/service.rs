@@ -0,0 +1,309 @@
+//! 知识库操作服务
+//!
+//! 图形界面的 Tauri 命令和命令行工具 `markwiki-cli` 共用的操作实现。
+//! 此模块不依赖 Tauri，函数都是同步的，不向标准输出或标准错误写入任何内容，
+//! 错误信息与命令返回给界面的格式相同。
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::config::AppConfig;
+use crate::draft::Drafts;
+use crate::wiki::content::{self, FileContent};
+use crate::wiki::search::SearchHit;
+use crate::wiki::tag::TagIndex;
+use crate::wiki::{self, Wiki};
+
+/// 删除知识库失败的原因
+///
+/// 存在未保存的工作时作为单独的类型返回，界面可以据此让用户选择归档后删除或强制删除。
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeleteWikiError {
+    /// 知识库存在未提交的修改或未推送的提交
+    UnsavedWork {
+        uncommitted: bool,
+        unpushed: usize,
+        message: String,
+    },
+    /// 其他错误
+    Failed { message: String },
+}
+
+impl From<String> for DeleteWikiError {
+    fn from(message: String) -> Self {
+        DeleteWikiError::Failed { message }
+    }
+}
+
+/// 保存文件失败的原因
+///
+/// 文件冲突作为单独的类型返回，界面可以据此展示磁盘上的当前内容，让用户选择如何处理。
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SaveFileError {
+    /// 文件在读取之后被修改或删除，`current` 为磁盘上的当前内容，文件已被删除时为 `None`
+    Conflict { current: Option<FileContent> },
+    /// 其他错误
+    Failed { message: String },
+}
+
+impl From<String> for SaveFileError {
+    fn from(message: String) -> Self {
+        SaveFileError::Failed { message }
+    }
+}
+
+/// 打开知识库，错误信息中包含知识库名称
+pub fn open_wiki(wiki_name: &str) -> Result<Wiki, String> {
+    Wiki::from_name(wiki_name).map_err(|e| format!("无法打开知识库 {}: {}", wiki_name, e))
+}
+
+/// 获取所有知识库，包括注册的外部知识库
+pub fn list_wikis() -> Result<Vec<Wiki>, String> {
+    Wiki::list().map_err(|e| format!("读取知识库列表失败: {}", e))
+}
+
+/// 创建本地知识库，并将 Git 用户名和邮箱保存到应用配置
+///
+/// # 参数
+/// * `wiki_name` - 要创建的知识库名称
+/// * `username` - Git 用户名
+/// * `email` - Git 邮箱
+pub fn create_local_wiki(wiki_name: &str, username: &str, email: &str) -> Result<Wiki, String> {
+    if Wiki::exists(wiki_name) {
+        return Err(format!("知识库 {} 已存在", wiki_name));
+    }
+
+    let wiki = Wiki::create_local_wiki(wiki_name, username, email)
+        .map_err(|e| format!("创建本地知识库失败: {}", e))?;
+    save_git_credentials(username, email, None)?;
+    Ok(wiki)
+}
+
+/// 从远程仓库克隆知识库，知识库名称取自仓库地址，凭据保存到应用配置
+///
+/// # 参数
+/// * `remote_url` - 远程仓库地址
+/// * `username` - Git 用户名
+/// * `email` - Git 邮箱
+/// * `password` - Git 密码或访问令牌，空字符串表示不需要密码
+pub fn create_remote_wiki(
+    remote_url: &str,
+    username: &str,
+    email: &str,
+    password: &str,
+) -> Result<Wiki, String> {
+    let wiki_name = remote_url
+        .split('/')
+        .next_back()
+        .and_then(|s| s.split('.').next())
+        .ok_or("从URL提取仓库名称失败")?;
+
+    if Wiki::exists(wiki_name) {
+        return Err(format!("知识库 {} 已存在", wiki_name));
+    }
+
+    let password = (!password.is_empty()).then_some(password);
+    let wiki = Wiki::create_remote_wiki(wiki_name, remote_url, username, email, password)
+        .map_err(|e| format!("从远程URL创建知识库失败: {}", e))?;
+    save_git_credentials(username, email, password)?;
+    Ok(wiki)
+}
+
+/// 将 Git 凭据保存到应用配置
+fn save_git_credentials(username: &str, email: &str, password: Option<&str>) -> Result<(), String> {
+    let mut config = AppConfig::load_or_default().map_err(|e| format!("读取配置失败: {}", e))?;
+    config.set_git_credentials(username, email, password);
+    config.save().map_err(|e| format!("保存配置失败: {}", e))
+}
+
+/// 删除知识库
+///
+/// # 参数
+/// * `wiki_name` - 要删除的知识库名称
+/// * `force` - 是否忽略未保存的工作强制删除
+/// * `archive` - 删除前是否先打包归档
+///
+/// # 返回值
+/// * `Result<Option<String>, DeleteWikiError>` - 成功时返回归档文件路径（未归档时为 `None`）
+pub fn delete_wiki(
+    wiki_name: &str,
+    force: bool,
+    archive: bool,
+) -> Result<Option<String>, DeleteWikiError> {
+    // 打开知识库时会校验名称，拒绝越过存储目录的路径
+    let wiki = open_wiki(wiki_name)?;
+
+    let archive_path = wiki.delete(force, archive).map_err(|e| match e {
+        wiki::Error::UnsavedWork {
+            uncommitted,
+            unpushed,
+        } => DeleteWikiError::UnsavedWork {
+            uncommitted,
+            unpushed,
+            message: format!(
+                "知识库 {} 存在未保存的工作（未提交的修改: {}，未推送的提交: {}）",
+                wiki_name,
+                if uncommitted { "有" } else { "无" },
+                unpushed
+            ),
+        },
+        e => format!("删除知识库失败: {}", e).into(),
+    })?;
+
+    Ok(archive_path.map(|path| path.to_string_lossy().to_string()))
+}
+
+/// 读取文件内容及其版本标识
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `file_path` - 文件路径，相对于知识库根目录
+pub fn read_file(wiki_name: &str, file_path: &str) -> Result<FileContent, String> {
+    let wiki = open_wiki(wiki_name)?;
+    let full_path = wiki.resolve_path(file_path).map_err(|e| e.to_string())?;
+
+    if !full_path.exists() {
+        return Err(format!("文件不存在: {}", full_path.display()));
+    }
+    if !full_path.is_file() {
+        return Err(format!("指定的路径不是一个文件: {}", full_path.display()));
+    }
+
+    content::read_text(&full_path)
+        .map_err(|e| format!("读取文件失败 {}: {}", full_path.display(), e))
+}
+
+/// 写入文件，提供版本标识时拒绝覆盖在此之后被修改的文件
+///
+/// 写入成功后删除该页面的草稿并更新标签索引。这两步失败时不影响保存结果：
+/// 草稿会在下次打开时再次提示，标签索引会在下次打开时重新解析该页面。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `file_path` - 文件路径，相对于知识库根目录
+/// * `content` - 要写入的内容
+/// * `version` - 读取文件时得到的版本标识，为 `None` 时直接覆盖
+pub fn save_file(
+    wiki_name: &str,
+    file_path: &str,
+    content: &str,
+    version: Option<&str>,
+) -> Result<FileContent, SaveFileError> {
+    let wiki = open_wiki(wiki_name)?;
+    let full_path = wiki.resolve_path(file_path).map_err(|e| e.to_string())?;
+
+    let saved = content::write_text(&full_path, content, version).map_err(|e| match e {
+        wiki::Error::Conflict { current, .. } => SaveFileError::Conflict { current },
+        e => format!("保存文件失败 {}: {}", full_path.display(), e).into(),
+    })?;
+
+    let _ = Drafts::for_wiki(&wiki).and_then(|drafts| drafts.discard(file_path));
+    update_tag_index(&wiki, file_path, content);
+    Ok(saved)
+}
+
+/// 页面保存后更新标签索引
+///
+/// 失败时忽略，保存结果不受影响，索引会在下次打开时重新解析该页面。
+///
+/// # 参数
+/// * `wiki` - 页面所在的知识库
+/// * `file_path` - 页面路径，相对于知识库根目录
+/// * `content` - 页面保存后的内容
+pub fn update_tag_index(wiki: &Wiki, file_path: &str, content: &str) {
+    let _ = TagIndex::for_wiki(wiki).and_then(|mut index| index.update_page(file_path, content));
+}
+
+/// 在知识库的页面中搜索
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `query` - 查询内容，多个关键词之间为“且”的关系
+/// * `limit` - 最多返回的页面数量
+pub fn search_wiki(wiki_name: &str, query: &str, limit: usize) -> Result<Vec<SearchHit>, String> {
+    let wiki = open_wiki(wiki_name)?;
+    let tree = wiki
+        .file_tree("", None)
+        .map_err(|e| format!("读取文件树失败: {}", e))?;
+
+    Ok(wiki::search::search(
+        Path::new(&wiki.path),
+        &tree,
+        query,
+        limit,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试创建知识库
+    ///
+    /// # 测试目标：
+    /// * 验证创建后知识库出现在列表中，Git 用户名和邮箱保存到应用配置
+    /// * 验证同名知识库已存在时拒绝创建
+    #[test]
+    fn test_create_local_wiki() {
+        let _data_dir = crate::config::test_data_dir();
+
+        let wiki = create_local_wiki("notes", "alice", "alice@example.com").unwrap();
+        assert!(Path::new(&wiki.path).join(".git").is_dir());
+        assert!(list_wikis().unwrap().iter().any(|w| w.path == wiki.path));
+        let config = AppConfig::load_or_default().unwrap();
+        assert_eq!(config.git_credentials.username, "alice");
+
+        let error = create_local_wiki("notes", "alice", "alice@example.com").unwrap_err();
+        assert!(error.contains("已存在"));
+    }
+
+    /// 测试保存和读取文件
+    ///
+    /// # 测试目标：
+    /// * 验证保存后读取到相同的内容和版本标识
+    /// * 验证使用过期的版本标识保存时返回 `SaveFileError::Conflict`，磁盘上的内容不变
+    #[test]
+    fn test_save_and_read_file() {
+        let _data_dir = crate::config::test_data_dir();
+        create_local_wiki("notes", "alice", "alice@example.com").unwrap();
+
+        let saved = save_file("notes", "a.md", "# A", None).unwrap();
+        let read = read_file("notes", "a.md").unwrap();
+        assert_eq!(read.content, "# A");
+        assert_eq!(read.version, saved.version);
+
+        let updated = save_file("notes", "a.md", "# B", Some(&read.version)).unwrap();
+        assert_ne!(updated.version, saved.version);
+
+        match save_file("notes", "a.md", "# C", Some(&saved.version)) {
+            Err(SaveFileError::Conflict { current }) => {
+                assert_eq!(current.unwrap().content, "# B");
+            }
+            other => panic!("expected conflict, got {:?}", other),
+        }
+        assert_eq!(read_file("notes", "a.md").unwrap().content, "# B");
+    }
+
+    /// 测试删除存在未保存工作的知识库
+    ///
+    /// # 测试目标：
+    /// * 验证存在未提交的修改时拒绝删除并返回 `DeleteWikiError::UnsavedWork`
+    /// * 验证强制删除后知识库不再出现在列表中
+    #[test]
+    fn test_delete_wiki_with_unsaved_work() {
+        let _data_dir = crate::config::test_data_dir();
+        create_local_wiki("notes", "alice", "alice@example.com").unwrap();
+        save_file("notes", "a.md", "# A", None).unwrap();
+
+        match delete_wiki("notes", false, false) {
+            Err(DeleteWikiError::UnsavedWork { uncommitted, .. }) => assert!(uncommitted),
+            other => panic!("expected unsaved work, got {:?}", other),
+        }
+        assert!(Wiki::exists("notes"));
+
+        assert_eq!(delete_wiki("notes", true, false).unwrap(), None);
+        assert!(!Wiki::exists("notes"));
+    }
+}
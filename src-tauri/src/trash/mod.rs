@@ -0,0 +1,300 @@
+//! 回收站模块
+//!
+//! 此模块为知识库中的文件和文件夹提供可撤销的删除功能。
+//! 被删除的条目会被移动到知识库 Git 目录下的回收站（`.git/markwiki/trash`），
+//! 不会出现在工作区和版本管理中，之后可以列出并恢复到原来的位置。
+
+use crate::wiki::{self, Wiki};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// 回收站条目元数据的文件名
+const ENTRY_FILE: &str = "entry.json";
+
+/// 回收站中保存被删除内容的名称
+const ITEM_NAME: &str = "item";
+
+/// 回收站操作可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON 序列化/反序列化错误
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// 知识库操作错误
+    #[error("Wiki error: {0}")]
+    Wiki(#[from] wiki::Error),
+
+    /// 要删除的文件或文件夹不存在
+    #[error("Path not found: {0}")]
+    PathNotFound(String),
+
+    /// 回收站条目不存在
+    #[error("Trash entry not found: {0}")]
+    EntryNotFound(String),
+
+    /// 恢复的目标位置已被占用
+    #[error("Restore target already exists: {0}")]
+    RestoreTargetExists(String),
+}
+
+/// 回收站条目
+///
+/// # 字段
+/// * `id` - 条目的唯一标识
+/// * `original_path` - 被删除前相对于知识库根目录的路径
+/// * `is_directory` - 是否为文件夹
+/// * `deleted_at` - 删除时间（Unix 时间戳，单位为秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    pub original_path: String,
+    pub is_directory: bool,
+    pub deleted_at: u64,
+}
+
+/// 知识库回收站
+pub struct Trash {
+    /// 知识库根目录
+    root: PathBuf,
+    /// 回收站目录
+    dir: PathBuf,
+}
+
+impl Trash {
+    /// 创建回收站实例
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    /// * `dir` - 回收站目录，不存在时会在首次删除时创建
+    pub fn new(root: &Path, dir: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// 获取指定知识库的回收站
+    ///
+    /// # 参数
+    /// * `wiki` - 知识库实例
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回 `Ok(Trash)`
+    /// * 失败时返回 `Err(Error)`，表示无法获取知识库的状态目录
+    pub fn for_wiki(wiki: &Wiki) -> Result<Self, Error> {
+        Self::for_root(Path::new(&wiki.path))
+    }
+
+    /// 获取指定根目录下知识库的回收站
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回 `Ok(Trash)`
+    /// * 失败时返回 `Err(Error)`，表示无法获取知识库的状态目录
+    pub fn for_root(root: &Path) -> Result<Self, Error> {
+        Ok(Self::new(root, &Wiki::state_dir_at(root)?.join("trash")))
+    }
+
+    /// 将文件或文件夹移动到回收站
+    ///
+    /// # 参数
+    /// * `relative` - 要删除的文件或文件夹相对于知识库根目录的路径
+    ///
+    /// # 返回值
+    /// * `Result<TrashEntry, Error>` - 成功时返回新建的回收站条目
+    /// * 失败时返回 `Err(Error)`，如路径不存在或移动失败
+    pub fn move_to_trash(&self, relative: &str) -> Result<TrashEntry, Error> {
+        let relative = wiki::normalize_relative_path(relative)?;
+        if relative.is_empty() {
+            return Err(wiki::Error::InvalidPath("不能删除知识库根目录".to_string()).into());
+        }
+
+        let source = self.root.join(&relative);
+        let metadata =
+            fs::symlink_metadata(&source).map_err(|_| Error::PathNotFound(relative.clone()))?;
+
+        let deleted_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let (id, entry_dir) = self.allocate_entry_dir()?;
+
+        let entry = TrashEntry {
+            id,
+            original_path: relative,
+            is_directory: metadata.is_dir(),
+            deleted_at,
+        };
+        fs::write(
+            entry_dir.join(ENTRY_FILE),
+            serde_json::to_string_pretty(&entry)?,
+        )?;
+
+        // 回收站位于知识库内部，与工作区处于同一文件系统，可以直接重命名
+        if let Err(e) = fs::rename(&source, entry_dir.join(ITEM_NAME)) {
+            let _ = fs::remove_dir_all(&entry_dir);
+            return Err(e.into());
+        }
+
+        Ok(entry)
+    }
+
+    /// 列出回收站中的所有条目
+    ///
+    /// # 返回值
+    /// * `Result<Vec<TrashEntry>, Error>` - 成功时返回按删除时间倒序排列的条目列表
+    /// * 失败时返回 `Err(Error)`
+    pub fn list(&self) -> Result<Vec<TrashEntry>, Error> {
+        let mut entries = Vec::new();
+        if !self.dir.exists() {
+            return Ok(entries);
+        }
+
+        for dir_entry in fs::read_dir(&self.dir)? {
+            let entry_file = dir_entry?.path().join(ENTRY_FILE);
+            // 跳过损坏或未写完的条目
+            let Ok(content) = fs::read_to_string(&entry_file) else {
+                continue;
+            };
+            if let Ok(entry) = serde_json::from_str::<TrashEntry>(&content) {
+                entries.push(entry);
+            }
+        }
+
+        entries.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at).then(b.id.cmp(&a.id)));
+        Ok(entries)
+    }
+
+    /// 将回收站条目恢复到原来的位置
+    ///
+    /// 如果原位置已经存在同名文件或文件夹，恢复会失败，以免覆盖新的内容。
+    ///
+    /// # 参数
+    /// * `id` - 回收站条目的唯一标识
+    ///
+    /// # 返回值
+    /// * `Result<TrashEntry, Error>` - 成功时返回被恢复的条目
+    /// * 失败时返回 `Err(Error)`
+    pub fn restore(&self, id: &str) -> Result<TrashEntry, Error> {
+        let entry_dir = self.entry_dir(id)?;
+        let entry: TrashEntry =
+            serde_json::from_str(&fs::read_to_string(entry_dir.join(ENTRY_FILE))?)?;
+
+        let target = self.root.join(&entry.original_path);
+        if target.exists() {
+            return Err(Error::RestoreTargetExists(entry.original_path));
+        }
+
+        // 原来的父目录可能已被删除，需要重新创建
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(entry_dir.join(ITEM_NAME), &target)?;
+        fs::remove_dir_all(&entry_dir)?;
+
+        Ok(entry)
+    }
+
+    /// 获取已存在的回收站条目目录
+    fn entry_dir(&self, id: &str) -> Result<PathBuf, Error> {
+        // 条目标识只由数字和连字符组成，拒绝任何路径成分
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit() || c == '-') {
+            return Err(Error::EntryNotFound(id.to_string()));
+        }
+
+        let entry_dir = self.dir.join(id);
+        if !entry_dir.join(ENTRY_FILE).exists() {
+            return Err(Error::EntryNotFound(id.to_string()));
+        }
+        Ok(entry_dir)
+    }
+
+    /// 分配一个新的回收站条目目录，返回条目标识和目录路径
+    fn allocate_entry_dir(&self) -> Result<(String, PathBuf), Error> {
+        fs::create_dir_all(&self.dir)?;
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let mut counter = 0u32;
+        loop {
+            let id = format!("{}-{}", nanos, counter);
+            let entry_dir = self.dir.join(&id);
+            match fs::create_dir(&entry_dir) {
+                Ok(()) => return Ok((id, entry_dir)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => counter += 1,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试删除与恢复的完整流程
+    ///
+    /// # 测试目标：
+    /// * 验证文件和文件夹能被移入回收站并从工作区消失
+    /// * 验证回收站条目能被列出，并恢复到原来的位置
+    /// * 验证原位置被占用时拒绝恢复
+    #[test]
+    fn test_move_to_trash_and_restore() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        fs::create_dir_all(root.join("docs/sub")).unwrap();
+        fs::write(root.join("docs/sub/a.md"), "a").unwrap();
+        fs::write(root.join("b.md"), "b").unwrap();
+
+        let trash = Trash::new(&root, &temp_dir.path().join("trash"));
+
+        let folder_entry = trash.move_to_trash("/docs/").unwrap();
+        assert_eq!(folder_entry.original_path, "docs");
+        assert!(folder_entry.is_directory);
+        assert!(!root.join("docs").exists());
+
+        let file_entry = trash.move_to_trash("b.md").unwrap();
+        assert!(!file_entry.is_directory);
+        assert!(!root.join("b.md").exists());
+
+        assert_eq!(trash.list().unwrap().len(), 2);
+        assert!(matches!(
+            trash.move_to_trash("../wiki"),
+            Err(Error::Wiki(wiki::Error::InvalidPath(_)))
+        ));
+        assert!(matches!(
+            trash.move_to_trash("missing.md"),
+            Err(Error::PathNotFound(_))
+        ));
+
+        // 恢复文件夹
+        trash.restore(&folder_entry.id).unwrap();
+        assert_eq!(fs::read_to_string(root.join("docs/sub/a.md")).unwrap(), "a");
+
+        // 原位置已有新文件时拒绝恢复
+        fs::write(root.join("b.md"), "new").unwrap();
+        assert!(matches!(
+            trash.restore(&file_entry.id),
+            Err(Error::RestoreTargetExists(_))
+        ));
+        assert_eq!(fs::read_to_string(root.join("b.md")).unwrap(), "new");
+
+        assert_eq!(trash.list().unwrap().len(), 1);
+        assert!(matches!(
+            trash.restore("../docs"),
+            Err(Error::EntryNotFound(_))
+        ));
+    }
+}
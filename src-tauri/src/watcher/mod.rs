@@ -0,0 +1,428 @@
+//! 文件系统监视模块
+//!
+//! 此模块为打开的知识库监视文件系统的变化（外部编辑器修改、同步或拉取等），
+//! 对事件进行防抖处理后转换为带有知识库相对路径的事件，再交给上层（如 Tauri 事件）推送给界面。
+//! 同步检出期间可以暂停监视，避免大量事件涌入界面。
+
+use crate::wiki::{to_relative_path, TreeFilter};
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{self, Event, EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// 事件防抖的时间间隔
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// 恢复监视后继续忽略事件的时间，用于丢弃暂停期间产生、但在恢复后才送达的事件
+const RESUME_GRACE: Duration = Duration::from_millis(1500);
+
+/// 文件系统监视可能出现的错误类型
+#[derive(Error, Debug)]
+pub enum Error {
+    /// 创建或启动监视器失败
+    #[error("Watcher error: {0}")]
+    Notify(#[from] notify::Error),
+
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// 推送给界面的文件变化事件
+///
+/// 所有路径都是相对于知识库根目录、使用 `/` 分隔的路径，与 `FileNode.path` 的格式相同。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum WatchEvent {
+    /// 文件或文件夹被创建
+    Created { wiki_name: String, path: String },
+    /// 文件内容被修改
+    Modified { wiki_name: String, path: String },
+    /// 文件或文件夹被删除
+    Deleted { wiki_name: String, path: String },
+    /// 文件或文件夹被重命名或移动
+    Renamed {
+        wiki_name: String,
+        from: String,
+        to: String,
+    },
+    /// 暂停期间发生了未推送的变化，界面需要重新加载整个文件树
+    Refreshed { wiki_name: String },
+}
+
+impl WatchEvent {
+    /// 获取事件在界面中的名称
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            WatchEvent::Created { .. } => "file-created",
+            WatchEvent::Modified { .. } => "file-modified",
+            WatchEvent::Deleted { .. } => "file-deleted",
+            WatchEvent::Renamed { .. } => "file-renamed",
+            WatchEvent::Refreshed { .. } => "file-tree-changed",
+        }
+    }
+}
+
+/// 监视器的暂停状态
+#[derive(Default)]
+struct PauseState {
+    /// 是否处于暂停状态
+    paused: bool,
+    /// 最近一次恢复监视的时间
+    resumed_at: Option<Instant>,
+}
+
+impl PauseState {
+    /// 当前是否应该丢弃事件
+    fn is_suppressed(&self) -> bool {
+        self.paused
+            || self
+                .resumed_at
+                .map(|t| t.elapsed() < RESUME_GRACE)
+                .unwrap_or(false)
+    }
+}
+
+/// 单个知识库的监视器
+struct WikiWatcher {
+    /// 防抖监视器，被丢弃时停止监视
+    _debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
+    /// 暂停状态，与事件回调共享
+    state: Arc<Mutex<PauseState>>,
+}
+
+/// 所有打开的知识库的监视器
+///
+/// 该结构体作为应用的全局状态，按知识库名称管理监视器。
+#[derive(Default)]
+pub struct WatcherRegistry {
+    watchers: Mutex<HashMap<String, WikiWatcher>>,
+}
+
+impl WatcherRegistry {
+    /// 开始监视知识库
+    ///
+    /// 如果该知识库已经在监视中，会先停止旧的监视器。
+    /// 根目录会先被规范化，事件中的路径是解析过符号链接的真实路径
+    /// （如 macOS 上的 `/private/var/...`），否则无法与根目录比较。
+    ///
+    /// # 参数
+    /// * `wiki_name` - 知识库名称
+    /// * `root` - 知识库根目录
+    /// * `ignore_rules` - 额外的忽略规则（知识库元数据中的 `ignore` 字段）
+    /// * `emit` - 推送事件的回调函数
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error)`，表示无法创建监视器
+    pub fn watch<F>(
+        &self,
+        wiki_name: &str,
+        root: &Path,
+        ignore_rules: &[String],
+        emit: F,
+    ) -> Result<(), Error>
+    where
+        F: Fn(WatchEvent) + Send + 'static,
+    {
+        let root = root.canonicalize()?;
+        let root = root.as_path();
+        let state = Arc::new(Mutex::new(PauseState::default()));
+        let callback_state = state.clone();
+        let callback_root = root.to_path_buf();
+        let filter = TreeFilter::new(root, ignore_rules);
+        let name = wiki_name.to_string();
+
+        let mut debouncer = new_debouncer(
+            DEBOUNCE_TIMEOUT,
+            None,
+            move |result: DebounceEventResult| {
+                let Ok(events) = result else {
+                    return;
+                };
+                if callback_state
+                    .lock()
+                    .map(|s| s.is_suppressed())
+                    .unwrap_or(false)
+                {
+                    return;
+                }
+
+                for event in events {
+                    for watch_event in translate_event(&name, &callback_root, &filter, &event.event)
+                    {
+                        emit(watch_event);
+                    }
+                }
+            },
+        )?;
+        debouncer.watch(root, RecursiveMode::Recursive)?;
+
+        if let Ok(mut watchers) = self.watchers.lock() {
+            watchers.insert(
+                wiki_name.to_string(),
+                WikiWatcher {
+                    _debouncer: debouncer,
+                    state,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// 停止监视知识库
+    ///
+    /// # 返回值
+    /// * `bool` - 知识库之前处于监视状态时返回 `true`
+    pub fn unwatch(&self, wiki_name: &str) -> bool {
+        self.watchers
+            .lock()
+            .map(|mut watchers| watchers.remove(wiki_name).is_some())
+            .unwrap_or(false)
+    }
+
+    /// 暂停推送知识库的事件，用于同步检出等会产生大量变化的操作
+    pub fn pause(&self, wiki_name: &str) {
+        self.with_state(wiki_name, |state| state.paused = true);
+    }
+
+    /// 恢复推送知识库的事件
+    ///
+    /// 暂停期间的变化不会逐条推送，恢复时会推送一个 `Refreshed` 事件通知界面重新加载文件树。
+    ///
+    /// # 参数
+    /// * `wiki_name` - 知识库名称
+    /// * `emit` - 推送事件的回调函数
+    pub fn resume<F>(&self, wiki_name: &str, emit: F)
+    where
+        F: Fn(WatchEvent),
+    {
+        let mut was_paused = false;
+        self.with_state(wiki_name, |state| {
+            was_paused = state.paused;
+            state.paused = false;
+            state.resumed_at = Some(Instant::now());
+        });
+
+        if was_paused {
+            emit(WatchEvent::Refreshed {
+                wiki_name: wiki_name.to_string(),
+            });
+        }
+    }
+
+    /// 修改知识库监视器的暂停状态，知识库未被监视时不做任何操作
+    fn with_state<F>(&self, wiki_name: &str, f: F)
+    where
+        F: FnOnce(&mut PauseState),
+    {
+        if let Ok(watchers) = self.watchers.lock() {
+            if let Some(watcher) = watchers.get(wiki_name) {
+                if let Ok(mut state) = watcher.state.lock() {
+                    f(&mut state);
+                }
+            }
+        }
+    }
+}
+
+/// 将文件系统事件转换为界面事件
+///
+/// 位于 `.git` 等被过滤目录中的路径，以及访问等不改变内容的事件会被忽略。
+///
+/// # 参数
+/// * `wiki_name` - 知识库名称
+/// * `root` - 知识库根目录
+/// * `filter` - 文件树过滤规则
+/// * `event` - 文件系统事件
+fn translate_event(
+    wiki_name: &str,
+    root: &Path,
+    filter: &TreeFilter,
+    event: &Event,
+) -> Vec<WatchEvent> {
+    let visible =
+        |path: &PathBuf| path.starts_with(root) && path != root && !is_filtered(root, filter, path);
+    let relative = |path: &PathBuf| to_relative_path(root, path);
+    let name = wiki_name.to_string();
+
+    match event.kind {
+        // 同时包含重命名前后路径的事件
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+            let (from, to) = (&event.paths[0], &event.paths[1]);
+            match (visible(from), visible(to)) {
+                (true, true) => vec![WatchEvent::Renamed {
+                    wiki_name: name,
+                    from: relative(from),
+                    to: relative(to),
+                }],
+                // 从被过滤的位置移入，或移动到被过滤的位置
+                (false, true) => vec![WatchEvent::Created {
+                    wiki_name: name,
+                    path: relative(to),
+                }],
+                (true, false) => vec![WatchEvent::Deleted {
+                    wiki_name: name,
+                    path: relative(from),
+                }],
+                (false, false) => Vec::new(),
+            }
+        }
+        EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => event
+            .paths
+            .iter()
+            .filter(|p| visible(p))
+            .map(|p| WatchEvent::Created {
+                wiki_name: name.clone(),
+                path: relative(p),
+            })
+            .collect(),
+        EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => event
+            .paths
+            .iter()
+            .filter(|p| visible(p))
+            .map(|p| WatchEvent::Deleted {
+                wiki_name: name.clone(),
+                path: relative(p),
+            })
+            .collect(),
+        EventKind::Modify(ModifyKind::Name(_)) => Vec::new(),
+        EventKind::Modify(_) => event
+            .paths
+            .iter()
+            .filter(|p| visible(p) && !p.is_dir())
+            .map(|p| WatchEvent::Modified {
+                wiki_name: name.clone(),
+                path: relative(p),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 判断路径本身或其任一上级目录是否被过滤
+fn is_filtered(root: &Path, filter: &TreeFilter, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return true;
+    };
+
+    let mut current = root.to_path_buf();
+    let mut components = relative.components().peekable();
+    while let Some(component) = components.next() {
+        current.push(component);
+        // 上级路径一定是目录；路径本身可能已被删除，无法判断时按文件处理
+        let is_directory = components.peek().is_some() || current.is_dir();
+        if filter.is_hidden(&current, is_directory) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_full::notify::event::{CreateKind, DataChange, RemoveKind};
+
+    /// 测试文件系统事件到界面事件的转换
+    ///
+    /// # 测试目标：
+    /// * 验证创建、修改、删除和重命名事件被转换为带相对路径的界面事件
+    /// * 验证 `.git` 目录和被忽略路径中的事件被丢弃
+    #[test]
+    fn test_translate_event() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        crate::git::Repository::init(root, None, None).unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/a.md"), "a").unwrap();
+        let filter = TreeFilter::new(root, &["drafts/".to_string()]);
+
+        let translate = |kind: EventKind, paths: &[&str]| {
+            let mut event = Event::new(kind);
+            for path in paths {
+                event = event.add_path(root.join(path));
+            }
+            translate_event("wiki", root, &filter, &event)
+        };
+
+        assert_eq!(
+            translate(EventKind::Create(CreateKind::File), &["docs/a.md"]),
+            vec![WatchEvent::Created {
+                wiki_name: "wiki".to_string(),
+                path: "docs/a.md".to_string()
+            }]
+        );
+        assert_eq!(
+            translate(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                &["docs/a.md"]
+            ),
+            vec![WatchEvent::Modified {
+                wiki_name: "wiki".to_string(),
+                path: "docs/a.md".to_string()
+            }]
+        );
+        assert_eq!(
+            translate(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &["docs/old.md", "docs/a.md"]
+            ),
+            vec![WatchEvent::Renamed {
+                wiki_name: "wiki".to_string(),
+                from: "docs/old.md".to_string(),
+                to: "docs/a.md".to_string()
+            }]
+        );
+        assert_eq!(
+            translate(EventKind::Remove(RemoveKind::File), &["docs/gone.md"]),
+            vec![WatchEvent::Deleted {
+                wiki_name: "wiki".to_string(),
+                path: "docs/gone.md".to_string()
+            }]
+        );
+
+        // 被过滤的路径
+        assert!(translate(EventKind::Create(CreateKind::File), &[".git/index.lock"]).is_empty());
+        assert!(translate(EventKind::Create(CreateKind::File), &["drafts/wip.md"]).is_empty());
+        assert!(translate(EventKind::Remove(RemoveKind::File), &[".a.md.swp"]).is_empty());
+    }
+
+    /// 测试通过符号链接路径监视知识库
+    ///
+    /// # 测试目标：
+    /// * 验证根目录经过符号链接（如 macOS 上 `/var` 指向 `/private/var`）时，
+    ///   事件仍能转换为相对路径推送，而不是因路径前缀不同被丢弃
+    #[cfg(unix)]
+    #[test]
+    fn test_watch_symlinked_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let real_root = temp_dir.path().join("real");
+        std::fs::create_dir_all(&real_root).unwrap();
+        let linked_root = temp_dir.path().join("linked");
+        std::os::unix::fs::symlink(&real_root, &linked_root).unwrap();
+
+        let registry = WatcherRegistry::default();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        registry
+            .watch("wiki", &linked_root, &[], move |event| {
+                let _ = sender.send(event);
+            })
+            .unwrap();
+        std::fs::write(linked_root.join("page.md"), "page").unwrap();
+
+        let event = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(
+            event,
+            WatchEvent::Created {
+                wiki_name: "wiki".to_string(),
+                path: "page.md".to_string()
+            }
+        );
+    }
+}
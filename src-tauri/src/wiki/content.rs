@@ -0,0 +1,325 @@
+//! 页面内容读写模块
+//!
+//! 读取文件时会返回内容的版本标识（内容的 SHA-256 哈希），保存时与磁盘上的当前版本比较。
+//! 如果文件在编辑期间被同步或外部编辑器修改，保存会被拒绝，避免过期的编辑内容覆盖新的修改。
+//!
+//! 所有写入都先写到同一目录下的临时文件，同步到磁盘后再重命名为目标文件，
+//! 写入过程中崩溃或断电时，目标文件要么保持原样，要么是完整的新内容。
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::Error;
+
+/// 文件内容及其版本信息
+///
+/// # 字段
+/// * `content` - 文件内容
+/// * `version` - 内容的版本标识，保存时传回以检测冲突
+/// * `modified` - 最后修改时间（Unix 时间戳，单位为秒），无法获取时为None
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileContent {
+    pub content: String,
+    pub version: String,
+    pub modified: Option<u64>,
+}
+
+/// 临时文件名的后缀，以 `~` 结尾的文件不会出现在文件树中
+const TEMP_SUFFIX: &str = ".tmp~";
+
+/// 临时文件超过该时间未被修改时才视为中断遗留的文件，较新的临时文件可能正在被并发的写入使用
+const STALE_TEMP_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// 计算内容的版本标识
+pub fn content_version(content: &[u8]) -> String {
+    sha256_hex(content)
+}
+
+/// 计算数据的 SHA-256 哈希，返回小写十六进制字符串
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 读取文本文件及其版本信息
+///
+/// # 参数
+/// * `path` - 文件的完整路径
+///
+/// # 返回值
+/// * `Result<FileContent, Error>` - 成功时返回文件内容和版本信息
+/// * 失败时返回 `Err(Error)`，如文件不存在或不是有效的 UTF-8 文本
+pub fn read_text(path: &Path) -> Result<FileContent, Error> {
+    let bytes = fs::read(path)?;
+    let version = content_version(&bytes);
+    let content = std::str::from_utf8(&bytes)?.to_string();
+
+    Ok(FileContent {
+        content,
+        version,
+        modified: modified_time(path),
+    })
+}
+
+/// 写入文本文件
+///
+/// 如果提供了 `expected_version`，只有磁盘上的当前内容与该版本一致时才会写入；
+/// 文件已被修改或删除时返回 `Error::Conflict`，其中包含磁盘上的当前内容。
+/// 要写入的内容与磁盘上的内容相同时不视为冲突。
+///
+/// # 参数
+/// * `path` - 文件的完整路径
+/// * `content` - 要写入的内容
+/// * `expected_version` - 读取文件时得到的版本标识，为 `None` 时直接覆盖
+///
+/// # 返回值
+/// * `Result<FileContent, Error>` - 成功时返回写入后的内容和新的版本信息
+/// * 失败时返回 `Err(Error)`
+pub fn write_text(
+    path: &Path,
+    content: &str,
+    expected_version: Option<&str>,
+) -> Result<FileContent, Error> {
+    let version = content_version(content.as_bytes());
+
+    if let Some(expected) = expected_version {
+        let current = match read_text(path) {
+            Ok(current) => Some(current),
+            Err(Error::Io(e)) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let unchanged = current
+            .as_ref()
+            .map(|c| c.version == expected || c.version == version)
+            .unwrap_or(false);
+        if !unchanged {
+            return Err(Error::Conflict {
+                path: path.display().to_string(),
+                current,
+            });
+        }
+    }
+
+    write_atomic(path, content.as_bytes())?;
+
+    Ok(FileContent {
+        content: content.to_string(),
+        version,
+        modified: modified_time(path),
+    })
+}
+
+/// 原子地写入文件
+///
+/// 内容先写入同一目录下的临时文件并同步到磁盘，再重命名为目标文件，
+/// 因此目标文件不会出现只写了一部分的情况。目标文件已存在时会保留其权限。
+/// 父目录不存在时会自动创建，之前写入中断时遗留的临时文件会被清理。
+///
+/// # 参数
+/// * `path` - 目标文件的完整路径
+/// * `content` - 要写入的内容
+///
+/// # 返回值
+/// * `io::Result<()>` - 成功时返回 `Ok(())`，失败时目标文件保持不变
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    write_atomic_with(path, |file| file.write_all(content))
+}
+
+/// 原子地写入文件，由 `write` 向临时文件写入内容
+fn write_atomic_with<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| io::Error::new(ErrorKind::InvalidInput, "无效的文件路径"))?;
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    fs::create_dir_all(&dir)?;
+    remove_stale_temp_files(&dir, file_name);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let temp_path = dir.join(format!(
+        ".{}.{}-{}{}",
+        file_name,
+        std::process::id(),
+        nanos,
+        TEMP_SUFFIX
+    ));
+
+    let result = (|| {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)?;
+        write(&mut file)?;
+        file.sync_all()?;
+
+        // 保留原文件的权限
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+        fs::rename(&temp_path, path)
+    })();
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    // 同步目录，确保重命名本身已写入磁盘（Windows 不支持打开目录，忽略失败）
+    if let Ok(dir_file) = File::open(&dir) {
+        let _ = dir_file.sync_all();
+    }
+    Ok(())
+}
+
+/// 删除之前写入同一文件时中断遗留的临时文件
+///
+/// 只删除超过 `STALE_TEMP_AGE` 未被修改的临时文件，不会影响正在进行的写入。
+fn remove_stale_temp_files(dir: &Path, file_name: &str) {
+    let prefix = format!(".{}.", file_name);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if !name.starts_with(&prefix) || !name.ends_with(TEMP_SUFFIX) {
+            continue;
+        }
+        let is_stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .map(|age| age >= STALE_TEMP_AGE)
+            .unwrap_or(false);
+        if is_stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// 获取文件的最后修改时间
+fn modified_time(path: &Path) -> Option<u64> {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试带版本检查的写入
+    ///
+    /// # 测试目标：
+    /// * 验证使用最新版本标识时可以写入，并返回新的版本标识
+    /// * 验证文件被修改或删除后，使用旧版本标识写入会返回包含当前内容的冲突错误
+    /// * 验证不提供版本标识时直接覆盖
+    #[test]
+    fn test_write_text_conflict() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("page.md");
+        fs::write(&path, "v1").unwrap();
+
+        let read = read_text(&path).unwrap();
+        assert_eq!(read.content, "v1");
+        let saved = write_text(&path, "v2", Some(&read.version)).unwrap();
+        assert_eq!(saved.version, content_version(b"v2"));
+
+        // 文件在编辑期间被外部修改
+        fs::write(&path, "external").unwrap();
+        match write_text(&path, "v3", Some(&saved.version)) {
+            Err(Error::Conflict { current, .. }) => {
+                assert_eq!(current.unwrap().content, "external");
+            }
+            other => panic!("expected conflict, got {:?}", other),
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "external");
+
+        // 文件在编辑期间被删除
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            write_text(&path, "v3", Some(&saved.version)),
+            Err(Error::Conflict { current: None, .. })
+        ));
+
+        write_text(&path, "forced", None).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "forced");
+    }
+
+    /// 测试写入中断时目标文件保持完整
+    ///
+    /// # 测试目标：
+    /// * 验证写入临时文件时出错，目标文件保持原内容且不遗留临时文件
+    /// * 验证崩溃遗留的临时文件不影响目标文件，并在下次写入时被清理
+    /// * 验证较新的临时文件可能属于并发的写入，不会被清理
+    /// * 验证写入后保留原文件的权限
+    #[test]
+    fn test_write_atomic_interrupted() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("page.md");
+        write_atomic(&path, b"original").unwrap();
+
+        // 写入一半时出错
+        let result = write_atomic_with(&path, |file| {
+            file.write_all(b"trunc")?;
+            Err(io::Error::other("interrupted"))
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+        // 模拟进程在重命名前崩溃遗留的临时文件
+        let stale = temp_dir.path().join(".page.md.1-1.tmp~");
+        fs::write(&stale, "trunc").unwrap();
+        File::options()
+            .write(true)
+            .open(&stale)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_TEMP_AGE)
+            .unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+
+        // 模拟另一个写入正在使用的临时文件
+        let in_progress = temp_dir.path().join(".page.md.2-2.tmp~");
+        fs::write(&in_progress, "partial").unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        write_atomic(&path, b"updated").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "updated");
+        assert!(!stale.exists());
+        assert!(in_progress.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
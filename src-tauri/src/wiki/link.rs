@@ -0,0 +1,378 @@
+//! Markdown 链接解析模块
+//!
+//! 此模块负责从 Markdown 页面中提取链接，并将链接目标解析为知识库内的相对路径。
+//! 支持标准的 `[文本](目标)`、`![图片](目标)` 语法以及 `[[页面]]` 形式的双链语法。
+
+use std::path::Path;
+
+use super::{list_files, Error};
+
+/// 页面中的一个链接
+///
+/// # 字段
+/// * `target` - 链接的原始目标，例如 `../images/a.png` 或 `页面`
+/// * `is_wikilink` - 是否为 `[[页面]]` 形式的双链
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub target: String,
+    pub is_wikilink: bool,
+}
+
+/// 从 Markdown 内容中提取所有链接
+///
+/// # 参数
+/// * `content` - Markdown 文本内容
+///
+/// # 返回值
+/// * `Vec<Link>` - 按出现顺序排列的链接列表
+pub fn extract_links(content: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut rest = content;
+
+    while let Some(pos) = rest.find(['[', ']']) {
+        let after = &rest[pos..];
+
+        // 双链语法 [[页面]] 或 [[页面|别名]]
+        if let Some(inner) = after.strip_prefix("[[") {
+            if let Some(end) = inner.find("]]") {
+                let target = inner[..end].split('|').next().unwrap_or("").trim();
+                if !target.is_empty() {
+                    links.push(Link {
+                        target: target.to_string(),
+                        is_wikilink: true,
+                    });
+                }
+                rest = &inner[end + 2..];
+                continue;
+            }
+        }
+
+        // 标准语法 [文本](目标)
+        if let Some(inner) = after.strip_prefix("](") {
+            if let Some((target, consumed)) = parse_link_destination(inner) {
+                if !target.is_empty() {
+                    links.push(Link {
+                        target,
+                        is_wikilink: false,
+                    });
+                }
+                rest = &inner[consumed..];
+                continue;
+            }
+        }
+
+        rest = &after[1..];
+    }
+
+    links
+}
+
+/// 解析 `](` 之后的链接目标，返回目标和消耗的字节数
+fn parse_link_destination(input: &str) -> Option<(String, usize)> {
+    // 尖括号形式：<目标 含空格>
+    if let Some(inner) = input.strip_prefix('<') {
+        let end = inner.find('>')?;
+        let close = inner[end..].find(')')? + end;
+        return Some((inner[..end].to_string(), close + 2));
+    }
+
+    // 普通形式，允许一层嵌套括号，目标之后可以带标题
+    let mut depth = 0usize;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => {
+                let target = input[..i].split_whitespace().next().unwrap_or("");
+                return Some((target.to_string(), i + 1));
+            }
+            ')' => depth -= 1,
+            '\n' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 判断链接目标是否为外部链接（网址、邮件或纯锚点）
+pub fn is_external(target: &str) -> bool {
+    target.starts_with('#')
+        || target.starts_with("mailto:")
+        || target.starts_with("data:")
+        || target.contains("://")
+}
+
+/// 将链接目标解析为知识库内的相对路径
+///
+/// 链接目标相对于所在页面的目录进行解析，以 `/` 开头的目标相对于知识库根目录解析。
+/// 锚点和查询参数会被去除，`%20` 等转义字符会被还原。
+///
+/// # 参数
+/// * `page` - 链接所在页面的相对路径，使用 `/` 作为分隔符
+/// * `target` - 链接的原始目标
+///
+/// # 返回值
+/// * `Option<String>` - 解析后的相对路径；外部链接或越过知识库根目录的链接返回 `None`
+pub fn resolve_link(page: &str, target: &str) -> Option<String> {
+    if is_external(target) {
+        return None;
+    }
+
+    let target = target.split(['#', '?']).next().unwrap_or("");
+    let target = percent_decode(target);
+    if target.is_empty() {
+        return None;
+    }
+
+    let mut parts: Vec<&str> = Vec::new();
+    if !target.starts_with('/') {
+        parts.extend(page.split('/').filter(|s| !s.is_empty()));
+        // 去掉页面文件名，保留其所在目录
+        parts.pop();
+    }
+
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            other => parts.push(other),
+        }
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("/"))
+    }
+}
+
+/// 计算从页面指向知识库内文件的相对链接，是 `resolve_link` 的逆操作
+///
+/// 链接中的空格、括号等会破坏 Markdown 链接语法的字符会被转义。
+///
+/// # 参数
+/// * `page` - 链接所在页面的相对路径，使用 `/` 作为分隔符
+/// * `target` - 目标文件相对于知识库根目录的路径
+///
+/// # 返回值
+/// * `String` - 相对于页面所在目录的链接
+pub fn relative_link(page: &str, target: &str) -> String {
+    let mut page_dir: Vec<&str> = page.split('/').filter(|s| !s.is_empty()).collect();
+    page_dir.pop();
+    let target: Vec<&str> = target.split('/').filter(|s| !s.is_empty()).collect();
+
+    let common = page_dir
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); page_dir.len() - common];
+    parts.extend(target[common..].iter().map(|part| percent_encode(part)));
+    parts.join("/")
+}
+
+/// 转义链接中会破坏 Markdown 语法或含义的字符
+pub fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            ' ' | '(' | ')' | '<' | '>' | '[' | ']' | '#' | '?' | '%' | '"' | '\'' => {
+                out.push_str(&format!("%{:02X}", c as u32))
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 还原链接中的百分号转义字符
+pub fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        // 按字节判断，`%` 后面紧跟多字节字符时不能按字符串下标切片
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            out.push(hex_value(bytes[i + 1]) << 4 | hex_value(bytes[i + 2]));
+            i += 3;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 将十六进制数字字符转换为对应的数值，调用方需保证是合法的十六进制数字
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+/// 判断双链 `[[页面]]` 是否指向给定的相对路径
+///
+/// 双链按页面名称匹配，既可以写文件名（不带 `.md` 后缀），也可以写从根目录开始的路径。
+pub fn wikilink_matches(target: &str, path: &str) -> bool {
+    let target = target.split('#').next().unwrap_or("").trim();
+    let without_ext = path.strip_suffix(".md").unwrap_or(path);
+    let stem = without_ext.rsplit('/').next().unwrap_or(without_ext);
+    target == path || target == without_ext || target == stem
+}
+
+/// 查找链接到指定路径（或其子路径）的所有页面
+///
+/// # 参数
+/// * `root` - 知识库根目录
+/// * `target` - 被链接的文件或文件夹的相对路径
+///
+/// # 返回值
+/// * `Result<Vec<String>, Error>` - 成功时返回包含链接的页面相对路径（不含目标自身）
+pub fn find_inbound_links(root: &Path, target: &str) -> Result<Vec<String>, Error> {
+    let target = target.trim_matches('/');
+    let folder_prefix = format!("{}/", target);
+    let points_to_target = |path: &str| path == target || path.starts_with(&folder_prefix);
+
+    let files = list_files(root)?;
+    // 被删除的目标可能是文件夹，双链需要与其中每个文件逐一匹配
+    let targeted: Vec<&String> = files.iter().filter(|f| points_to_target(f)).collect();
+
+    let mut pages = Vec::new();
+    for page in &files {
+        if !page.ends_with(".md") || points_to_target(page) {
+            continue;
+        }
+
+        let content = match std::fs::read_to_string(root.join(page)) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+
+        let linked = extract_links(&content).iter().any(|link| {
+            if link.is_wikilink {
+                targeted
+                    .iter()
+                    .any(|path| wikilink_matches(&link.target, path))
+            } else {
+                resolve_link(page, &link.target)
+                    .map(|resolved| points_to_target(&resolved))
+                    .unwrap_or(false)
+            }
+        });
+
+        if linked {
+            pages.push(page.clone());
+        }
+    }
+
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试链接提取与解析
+    ///
+    /// # 测试目标：
+    /// * 验证标准链接、图片链接与双链均能被提取
+    /// * 验证相对路径、根路径与外部链接的解析结果
+    #[test]
+    fn test_extract_and_resolve_links() {
+        let content = "见 [说明](../docs/guide.md#安装) 和 ![图](<img/a b.png> \"标题\")，\
+                       以及 [[首页|主页]] 与 [网站](https://example.com)。";
+        let links = extract_links(content);
+        let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(
+            targets,
+            vec![
+                "../docs/guide.md#安装",
+                "img/a b.png",
+                "首页",
+                "https://example.com"
+            ]
+        );
+        assert!(links[2].is_wikilink);
+
+        assert_eq!(
+            resolve_link("notes/page.md", "../docs/guide.md#安装"),
+            Some("docs/guide.md".to_string())
+        );
+        assert_eq!(
+            resolve_link("notes/page.md", "img/a%20b.png"),
+            Some("notes/img/a b.png".to_string())
+        );
+        assert_eq!(
+            resolve_link("notes/page.md", "/index.md"),
+            Some("index.md".to_string())
+        );
+        assert_eq!(resolve_link("page.md", "../outside.md"), None);
+        assert_eq!(resolve_link("page.md", "https://example.com"), None);
+
+        assert_eq!(
+            relative_link("notes/page.md", "assets/a b.png"),
+            "../assets/a%20b.png"
+        );
+        assert_eq!(
+            relative_link("notes/page.md", "notes/page.assets/x.pdf"),
+            "page.assets/x.pdf"
+        );
+        assert_eq!(
+            resolve_link(
+                "notes/sub/page.md",
+                &relative_link("notes/sub/page.md", "img/(1).png")
+            ),
+            Some("img/(1).png".to_string())
+        );
+    }
+
+    /// 测试 `percent_decode` 函数
+    ///
+    /// # 测试目标：
+    /// * 验证大小写十六进制转义均能还原，包括多字节 UTF-8 字符
+    /// * 验证不完整或非法的转义原样保留，`%` 后紧跟多字节字符时不会崩溃
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%20b%2fc"), "a b/c");
+        assert_eq!(percent_decode("%E4%B8%AD"), "中");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+        assert_eq!(percent_decode("/%中"), "/%中");
+        assert_eq!(percent_decode("%中文"), "%中文");
+        assert_eq!(resolve_link("page.md", "%中"), Some("%中".to_string()));
+    }
+
+    /// 测试 `find_inbound_links` 函数
+    ///
+    /// # 测试目标：
+    /// * 验证能找到链接到文件以及文件夹内文件的页面
+    /// * 验证双链按页面名称匹配
+    #[test]
+    fn test_find_inbound_links() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/guide.md"), "# 指南").unwrap();
+        std::fs::write(root.join("a.md"), "[指南](docs/guide.md)").unwrap();
+        std::fs::write(root.join("b.md"), "[[guide]]").unwrap();
+        std::fs::write(root.join("c.md"), "无链接").unwrap();
+
+        assert_eq!(
+            find_inbound_links(root, "docs/guide.md").unwrap(),
+            vec!["a.md".to_string(), "b.md".to_string()]
+        );
+        assert_eq!(
+            find_inbound_links(root, "docs").unwrap(),
+            vec!["a.md".to_string(), "b.md".to_string()]
+        );
+        assert!(find_inbound_links(root, "c.md").unwrap().is_empty());
+    }
+}
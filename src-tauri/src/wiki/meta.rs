@@ -0,0 +1,124 @@
+//! 知识库元数据模块
+//!
+//! 知识库的元数据保存在仓库内的 `.markwiki/wiki.toml` 文件中，会随知识库一起同步。
+//! 元数据包括显示名称、描述、图标、默认页面、忽略路径以及知识库级别的设置。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Error;
+
+/// 知识库内保存应用数据的目录名称
+pub const META_DIR: &str = ".markwiki";
+
+/// 元数据文件名称
+pub const META_FILE: &str = "wiki.toml";
+
+/// 知识库元数据
+///
+/// 所有字段都是可选的，缺失的字段使用默认值，因此没有元数据文件的知识库同样可以正常使用。
+///
+/// # 字段
+/// * `display_name` - 显示名称，为 `None` 时使用知识库名称
+/// * `description` - 知识库描述
+/// * `icon` - 图标（emoji 或知识库内图片的相对路径）
+/// * `default_page` - 打开知识库时默认显示的页面，相对于知识库根目录
+/// * `ignore` - 在文件树中隐藏的路径规则，语法与 `.gitignore` 相同
+/// * `settings` - 知识库级别的其他设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WikiMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_page: Option<String>,
+    pub ignore: Vec<String>,
+    pub settings: BTreeMap<String, toml::Value>,
+}
+
+impl WikiMeta {
+    /// 获取元数据文件的完整路径
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(META_DIR).join(META_FILE)
+    }
+
+    /// 从知识库中加载元数据
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回元数据，元数据文件不存在时返回默认值
+    /// * 失败时返回 `Err(Error)`，如文件无法读取或格式错误
+    pub fn load(root: &Path) -> Result<Self, Error> {
+        let path = Self::path(root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| Error::Meta(e.to_string()))
+    }
+
+    /// 将元数据保存到知识库中
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error)`
+    pub fn save(&self, root: &Path) -> Result<(), Error> {
+        let content = toml::to_string_pretty(self).map_err(|e| Error::Meta(e.to_string()))?;
+        super::content::write_atomic(&Self::path(root), content.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试元数据的读取和保存
+    ///
+    /// # 测试目标：
+    /// * 验证没有元数据文件时返回默认值
+    /// * 验证保存后能读取到相同的元数据
+    /// * 验证手写的元数据文件中缺失的字段使用默认值
+    #[test]
+    fn test_load_and_save() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        assert_eq!(WikiMeta::load(root).unwrap(), WikiMeta::default());
+
+        let mut meta = WikiMeta {
+            display_name: Some("团队手册".to_string()),
+            default_page: Some("index.md".to_string()),
+            ignore: vec!["drafts/".to_string()],
+            ..Default::default()
+        };
+        meta.settings
+            .insert("spell_check".to_string(), toml::Value::Boolean(true));
+        meta.save(root).unwrap();
+        assert!(root.join(".markwiki/wiki.toml").is_file());
+        assert_eq!(WikiMeta::load(root).unwrap(), meta);
+
+        fs::write(WikiMeta::path(root), "description = \"说明\"\n").unwrap();
+        let meta = WikiMeta::load(root).unwrap();
+        assert_eq!(meta.description.as_deref(), Some("说明"));
+        assert!(meta.display_name.is_none());
+        assert!(meta.ignore.is_empty());
+
+        fs::write(WikiMeta::path(root), "ignore = 1").unwrap();
+        assert!(matches!(WikiMeta::load(root), Err(Error::Meta(_))));
+    }
+}
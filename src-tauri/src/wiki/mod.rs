@@ -4,7 +4,16 @@
 //! 此模块提供了知识库的核心功能，包括知识库的创建、查询和管理。
 //! 它定义了知识库的数据结构，并实现了获取知识库列表、文件结构等功能。
 
+pub mod content;
+pub mod link;
+pub mod meta;
+pub mod page;
+pub mod search;
+pub mod tag;
+pub mod template;
+
 use crate::git;
+use meta::WikiMeta;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -25,11 +34,6 @@ pub enum Error {
     #[error("UTF-8 conversion error: {0}")]
     Utf8(#[from] std::str::Utf8Error),
 
-    /// 路径转换错误
-    #[cfg(not(target_os = "android"))]
-    #[error("Path conversion error: {0}")]
-    Path(String),
-
     /// 知识库已存在错误
     #[error("Wiki already exists: {0}")]
     AlreadyExists(String),
@@ -45,6 +49,59 @@ pub enum Error {
     /// 构建文件树失败
     #[error("Failed to build file tree: {0}")]
     BuildFileTree(String),
+
+    /// 非法的相对路径（绝对路径或越过知识库根目录）
+    #[error("Invalid path: {0}")]
+    InvalidPath(String),
+
+    /// 非法的知识库名称
+    #[error("Invalid wiki name: {0}")]
+    InvalidName(String),
+
+    /// 存在尚未提交或推送的修改
+    #[error(
+        "Wiki has unsaved work (uncommitted changes: {uncommitted}, unpushed commits: {unpushed})"
+    )]
+    UnsavedWork { uncommitted: bool, unpushed: usize },
+
+    /// 归档失败
+    #[error("Archive error: {0}")]
+    Archive(#[from] crate::archive::Error),
+
+    /// 配置读写失败
+    #[error("Config error: {0}")]
+    Config(#[from] crate::config::Error),
+
+    /// 知识库元数据文件读写失败
+    #[error("Wiki metadata error: {0}")]
+    Meta(String),
+
+    /// 对外部知识库执行了仅适用于存储目录内知识库的操作
+    #[error("Wiki is registered from an external folder: {0}")]
+    External(String),
+
+    /// 页面的 front matter 无法解析或序列化
+    #[error("Front matter error: {0}")]
+    FrontMatter(String),
+
+    /// 非法的标签名称
+    #[error("Invalid tag: {0}")]
+    InvalidTag(String),
+
+    /// 页面存在尚未提交的修改，无法只提交本次操作的改动
+    #[error("Pages have uncommitted changes: {}", .0.join(", "))]
+    UncommittedPages(Vec<String>),
+
+    /// 页面模板不存在
+    #[error("Template not found: {0}")]
+    TemplateNotFound(String),
+
+    /// 文件在读取之后被修改，`current` 为磁盘上的当前内容，文件已被删除时为 `None`
+    #[error("File changed on disk: {path}")]
+    Conflict {
+        path: String,
+        current: Option<content::FileContent>,
+    },
 }
 
 /// 知识库项结构体，用于表示知识库的基本信息
@@ -55,19 +112,25 @@ pub enum Error {
 /// * `name` - 知识库的名称，通常与存储目录名称一致
 /// * `has_remote_repo` - 布尔值，表示该知识库是否配置了远程Git仓库
 /// * `path` - 知识库的完整文件路径
+/// * `is_external` - 布尔值，表示该知识库是否为注册的外部文件夹（不在统一存储目录中）
+/// * `meta` - 知识库元数据，来自仓库内的 `.markwiki/wiki.toml` 文件
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Wiki {
     name: String,
     has_remote_repo: bool,
     pub path: String,
+    #[serde(default)]
+    is_external: bool,
+    #[serde(default)]
+    pub meta: WikiMeta,
 }
 
 impl Wiki {
     /// 根据名称获取知识库实例
     ///
-    /// 该函数尝试根据指定的名称从存储目录中加载知识库。
-    /// 首先构建知识库的存储路径，然后检查该路径是否存在，
-    /// 最后尝试打开Git仓库并返回知识库实例。
+    /// 该函数尝试根据指定的名称加载知识库。
+    /// 优先打开统一存储目录下的同名知识库，不存在时再查找应用配置中注册的外部知识库，
+    /// 与 `list` 对重名知识库的处理保持一致。
     ///
     /// # 参数
     /// * `name` - 知识库的名称
@@ -76,10 +139,28 @@ impl Wiki {
     /// * `Result<Self, ()>` - 成功时返回 `Ok(Wiki)`，包含知识库实例
     /// * 失败时返回 `Err(())`，表示无法获取知识库实例（如路径不存在或不是Git仓库）
     pub fn from_name(name: &str) -> Result<Self, Error> {
-        // 构建知识库的存储路径
-        let path = Self::get_wiki_storage_dir()
-            .map_err(|_| Error::StorageDir)?
-            .join(name);
+        // 优先打开存储目录中的知识库
+        let path = Self::storage_path(name)?;
+        let error = match Self::open(name, &path, false) {
+            Ok(wiki) => return Ok(wiki),
+            Err(e) => e,
+        };
+
+        // 再查找注册的外部知识库
+        let config = crate::config::AppConfig::load_or_default()?;
+        match config.registered_wikis.iter().find(|w| w.name == name) {
+            Some(registered) => Self::open(name, Path::new(&registered.path), true),
+            None => Err(error),
+        }
+    }
+
+    /// 打开指定路径下的知识库
+    ///
+    /// # 参数
+    /// * `name` - 知识库的名称
+    /// * `path` - 知识库的完整路径
+    /// * `is_external` - 是否为注册的外部知识库
+    fn open(name: &str, path: &Path, is_external: bool) -> Result<Self, Error> {
         if !path.exists() {
             return Err(Error::NotFound(format!(
                 "知识库路径不存在: {}",
@@ -87,17 +168,191 @@ impl Wiki {
             )));
         }
         // 检查是否为 git 仓库
-        let repo = git::Repository::open(&path)?;
+        let repo = git::Repository::open(path)?;
 
-        // 构建知识库实例
+        // 构建知识库实例，元数据文件损坏时使用默认值，不影响知识库的打开
         let has_remote_repo = repo.has_remote_repo().unwrap_or(false);
         Ok(Wiki {
             name: name.to_string(),
             has_remote_repo,
             path: path.to_string_lossy().to_string(),
+            is_external,
+            meta: WikiMeta::load(path).unwrap_or_default(),
         })
     }
 
+    /// 获取所有知识库列表
+    ///
+    /// 该函数会遍历统一存储目录下的所有文件夹，将其中的 Git 仓库作为知识库，
+    /// 再合并应用配置中注册的外部知识库。与存储目录中知识库重名的注册项会被忽略（与 `from_name` 一致），
+    /// 注册路径已失效（不存在或不再是 Git 仓库）的注册项也会被跳过。
+    ///
+    /// # 返回值
+    /// * `Result<Vec<Wiki>, Error>` - 成功时返回按名称排序的知识库列表
+    /// * 失败时返回 `Err(Error)`
+    pub fn list() -> Result<Vec<Self>, Error> {
+        // 统计知识库信息
+        let mut wikis = Vec::new();
+
+        // 遍历 Wikis 目录下的所有文件夹
+        for entry in fs::read_dir(Self::get_wiki_storage_dir()?)? {
+            let path = entry?.path();
+
+            // 跳过非目录
+            if !path.is_dir() {
+                continue;
+            }
+
+            // 将文件夹名称转换为知识库名称
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if Self::validate_name(name).is_err() {
+                continue;
+            }
+
+            // 尝试打开知识库
+            if let Ok(wiki) = Self::open(name, &path, false) {
+                wikis.push(wiki);
+            }
+        }
+
+        // 合并注册的外部知识库
+        let config = crate::config::AppConfig::load_or_default()?;
+        for registered in &config.registered_wikis {
+            if wikis.iter().any(|w| w.name == registered.name) {
+                continue;
+            }
+            if let Ok(wiki) = Self::open(&registered.name, Path::new(&registered.path), true) {
+                wikis.push(wiki);
+            }
+        }
+
+        wikis.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(wikis)
+    }
+
+    /// 将已有的 Git 仓库注册为外部知识库
+    ///
+    /// 注册只会在应用配置中记录知识库的名称和路径，不会移动或修改仓库中的任何文件。
+    ///
+    /// # 参数
+    /// * `path` - 仓库的绝对路径
+    /// * `name` - 知识库名称，为 `None` 时使用仓库文件夹的名称
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回 `Ok(Wiki)`，包含注册的知识库实例
+    /// * 失败时返回 `Err(Error)`，如路径不是 Git 仓库或名称已被占用
+    pub fn register(path: &Path, name: Option<&str>) -> Result<Self, Error> {
+        let name = Self::registration_name(path, name)?;
+        Self::add_registration(path, &name)
+    }
+
+    /// 校验要注册的文件夹，并确定注册使用的知识库名称
+    ///
+    /// # 参数
+    /// * `path` - 文件夹的绝对路径
+    /// * `name` - 知识库名称，为 `None` 时使用文件夹的名称
+    ///
+    /// # 返回值
+    /// * `Result<String, Error>` - 成功时返回合法且未被占用的知识库名称
+    /// * 失败时返回 `Err(Error)`，如路径不是已存在的绝对路径或名称已被占用
+    fn registration_name(path: &Path, name: Option<&str>) -> Result<String, Error> {
+        if !path.is_absolute() || !path.is_dir() {
+            return Err(Error::InvalidPath(path.display().to_string()));
+        }
+
+        // 默认使用文件夹名称作为知识库名称
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| Error::InvalidPath(path.display().to_string()))?
+                .to_string(),
+        };
+        Self::validate_name(&name)?;
+        if Self::exists(&name) {
+            return Err(Error::AlreadyExists(format!("知识库已存在: {}", name)));
+        }
+        Ok(name)
+    }
+
+    /// 将 Git 仓库以给定名称记录到应用配置中
+    fn add_registration(path: &Path, name: &str) -> Result<Self, Error> {
+        // 检查是否为 git 仓库
+        let wiki = Self::open(name, path, true)?;
+
+        let mut config = crate::config::AppConfig::load_or_default()?;
+        config.registered_wikis.push(crate::config::RegisteredWiki {
+            name: name.to_string(),
+            path: wiki.path.clone(),
+        });
+        config.save()?;
+
+        Ok(wiki)
+    }
+
+    /// 在已有的普通文件夹中初始化 Git 仓库，并将其注册为外部知识库
+    ///
+    /// 名称在初始化仓库之前校验，注册失败时会删除新建的 `.git` 目录，文件夹保持原样。
+    ///
+    /// # 参数
+    /// * `path` - 文件夹的绝对路径
+    /// * `name` - 知识库名称，为 `None` 时使用文件夹的名称
+    /// * `username` - Git 用户名
+    /// * `email` - Git 邮箱
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回 `Ok(Wiki)`，包含注册的知识库实例
+    /// * 失败时返回 `Err(Error)`，如文件夹已经是 Git 仓库或名称已被占用
+    pub fn init_external(
+        path: &Path,
+        name: Option<&str>,
+        username: &str,
+        email: &str,
+    ) -> Result<Self, Error> {
+        let name = Self::registration_name(path, name)?;
+        if git::Repository::open(path).is_ok() {
+            return Err(Error::AlreadyExists(format!(
+                "文件夹已经是 Git 仓库: {}",
+                path.display()
+            )));
+        }
+
+        // 初始化 git 仓库，设置用户名和邮箱
+        let git_dir = path.join(".git");
+        let had_git_dir = git_dir.exists();
+        let result = git::Repository::init(path, Some(username), Some(email))
+            .map_err(Error::from)
+            .and_then(|_| Self::add_registration(path, &name));
+        if result.is_err() && !had_git_dir {
+            let _ = fs::remove_dir_all(&git_dir);
+        }
+        result
+    }
+
+    /// 取消注册外部知识库
+    ///
+    /// 只会从应用配置中移除注册信息，不会删除仓库中的任何文件。
+    ///
+    /// # 参数
+    /// * `name` - 知识库名称
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error::NotFound)`，表示没有该名称的外部知识库
+    pub fn unregister(name: &str) -> Result<(), Error> {
+        let mut config = crate::config::AppConfig::load_or_default()?;
+        let count = config.registered_wikis.len();
+        config.registered_wikis.retain(|w| w.name != name);
+        if config.registered_wikis.len() == count {
+            return Err(Error::NotFound(format!("没有注册的外部知识库: {}", name)));
+        }
+        config.save()?;
+        Ok(())
+    }
+
     /// 检查指定名称的知识库是否存在
     ///
     /// 该函数通过尝试调用`from_name`方法来检查指定名称的知识库是否存在。
@@ -125,9 +380,7 @@ impl Wiki {
     /// * 失败时返回 `Err(Error)`，表示无法创建知识库（如路径已存在或创建目录失败）
     pub fn create_local_wiki(name: &str, username: &str, email: &str) -> Result<Self, Error> {
         // 构造知识库的存储路径
-        let path = Self::get_wiki_storage_dir()
-            .map_err(|_| Error::StorageDir)?
-            .join(name);
+        let path = Self::storage_path(name)?;
         if path.exists() {
             return Err(Error::AlreadyExists(format!(
                 "知识库已存在: {}",
@@ -143,6 +396,8 @@ impl Wiki {
             name: name.to_string(),
             has_remote_repo: false,
             path: path.to_string_lossy().to_string(),
+            is_external: false,
+            meta: WikiMeta::default(),
         })
     }
 
@@ -167,9 +422,7 @@ impl Wiki {
         password: Option<&str>,
     ) -> Result<Self, Error> {
         // 构造知识库的存储路径
-        let path = Self::get_wiki_storage_dir()
-            .map_err(|_| Error::StorageDir)?
-            .join(name);
+        let path = Self::storage_path(name)?;
         if path.exists() {
             return Err(Error::AlreadyExists(format!(
                 "知识库已存在: {}",
@@ -182,53 +435,518 @@ impl Wiki {
             name: name.to_string(),
             has_remote_repo: true,
             path: path.to_string_lossy().to_string(),
+            is_external: false,
+            meta: WikiMeta::load(&path).unwrap_or_default(),
         })
     }
 
+    /// 校验知识库名称
+    ///
+    /// 知识库名称会直接作为存储目录下的文件夹名称，因此不能为空，
+    /// 不能包含路径分隔符或盘符，也不能以 `.` 开头（这类名称保留给应用内部使用）。
+    ///
+    /// # 参数
+    /// * `name` - 要校验的知识库名称
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 名称合法时返回 `Ok(())`，否则返回 `Err(Error::InvalidName)`
+    pub fn validate_name(name: &str) -> Result<(), Error> {
+        if name.trim().is_empty() || name.starts_with('.') || name.contains(['/', '\\', ':']) {
+            return Err(Error::InvalidName(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// 获取指定名称的知识库在存储目录下的路径
+    ///
+    /// 名称会先经过 `validate_name` 校验，保证得到的路径是存储目录的直接子目录。
+    fn storage_path(name: &str) -> Result<PathBuf, Error> {
+        Self::validate_name(name)?;
+        Ok(Self::get_wiki_storage_dir()
+            .map_err(|_| Error::StorageDir)?
+            .join(name))
+    }
+
+    /// 删除知识库
+    ///
+    /// 只能删除统一存储目录中的知识库，外部知识库需要通过 `unregister` 取消注册。
+    /// 默认情况下，如果知识库存在未提交的修改或未推送的提交，会拒绝删除并返回
+    /// `Error::UnsavedWork`，此时可以通过 `force` 强制删除。
+    /// 如果 `archive` 为 `true`，删除前会先将整个知识库（包括 Git 历史）打包到归档目录。
+    ///
+    /// # 参数
+    /// * `force` - 是否忽略未保存的工作强制删除
+    /// * `archive` - 删除前是否先打包归档
+    ///
+    /// # 返回值
+    /// * `Result<Option<PathBuf>, Error>` - 成功时返回归档文件路径（未归档时为 `None`）
+    /// * 失败时返回 `Err(Error)`
+    pub fn delete(self, force: bool, archive: bool) -> Result<Option<PathBuf>, Error> {
+        // 外部知识库只能取消注册，不能删除其文件
+        if self.is_external {
+            return Err(Error::External(self.name));
+        }
+
+        let path = PathBuf::from(&self.path);
+
+        // 确认知识库目录确实是存储目录的直接子目录，防止误删其他位置的内容
+        let storage_dir = Self::get_wiki_storage_dir()
+            .map_err(|_| Error::StorageDir)?
+            .canonicalize()?;
+        if path.canonicalize()?.parent() != Some(storage_dir.as_path()) {
+            return Err(Error::InvalidName(self.name));
+        }
+
+        // 检查是否存在会丢失的工作
+        if !force {
+            let repo = git::Repository::open(&path)?;
+            let uncommitted = repo.has_uncommitted_changes()?;
+            let unpushed = repo.ahead_count()?;
+            if uncommitted || unpushed > 0 {
+                return Err(Error::UnsavedWork {
+                    uncommitted,
+                    unpushed,
+                });
+            }
+        }
+
+        // 删除前打包归档
+        let archive_path = if archive {
+            let timestamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let archive_path =
+                Self::get_archive_dir()?.join(format!("{}-{}.tar.gz", self.name, timestamp));
+            crate::archive::pack_dir(&path, &archive_path)?;
+            Some(archive_path)
+        } else {
+            None
+        };
+
+        fs::remove_dir_all(&path)?;
+        Ok(archive_path)
+    }
+
+    /// 将知识库导出为便于离线交接的归档
+    ///
+    /// # 参数
+    /// * `output` - 归档文件的保存路径，扩展名为 `.zip` 时导出 zip 归档，否则导出 `.tar.gz` 归档
+    /// * `history` - 归档中包含的 Git 历史
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error)`，如归档路径位于知识库内部
+    pub fn export_archive(
+        &self,
+        output: &Path,
+        history: crate::archive::ArchiveHistory,
+    ) -> Result<(), Error> {
+        let root = Path::new(&self.path).canonicalize()?;
+        let parent = output
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        fs::create_dir_all(parent)?;
+        if parent.canonicalize()?.starts_with(&root) {
+            return Err(Error::InvalidPath(output.display().to_string()));
+        }
+
+        crate::archive::pack_wiki(
+            &root,
+            output,
+            crate::archive::ArchiveFormat::from_path(output),
+            history,
+        )?;
+        Ok(())
+    }
+
+    /// 从归档中恢复知识库
+    ///
+    /// 归档先解压到存储目录中的临时目录，完成后再重命名为知识库目录。
+    /// 归档中有 `.git` 目录时保留其中的历史，但会删除钩子脚本并重建仓库配置，
+    /// 远程仓库等设置需要重新配置；有 Git bundle 时用其中的历史初始化仓库；
+    /// 两者都没有时初始化新的仓库，并把所有文件作为第一次提交。
+    ///
+    /// # 参数
+    /// * `archive` - 归档文件路径
+    /// * `name` - 知识库名称，为 `None` 时使用归档的顶层目录名称
+    /// * `username` - Git 用户名
+    /// * `email` - Git 邮箱
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回恢复的知识库
+    /// * 失败时返回 `Err(Error)`，如归档中存在不安全的路径或知识库已存在
+    pub fn import_archive(
+        archive: &Path,
+        name: Option<&str>,
+        username: &str,
+        email: &str,
+    ) -> Result<Self, Error> {
+        let storage_dir = Self::get_wiki_storage_dir().map_err(|_| Error::StorageDir)?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let temp_path = storage_dir.join(format!(".import-{}", timestamp));
+
+        let result = (|| -> Result<Self, Error> {
+            let top_level = crate::archive::unpack(archive, &temp_path)?;
+            let name = name.unwrap_or(&top_level);
+            let path = Self::storage_path(name)?;
+            if path.exists() {
+                return Err(Error::AlreadyExists(format!(
+                    "知识库已存在: {}",
+                    path.display()
+                )));
+            }
+
+            let bundle = temp_path.join(crate::archive::BUNDLE_FILE_NAME);
+            let git_dir = temp_path.join(".git");
+            if git_dir.exists() && !git_dir.is_dir() {
+                // `.git` 文件可能指向其他位置的仓库，不能使用
+                fs::remove_file(&git_dir)?;
+            }
+            if git_dir.is_dir() {
+                Self::reset_git_dir(&temp_path, username, email)?;
+            } else if bundle.is_file() {
+                let repo = git::Repository::init(&temp_path, Some(username), Some(email))?;
+                repo.restore_bundle(&bundle)?;
+                fs::remove_file(&bundle)?;
+            } else {
+                let mut repo = git::Repository::init(&temp_path, Some(username), Some(email))?;
+                repo.add_all()?;
+                // 空归档没有可提交的内容，保留空仓库即可
+                match repo.commit("从归档导入") {
+                    Ok(()) | Err(git::Error::NothingToCommit) => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            fs::rename(&temp_path, &path)?;
+            Ok(Wiki {
+                name: name.to_string(),
+                has_remote_repo: git::Repository::open(&path)?.has_remote_repo()?,
+                path: path.to_string_lossy().to_string(),
+                is_external: false,
+                meta: WikiMeta::load(&path).unwrap_or_default(),
+            })
+        })();
+
+        if result.is_err() && temp_path.exists() {
+            let _ = fs::remove_dir_all(&temp_path);
+        }
+        result
+    }
+
+    /// 清理来自归档的 `.git` 目录，只保留提交历史
+    ///
+    /// 钩子脚本和仓库配置（如 `core.fsmonitor`、过滤器和远程仓库）可能在之后的 Git 操作中
+    /// 执行任意命令或访问其他位置，因此删除钩子目录、备用对象库和工作树配置，并重新生成仓库配置。
+    fn reset_git_dir(path: &Path, username: &str, email: &str) -> Result<(), Error> {
+        let git_dir = path.join(".git");
+        let hooks = git_dir.join("hooks");
+        if hooks.exists() {
+            fs::remove_dir_all(&hooks)?;
+        }
+        for file in [
+            "config",
+            "config.worktree",
+            "commondir",
+            "objects/info/alternates",
+        ] {
+            let file = git_dir.join(file);
+            if file.is_file() {
+                fs::remove_file(&file)?;
+            }
+        }
+        // 在已有的仓库上初始化会重新生成默认配置，提交历史保持不变
+        git::Repository::init(path, Some(username), Some(email))?;
+        Ok(())
+    }
+
+    /// 获取被删除知识库的归档目录
+    ///
+    /// 归档目录位于应用数据目录下的 `archive` 文件夹中，不会随知识库存储目录迁移。
+    pub fn get_archive_dir() -> Result<PathBuf, Error> {
+        Ok(crate::config::get_data_dir()?.join("archive"))
+    }
+
+    /// 更新知识库元数据
+    ///
+    /// 新的元数据会写入仓库内的 `.markwiki/wiki.toml` 文件，随下一次提交同步到远程仓库。
+    ///
+    /// # 参数
+    /// * `meta` - 新的元数据
+    ///
+    /// # 返回值
+    /// * `Result<(), Error>` - 成功时返回 `Ok(())`
+    /// * 失败时返回 `Err(Error)`，如默认页面路径不合法或写入失败
+    pub fn update_meta(&mut self, meta: WikiMeta) -> Result<(), Error> {
+        if let Some(default_page) = &meta.default_page {
+            self.resolve_path(default_page)?;
+        }
+
+        meta.save(Path::new(&self.path))?;
+        self.meta = meta;
+        Ok(())
+    }
+
+    /// 构建知识库的文件树
+    ///
+    /// 文件树会应用 `TreeFilter` 的过滤规则，包括知识库元数据中的 `ignore` 规则。
+    ///
+    /// # 参数
+    /// * `relative_dir` - 作为文件树根节点的目录，相对于知识库根目录，空字符串表示知识库根目录
+    /// * `depth` - 加载的目录层数，`None` 表示加载全部层级
+    ///
+    /// # 返回值
+    /// * `Result<FileNode, Error>` - 成功时返回 `Ok(FileNode)`，包含构建的文件节点树
+    /// * 失败时返回 `Err(Error)`
+    pub fn file_tree(&self, relative_dir: &str, depth: Option<usize>) -> Result<FileNode, Error> {
+        let root = Path::new(&self.path);
+        let filter = TreeFilter::new(root, &self.meta.ignore);
+        build_file_tree_with(&self.resolve_path(relative_dir)?, &filter, depth)
+    }
+
+    /// 将知识库内的相对路径解析为完整路径
+    ///
+    /// 相对路径使用 `/` 或 `\` 作为分隔符，开头的分隔符会被忽略，空路径表示知识库根目录。
+    /// 为防止越权访问，包含 `..` 或盘符等绝对路径成分的路径会被拒绝。
+    ///
+    /// # 参数
+    /// * `relative` - 相对于知识库根目录的路径
+    ///
+    /// # 返回值
+    /// * `Result<PathBuf, Error>` - 成功时返回 `Ok(PathBuf)`，包含完整路径
+    /// * 失败时返回 `Err(Error::InvalidPath)`
+    pub fn resolve_path(&self, relative: &str) -> Result<PathBuf, Error> {
+        let mut full_path = PathBuf::from(&self.path);
+        for part in relative.split(['/', '\\']) {
+            match part {
+                "" | "." => {}
+                ".." => return Err(Error::InvalidPath(relative.to_string())),
+                // 含有盘符（如 `C:`）的路径在 Windows 上会替换掉根目录
+                part if part.contains(':') => return Err(Error::InvalidPath(relative.to_string())),
+                part => full_path.push(part),
+            }
+        }
+        Ok(full_path)
+    }
+
+    /// 获取知识库的应用状态目录
+    ///
+    /// 该目录位于 Git 目录内部（通常为 `.git/markwiki`），不会被纳入版本管理，
+    /// 用于存放回收站等仅属于本机的数据。如果目录不存在，会自动创建。
+    ///
+    /// # 返回值
+    /// * `Result<PathBuf, Error>` - 成功时返回 `Ok(PathBuf)`，包含状态目录
+    /// * 失败时返回 `Err(Error)`
+    pub fn state_dir(&self) -> Result<PathBuf, Error> {
+        Self::state_dir_at(Path::new(&self.path))
+    }
+
+    /// 获取指定根目录下知识库的应用状态目录，与 `state_dir` 相同
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    pub fn state_dir_at(root: &Path) -> Result<PathBuf, Error> {
+        let repo = git::Repository::open(root)?;
+        let state_dir = repo.git_dir().join("markwiki");
+        if !state_dir.exists() {
+            fs::create_dir_all(&state_dir)?;
+        }
+        Ok(state_dir)
+    }
+
     /// 获取所有知识库的统一存储目录
     ///
-    /// 该函数根据当前运行平台，返回MarkWiki应用存储知识库的统一目录路径。
+    /// 该函数返回MarkWiki应用存储知识库的统一目录路径。
+    /// 如果应用配置中设置了 `wiki_storage_dir`，则使用该目录，否则使用默认存储目录。
     /// 如果目录不存在，会自动创建该目录。
     ///
     /// # 返回值
     /// * `Result<PathBuf, Error>` - 成功时返回 `Ok(PathBuf)`，包含知识库的统一存储目录
     /// * 失败时返回 `Err(Error)`
+    pub fn get_wiki_storage_dir() -> Result<PathBuf, Error> {
+        let wiki_dir = match crate::config::AppConfig::load_or_default()?.wiki_storage_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => Self::get_default_storage_dir()?,
+        };
+
+        // 确保 wiki 目录存在
+        if !wiki_dir.exists() {
+            fs::create_dir_all(&wiki_dir)?;
+        }
+        Ok(wiki_dir)
+    }
+
+    /// 获取默认的知识库存储目录，即应用数据目录下的 `wiki` 目录
     ///
     /// # 平台差异
-    /// * Android平台：使用临时目录下的markwiki/wiki目录
-    /// * Linux/Windows平台：使用可执行文件所在目录下的wiki目录
-    pub fn get_wiki_storage_dir() -> Result<PathBuf, Error> {
+    /// * Android平台：应用私有数据目录下的wiki目录，不会像临时目录一样被系统清理
+    /// * Linux/Windows平台：系统数据目录（如 `~/.local/share` 或 `%APPDATA%`）下的wiki目录
+    pub fn get_default_storage_dir() -> Result<PathBuf, Error> {
+        Ok(crate::config::get_data_dir()?.join("wiki"))
+    }
+
+    /// 获取旧版本使用的知识库存储目录
+    ///
+    /// 旧版本在桌面平台上将知识库存储在可执行文件所在目录下的 `wiki` 目录，
+    /// 在 Android 平台上存储在临时目录下的 `markwiki/wiki` 目录。
+    ///
+    /// # 返回值
+    /// * `Option<PathBuf>` - 旧的存储目录存在时返回 `Some(PathBuf)`，否则返回 `None`
+    pub fn get_legacy_storage_dir() -> Option<PathBuf> {
         // Android 平台
         #[cfg(target_os = "android")]
-        {
-            let wiki_dir = std::env::temp_dir().join("markwiki").join("wiki");
-            // 确保 wiki 目录存在
-            if !wiki_dir.exists() {
-                std::fs::create_dir_all(&wiki_dir)?;
-            }
-            Ok(wiki_dir)
-        }
+        let wiki_dir = std::env::temp_dir().join("markwiki").join("wiki");
 
         // Linux/Windows 平台
         #[cfg(not(target_os = "android"))]
-        {
-            // 获取 MarkWiki 可执行文件路径
-            let exe_path = std::env::current_exe()?;
+        let wiki_dir = std::env::current_exe().ok()?.parent()?.join("wiki");
+
+        wiki_dir.is_dir().then_some(wiki_dir)
+    }
+
+    /// 将知识库从一个存储目录迁移到另一个存储目录
+    ///
+    /// 只会迁移源目录下的知识库（即 Git 仓库目录），其他文件保持不动。
+    /// 迁移前会先检查目标目录中是否存在同名条目，存在冲突时不会移动任何知识库。
+    /// 同一文件系统内直接重命名，跨文件系统时先复制再删除源目录。
+    ///
+    /// # 参数
+    /// * `from` - 原存储目录
+    /// * `to` - 新存储目录，不存在时会自动创建
+    ///
+    /// # 返回值
+    /// * `Result<Vec<String>, Error>` - 成功时返回已迁移的知识库名称列表
+    /// * 失败时返回 `Err(Error)`
+    pub fn migrate_storage_dir(from: &Path, to: &Path) -> Result<Vec<String>, Error> {
+        fs::create_dir_all(to)?;
+        if from.canonicalize()? == to.canonicalize()? {
+            return Ok(Vec::new());
+        }
 
-            // 获取 MarkWiki 所在目录
-            let exe_dir = exe_path
-                .parent()
-                .ok_or(Error::Path("无法获取可执行文件所在目录".to_string()))?;
+        // 收集需要迁移的知识库
+        let mut names = Vec::new();
+        for entry in fs::read_dir(from)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if path.is_dir() && git::Repository::open(&path).is_ok() {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
 
-            // 构建知识库目录路径
-            let wiki_dir = exe_dir.join("wiki");
+        // 先检查冲突，避免迁移到一半才失败
+        if let Some(name) = names.iter().find(|name| to.join(name).exists()) {
+            return Err(Error::AlreadyExists(format!(
+                "知识库已存在: {}",
+                to.join(name).display()
+            )));
+        }
 
-            // 确保 wiki 目录存在
-            if !wiki_dir.exists() {
-                std::fs::create_dir_all(&wiki_dir)?;
+        for name in &names {
+            let source = from.join(name);
+            let target = to.join(name);
+            if fs::rename(&source, &target).is_err() {
+                copy_dir_all(&source, &target)?;
+                fs::remove_dir_all(&source)?;
             }
-            Ok(wiki_dir)
         }
+
+        Ok(names)
+    }
+}
+
+/// 递归复制目录
+///
+/// 符号链接按链接本身复制，不会复制或跟随链接指向的内容。
+fn copy_dir_all(from: &Path, to: &Path) -> Result<(), Error> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// 在 `to` 处创建一个与 `from` 指向相同目标的符号链接
+fn copy_symlink(from: &Path, to: &Path) -> Result<(), Error> {
+    let link_target = fs::read_link(from)?;
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&link_target, to)?;
+    #[cfg(windows)]
+    {
+        // Windows 区分文件链接和目录链接，按链接指向的类型创建
+        if fs::metadata(from).map(|m| m.is_dir()).unwrap_or(false) {
+            std::os::windows::fs::symlink_dir(&link_target, to)?;
+        } else {
+            std::os::windows::fs::symlink_file(&link_target, to)?;
+        }
+    }
+    Ok(())
+}
+
+/// 文件类型，用于前端区分页面、图片和其他附件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    /// 目录
+    Folder,
+    /// Markdown 页面
+    Markdown,
+    /// 图片
+    Image,
+    /// 其他附件
+    Attachment,
+}
+
+impl FileKind {
+    /// 根据文件扩展名判断文件类型
+    ///
+    /// # 参数
+    /// * `path` - 文件路径
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "md" | "markdown" => FileKind::Markdown,
+            "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "bmp" | "ico" | "avif" => {
+                FileKind::Image
+            }
+            _ => FileKind::Attachment,
+        }
+    }
+}
+
+/// 判断相对路径是否为 Markdown 页面
+pub fn is_page(path: &str) -> bool {
+    FileKind::from_path(Path::new(path)) == FileKind::Markdown
+}
+
+/// 收集文件树中的所有文件，按文件树中的顺序排列
+pub fn collect_files<'a>(node: &'a FileNode, files: &mut Vec<&'a FileNode>) {
+    if node.is_directory() {
+        for child in node.children() {
+            collect_files(child, files);
+        }
+    } else {
+        files.push(node);
     }
 }
 
@@ -240,14 +958,131 @@ impl Wiki {
 /// # 字段
 /// * `name` - 文件名或目录名
 /// * `is_directory` - 布尔值，表示是否为目录
-/// * `path` - 文件或目录的完整路径字符串
-/// * `children` - 可选的子节点列表，如果是目录则包含子文件和子目录，否则为None
+/// * `path` - 相对于知识库根目录、使用 `/` 分隔的路径，根目录为空字符串；
+///   该路径同时作为节点的稳定标识，可以直接传给 `read_file` 等命令
+/// * `kind` - 文件类型（目录、Markdown 页面、图片或其他附件）
+/// * `size` - 文件大小（字节），目录为 0
+/// * `modified` - 最后修改时间（Unix 时间戳，单位为秒），无法获取时为None
+/// * `children` - 可选的子节点列表，如果是已加载的目录则包含子文件和子目录，否则为None
+/// * `has_children` - 布尔值，表示目录中是否有可显示的子项，用于按需加载时显示展开按钮
+/// * `lfs_pointer` - 文件是否为内容尚未下载的 Git LFS 指针
+/// * `error` - 读取该节点时出现的错误信息，为None表示读取正常
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileNode {
     name: String,
     is_directory: bool,
     path: String,
+    kind: FileKind,
+    size: u64,
+    modified: Option<u64>,
     children: Option<Vec<FileNode>>,
+    has_children: bool,
+    #[serde(default)]
+    lfs_pointer: bool,
+    error: Option<String>,
+}
+
+impl FileNode {
+    /// 文件名或目录名
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 相对于知识库根目录、使用 `/` 分隔的路径
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// 是否为目录
+    pub fn is_directory(&self) -> bool {
+        self.is_directory
+    }
+
+    /// 文件类型
+    pub fn kind(&self) -> FileKind {
+        self.kind
+    }
+
+    /// 已加载的子节点，文件或未加载的目录返回空列表
+    pub fn children(&self) -> &[FileNode] {
+        self.children.as_deref().unwrap_or_default()
+    }
+
+    /// 文件是否为内容尚未下载的 Git LFS 指针
+    pub fn is_lfs_pointer(&self) -> bool {
+        self.lfs_pointer
+    }
+}
+
+/// 文件树过滤规则
+///
+/// 构建文件树时会跳过以下条目：
+/// * `.git` 目录和保存知识库元数据的 `.markwiki` 目录
+/// * `node_modules`、编辑器的交换文件和备份文件、系统生成的缩略图等文件
+/// * 被 `.gitignore` 忽略的路径
+/// * 知识库元数据中 `ignore` 字段设置的路径（语法与 `.gitignore` 相同）
+pub struct TreeFilter {
+    /// 知识库根目录
+    root: PathBuf,
+    /// 知识库的 Git 仓库，根目录不是 Git 仓库时为 None
+    repo: Option<git::Repository>,
+}
+
+impl TreeFilter {
+    /// 创建文件树过滤规则
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    /// * `ignore_rules` - 额外的忽略规则，语法与 `.gitignore` 相同
+    pub fn new(root: &Path, ignore_rules: &[String]) -> Self {
+        let repo = git::Repository::open(root).ok();
+        if let Some(repo) = &repo {
+            // 忽略规则只添加到本次打开的仓库实例中，不会写入任何文件
+            let _ = repo.add_ignore_rules(&ignore_rules.join("\n"));
+        }
+        Self {
+            root: root.to_path_buf(),
+            repo,
+        }
+    }
+
+    /// 判断文件或目录是否应该在文件树中隐藏
+    ///
+    /// # 参数
+    /// * `path` - 文件或目录的完整路径
+    /// * `is_directory` - 是否为目录
+    pub fn is_hidden(&self, path: &Path, is_directory: bool) -> bool {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+
+        // 内置的忽略规则
+        let builtin = matches!(
+            name.as_ref(),
+            ".git" | meta::META_DIR | "node_modules" | ".DS_Store" | "Thumbs.db" | "desktop.ini"
+        ) || name.ends_with(".swp")
+            || name.ends_with(".swo")
+            || name.ends_with('~')
+            || name.starts_with(".#")
+            || name.starts_with(".~lock.");
+        if builtin {
+            return true;
+        }
+
+        // .gitignore 与知识库元数据中的忽略规则
+        let (Some(repo), Ok(relative)) = (&self.repo, path.strip_prefix(&self.root)) else {
+            return false;
+        };
+        let mut relative = relative.to_string_lossy().replace('\\', "/");
+        if relative.is_empty() {
+            return false;
+        }
+        if is_directory {
+            relative.push('/');
+        }
+        repo.is_path_ignored(Path::new(&relative)).unwrap_or(false)
+    }
 }
 
 /// 递归构建文件系统的节点树
@@ -266,66 +1101,256 @@ pub struct FileNode {
 /// * 对于目录，会递归遍历其所有子项并构建子节点树
 /// * 子节点会按名称排序，目录排在文件前面
 /// * 对于文件，不会包含子节点信息
+/// * 被 `TreeFilter` 过滤的条目不会出现在文件树中
 pub fn build_file_tree(path: &Path) -> Result<FileNode, Error> {
+    build_file_tree_with(path, &TreeFilter::new(path, &[]), None)
+}
+
+/// 使用指定的过滤规则构建文件系统的节点树
+///
+/// 与 `build_file_tree` 不同，该函数可以限制加载的层数，用于大型知识库按需加载目录。
+/// 只有根路径本身无法读取时才会返回错误；子项无法读取（如没有权限或文件名不是有效的UTF-8）时，
+/// 会在对应节点的 `error` 字段中记录错误信息，不影响其他节点。
+/// 根路径以外指向目录的符号链接显示为没有子项的目录，不会被展开。
+///
+/// # 参数
+/// * `path` - 要构建文件树的根路径，可以是文件或目录
+/// * `filter` - 文件树过滤规则
+/// * `depth` - 加载的目录层数，`Some(1)` 表示只加载根目录的直接子项，`None` 表示加载全部层级
+///
+/// # 返回值
+/// * `Result<FileNode, Error>` - 成功时返回 `Ok(FileNode)`，包含构建的文件节点树
+/// * 失败时返回 `Err(Error)`，包含具体错误信息
+pub fn build_file_tree_with(
+    path: &Path,
+    filter: &TreeFilter,
+    depth: Option<usize>,
+) -> Result<FileNode, Error> {
     // 获取文件或目录的元数据
     let metadata = fs::metadata(path)
         .map_err(|e| Error::BuildFileTree(format!("获取文件元数据失败: {:?}: {}", path, e)))?;
 
-    // 提取并转换文件或目录名称为字符串
-    let name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| Error::BuildFileTree(format!("获取文件名失败: {:?}", path)))?
-        .to_string();
-
-    // 转换路径为字符串表示
-    let path_str = path
-        .to_str()
-        .ok_or_else(|| Error::BuildFileTree(format!("将路径转换为字符串失败: {:?}", path)))?
-        .to_string();
-
-    // 如果是目录，递归构建子节点树
-    if metadata.is_dir() {
-        let mut children = Vec::new();
-        let entries = fs::read_dir(path)
-            .map_err(|e| Error::BuildFileTree(format!("读取目录失败: {:?}: {}", path, e)))?;
-
-        // 遍历目录中的所有条目
-        for entry in entries {
-            let entry =
-                entry.map_err(|e| Error::BuildFileTree(format!("读取目录条目失败: {}", e)))?;
-            let child_path = entry.path();
-            // 递归构建子节点
-            children.push(build_file_tree(&child_path)?);
-        }
-
-        // 按名称排序，目录在前，文件在后
-        children.sort_by(|a, b| {
-            if a.is_directory && !b.is_directory {
-                std::cmp::Ordering::Less
-            } else if !a.is_directory && b.is_directory {
-                std::cmp::Ordering::Greater
-            } else {
-                a.name.cmp(&b.name)
+    Ok(build_node(path, metadata.is_dir(), filter, depth))
+}
+
+/// 构建单个节点，读取失败时在节点中记录错误信息
+fn build_node(
+    path: &Path,
+    is_directory: bool,
+    filter: &TreeFilter,
+    depth: Option<usize>,
+) -> FileNode {
+    let mut errors = Vec::new();
+
+    // 提取文件或目录名称，无法转换为UTF-8时使用替换字符并记录错误
+    let name = match path.file_name() {
+        Some(name) => match name.to_str() {
+            Some(name) => name.to_string(),
+            None => {
+                errors.push(format!("文件名不是有效的UTF-8: {:?}", name));
+                name.to_string_lossy().to_string()
             }
-        });
+        },
+        None => path.to_string_lossy().to_string(),
+    };
 
-        // 返回目录节点，包含子节点信息
-        Ok(FileNode {
-            name,
-            is_directory: true,
-            path: path_str,
-            children: Some(children),
-        })
-    } else {
+    // 转换为相对于知识库根目录的路径
+    let path_str = to_relative_path(&filter.root, path);
+
+    // 获取文件大小与修改时间
+    let (size, modified) = match fs::metadata(path) {
+        Ok(metadata) => (
+            if is_directory { 0 } else { metadata.len() },
+            metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+        ),
+        Err(e) => {
+            errors.push(format!("获取文件元数据失败: {}", e));
+            (0, None)
+        }
+    };
+
+    if !is_directory {
         // 返回文件节点，不包含子节点
-        Ok(FileNode {
+        return FileNode {
             name,
             is_directory: false,
             path: path_str,
+            kind: FileKind::from_path(path),
+            size,
+            modified,
             children: None,
-        })
+            has_children: false,
+            lfs_pointer: git::lfs::read_pointer_file(path).is_some(),
+            error: errors.pop(),
+        };
+    }
+
+    // 读取目录中可显示的子项。指向目录的符号链接不会被展开，
+    // 避免循环链接（如 `a -> .`）导致无限递归，也避免把知识库之外的内容纳入文件树
+    let entries = if path != filter.root && is_symlink(path) {
+        errors.push("符号链接指向的目录不会被展开".to_string());
+        Vec::new()
+    } else {
+        match visible_entries(path, filter) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errors.push(format!("读取目录失败: {}", e));
+                Vec::new()
+            }
+        }
+    };
+
+    // 已达到加载层数的目录不加载子节点
+    let children = match depth {
+        Some(0) => None,
+        _ => {
+            let child_depth = depth.map(|d| d - 1);
+            let mut children: Vec<FileNode> = entries
+                .iter()
+                .map(|(child_path, child_is_dir)| {
+                    build_node(child_path, *child_is_dir, filter, child_depth)
+                })
+                .collect();
+
+            // 按名称排序，目录在前，文件在后
+            children.sort_by(|a, b| {
+                b.is_directory
+                    .cmp(&a.is_directory)
+                    .then_with(|| a.name.cmp(&b.name))
+            });
+            Some(children)
+        }
+    };
+
+    // 返回目录节点
+    FileNode {
+        name,
+        is_directory: true,
+        path: path_str,
+        kind: FileKind::Folder,
+        size,
+        modified,
+        children,
+        has_children: !entries.is_empty(),
+        lfs_pointer: false,
+        error: errors.pop(),
+    }
+}
+
+/// 判断路径本身是否为符号链接（不跟随链接）
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+/// 读取目录中未被过滤的子项，返回子项路径和是否为目录
+///
+/// 无法读取的子项会被保留（视为文件），由 `build_node` 在节点中记录错误。
+fn visible_entries(dir: &Path, filter: &TreeFilter) -> std::io::Result<Vec<(PathBuf, bool)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let child_path = entry.path();
+        // 跟随符号链接判断是否为目录
+        let is_dir = fs::metadata(&child_path)
+            .map(|m| m.is_dir())
+            .unwrap_or(false);
+        if !filter.is_hidden(&child_path, is_dir) {
+            entries.push((child_path, is_dir));
+        }
+    }
+    Ok(entries)
+}
+
+/// 将知识库内的相对路径统一为不带首尾分隔符、使用 `/` 分隔的形式
+///
+/// 与 `Wiki::resolve_path` 相同，包含 `..` 或盘符的路径会被拒绝。
+///
+/// # 参数
+/// * `relative` - 相对于知识库根目录的路径，可以使用 `/` 或 `\` 作为分隔符
+///
+/// # 返回值
+/// * `Result<String, Error>` - 成功时返回统一后的路径，根目录为空字符串
+/// * 失败时返回 `Err(Error::InvalidPath)`
+pub fn normalize_relative_path(relative: &str) -> Result<String, Error> {
+    let mut parts = Vec::new();
+    for part in relative.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            part if part == ".." || part.contains(':') => {
+                return Err(Error::InvalidPath(relative.to_string()))
+            }
+            part => parts.push(part),
+        }
     }
+    Ok(parts.join("/"))
+}
+
+/// 将完整路径转换为相对于根目录、使用 `/` 分隔的路径
+///
+/// # 参数
+/// * `root` - 根目录，通常为知识库根目录
+/// * `path` - 位于根目录内的完整路径
+///
+/// # 返回值
+/// * `String` - 相对路径，`path` 等于 `root` 时返回空字符串；`path` 不在根目录内时返回原路径
+pub fn to_relative_path(root: &Path, path: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+/// 列出目录下的所有文件
+///
+/// 该函数递归遍历指定目录，跳过 `.git` 目录，返回所有文件相对于该目录的路径。
+/// 路径统一使用 `/` 作为分隔符，并按字典序排列，便于与前端交互和比较。
+///
+/// # 参数
+/// * `root` - 要遍历的根目录，通常为知识库根目录
+///
+/// # 返回值
+/// * `Result<Vec<String>, Error>` - 成功时返回 `Ok(Vec<String>)`，包含所有文件的相对路径
+/// * 失败时返回 `Err(Error)`
+pub fn list_files(root: &Path) -> Result<Vec<String>, Error> {
+    fn walk(dir: &Path, prefix: &str, files: &mut Vec<String>) -> Result<(), Error> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" {
+                continue;
+            }
+
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+
+            if entry.file_type()?.is_dir() {
+                walk(&entry.path(), &relative, files)?;
+            } else {
+                files.push(relative);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(root, "", &mut files)?;
+    files.sort();
+    Ok(files)
 }
 
 #[cfg(test)]
@@ -335,30 +1360,290 @@ mod tests {
     /// 测试 `get_wiki_storage_dir` 函数的基本功能
     ///
     /// # 测试目标：
-    /// * 验证函数是否能正确返回知识库的统一存储目录
-    /// * 验证知识库的统一存储目录名称为 "wiki"
-    /// * 验证该目录存在且父目录包含 markwiki 应用程序
+    /// * 验证未配置存储目录时使用应用数据目录下的 "wiki" 目录
+    /// * 验证配置了存储目录时使用配置的目录
+    /// * 验证返回的目录会被自动创建
     #[test]
     fn test_get_wiki_storage_dir() {
-        // 调用函数获取知识库目录
+        let data_dir = crate::config::test_data_dir();
+
+        // 未配置存储目录时使用默认存储目录
         let wiki_dir = Wiki::get_wiki_storage_dir().unwrap();
+        assert!(wiki_dir.is_dir());
+        assert_eq!(wiki_dir, Wiki::get_default_storage_dir().unwrap());
+        assert_eq!(wiki_dir, data_dir.path().join("wiki"));
 
-        // 验证知识库目录名称为 "wiki"
-        assert_eq!(wiki_dir.file_name().unwrap().to_str().unwrap(), "wiki");
+        // 配置了存储目录时使用配置的目录
+        let custom_dir = data_dir.path().join("custom");
+        let config = crate::config::AppConfig {
+            wiki_storage_dir: Some(custom_dir.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        config.save().unwrap();
+        assert_eq!(Wiki::get_wiki_storage_dir().unwrap(), custom_dir);
+        assert!(custom_dir.is_dir());
+    }
 
-        // 验证知识库目录存在且是一个目录
-        assert!(wiki_dir.exists());
-        assert!(wiki_dir.is_dir());
+    /// 测试 `migrate_storage_dir` 函数
+    ///
+    /// # 测试目标：
+    /// * 验证只有知识库（Git 仓库）会被迁移，其他文件保持不动
+    /// * 验证目标目录存在同名条目时拒绝迁移且不移动任何内容
+    #[test]
+    fn test_migrate_storage_dir() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let from = temp_dir.path().join("old");
+        let to = temp_dir.path().join("new");
+        git::Repository::init(&from.join("alpha"), None, None).unwrap();
+        git::Repository::init(&from.join("beta"), None, None).unwrap();
+        fs::write(from.join("alpha/page.md"), "alpha").unwrap();
+        fs::write(from.join("config.json"), "{}").unwrap();
+
+        // 目标目录存在冲突时不迁移
+        fs::create_dir_all(to.join("beta")).unwrap();
+        assert!(matches!(
+            Wiki::migrate_storage_dir(&from, &to),
+            Err(Error::AlreadyExists(_))
+        ));
+        assert!(from.join("alpha").exists());
+
+        fs::remove_dir_all(to.join("beta")).unwrap();
+        let moved = Wiki::migrate_storage_dir(&from, &to).unwrap();
+        assert_eq!(moved, vec!["alpha".to_string(), "beta".to_string()]);
+        assert_eq!(
+            fs::read_to_string(to.join("alpha/page.md")).unwrap(),
+            "alpha"
+        );
+        assert!(git::Repository::open(&to.join("beta")).is_ok());
+        assert!(!from.join("alpha").exists());
+        assert!(from.join("config.json").exists());
+    }
+
+    /// 测试删除知识库
+    ///
+    /// # 测试目标：
+    /// * 验证存在未提交的修改或未推送的提交时拒绝删除，知识库保持不变
+    /// * 验证强制删除前可以先归档，归档中包含页面和 Git 历史
+    #[test]
+    fn test_delete() {
+        let _data_dir = crate::config::test_data_dir();
+        let wiki = Wiki::create_local_wiki("notes", "tester", "tester@example.com").unwrap();
+        let root = PathBuf::from(&wiki.path);
+        fs::write(root.join("page.md"), "# 页面").unwrap();
+
+        assert!(matches!(
+            Wiki::from_name("notes").unwrap().delete(false, true),
+            Err(Error::UnsavedWork {
+                uncommitted: true,
+                ..
+            })
+        ));
+        assert!(root.join("page.md").exists());
+
+        // 没有远程仓库时所有提交都视为未推送
+        let mut repo = git::Repository::open(&root).unwrap();
+        repo.add_all().unwrap();
+        repo.commit("添加页面").unwrap();
+        assert!(matches!(
+            Wiki::from_name("notes").unwrap().delete(false, false),
+            Err(Error::UnsavedWork {
+                uncommitted: false,
+                unpushed: 1
+            })
+        ));
+        assert!(root.exists());
 
-        // 验证父目录下存在 markwiki 应用程序
-        #[cfg(target_os = "windows")]
-        let markwiki_exe = wiki_dir.parent().unwrap().join("markwiki.exe");
-        #[cfg(target_os = "linux")]
-        let markwiki_exe = wiki_dir.parent().unwrap().join("markwiki");
-        assert!(markwiki_exe.exists());
+        let archive_path = Wiki::from_name("notes")
+            .unwrap()
+            .delete(true, true)
+            .unwrap()
+            .unwrap();
+        assert!(!root.exists());
+        assert!(archive_path.starts_with(Wiki::get_archive_dir().unwrap()));
 
-        // 验证 markwiki 应用程序是一个可执行文件
-        assert!(markwiki_exe.is_file());
+        let restored = tempfile::TempDir::new().unwrap();
+        crate::archive::unpack(&archive_path, restored.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(restored.path().join("page.md")).unwrap(),
+            "# 页面"
+        );
+        assert!(git::Repository::open(restored.path()).is_ok());
+    }
+
+    /// 测试从包含 `.git` 目录的归档中恢复知识库
+    ///
+    /// # 测试目标：
+    /// * 验证提交历史被保留，钩子脚本被删除
+    /// * 验证仓库配置被重新生成，`core.fsmonitor` 和远程仓库等设置不会被带入
+    #[test]
+    fn test_import_archive_resets_git_dir() {
+        let _data_dir = crate::config::test_data_dir();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let src = temp_dir.path().join("shared");
+        fs::create_dir_all(&src).unwrap();
+        let mut repo = git::Repository::init(&src, Some("sender"), Some("s@example.com")).unwrap();
+        fs::write(src.join("page.md"), "# 页面").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("添加页面").unwrap();
+        repo.set_config("core.fsmonitor", "touch pwned").unwrap();
+        repo.set_config("remote.origin.url", "https://example.com/evil.git")
+            .unwrap();
+        fs::write(
+            src.join(".git/hooks/post-checkout"),
+            "#!/bin/sh
+touch pwned
+",
+        )
+        .unwrap();
+
+        let archive = temp_dir.path().join("shared.zip");
+        crate::archive::pack_wiki(
+            &src,
+            &archive,
+            crate::archive::ArchiveFormat::Zip,
+            crate::archive::ArchiveHistory::GitDir,
+        )
+        .unwrap();
+
+        let wiki = Wiki::import_archive(&archive, None, "tester", "tester@example.com").unwrap();
+        let root = PathBuf::from(&wiki.path);
+        assert!(!wiki.has_remote_repo);
+        assert!(!root.join(".git/hooks").exists());
+        assert_eq!(fs::read_to_string(root.join("page.md")).unwrap(), "# 页面");
+
+        let repo = git2::Repository::open(&root).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("添加页面"));
+        let config = repo
+            .config()
+            .unwrap()
+            .open_level(git2::ConfigLevel::Local)
+            .unwrap();
+        assert!(config.get_string("core.fsmonitor").is_err());
+        assert!(config.get_string("remote.origin.url").is_err());
+        assert_eq!(config.get_string("user.name").unwrap(), "tester");
+    }
+
+    /// 测试外部知识库的注册、列出与取消注册
+    ///
+    /// # 测试目标：
+    /// * 验证注册的 Git 仓库出现在知识库列表中，并标记为外部知识库
+    /// * 验证非法名称、重复名称和非 Git 仓库的文件夹被拒绝
+    /// * 验证与存储目录中知识库重名的注册项在 `list` 和 `from_name` 中都被忽略
+    /// * 验证取消注册只移除配置，不删除仓库文件
+    #[test]
+    fn test_register_and_unregister() {
+        let _data_dir = crate::config::test_data_dir();
+        let external = tempfile::TempDir::new().unwrap();
+        let repo_path = external.path().join("docs");
+        git::Repository::init(&repo_path, None, None).unwrap();
+        Wiki::create_local_wiki("local", "tester", "tester@example.com").unwrap();
+
+        let wiki = Wiki::register(&repo_path, None).unwrap();
+        assert_eq!(wiki.name, "docs");
+        assert!(wiki.is_external);
+        assert!(matches!(
+            Wiki::register(&repo_path, None),
+            Err(Error::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            Wiki::register(&repo_path, Some("local")),
+            Err(Error::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            Wiki::register(&repo_path, Some(".hidden")),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(Wiki::register(external.path(), Some("plain")).is_err());
+
+        let names: Vec<(String, bool)> = Wiki::list()
+            .unwrap()
+            .into_iter()
+            .map(|w| (w.name, w.is_external))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("docs".to_string(), true), ("local".to_string(), false)]
+        );
+
+        // 存储目录中出现同名知识库时，两处都使用存储目录中的知识库
+        Wiki::create_local_wiki("docs", "tester", "tester@example.com").unwrap();
+        assert!(!Wiki::from_name("docs").unwrap().is_external);
+        let docs: Vec<bool> = Wiki::list()
+            .unwrap()
+            .into_iter()
+            .filter(|w| w.name == "docs")
+            .map(|w| w.is_external)
+            .collect();
+        assert_eq!(docs, vec![false]);
+
+        Wiki::unregister("docs").unwrap();
+        assert!(matches!(Wiki::unregister("docs"), Err(Error::NotFound(_))));
+        assert!(git::Repository::open(&repo_path).is_ok());
+    }
+
+    /// 测试在普通文件夹中初始化并注册外部知识库
+    ///
+    /// # 测试目标：
+    /// * 验证名称非法或已被占用时拒绝初始化，且不会在文件夹中留下 `.git` 目录
+    /// * 验证已经是 Git 仓库的文件夹被拒绝
+    /// * 验证初始化成功后知识库可以按名称打开，文件夹中原有的文件保持不变
+    #[test]
+    fn test_init_external() {
+        let _data_dir = crate::config::test_data_dir();
+        let folder = tempfile::TempDir::new().unwrap();
+        fs::write(folder.path().join("note.md"), "note").unwrap();
+        Wiki::create_local_wiki("taken", "tester", "tester@example.com").unwrap();
+
+        assert!(matches!(
+            Wiki::init_external(folder.path(), Some("a/b"), "tester", "t@example.com"),
+            Err(Error::InvalidName(_))
+        ));
+        assert!(matches!(
+            Wiki::init_external(folder.path(), Some("taken"), "tester", "t@example.com"),
+            Err(Error::AlreadyExists(_))
+        ));
+        assert!(!folder.path().join(".git").exists());
+
+        let wiki =
+            Wiki::init_external(folder.path(), Some("notes"), "tester", "t@example.com").unwrap();
+        assert!(wiki.is_external);
+        assert_eq!(Wiki::from_name("notes").unwrap().path, wiki.path);
+        assert_eq!(
+            fs::read_to_string(folder.path().join("note.md")).unwrap(),
+            "note"
+        );
+        assert!(matches!(
+            Wiki::init_external(folder.path(), Some("again"), "tester", "t@example.com"),
+            Err(Error::AlreadyExists(_))
+        ));
+    }
+
+    /// 测试 `copy_dir_all` 函数
+    ///
+    /// # 测试目标：
+    /// * 验证跨设备迁移时使用的复制会保留符号链接本身，而不是丢弃或复制其内容
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_dir_all_keeps_symlinks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let from = temp_dir.path().join("from");
+        let to = temp_dir.path().join("to");
+        fs::create_dir_all(from.join("docs")).unwrap();
+        fs::write(from.join("docs/page.md"), "page").unwrap();
+        std::os::unix::fs::symlink("docs/page.md", from.join("link.md")).unwrap();
+        std::os::unix::fs::symlink("docs", from.join("docs-link")).unwrap();
+
+        copy_dir_all(&from, &to).unwrap();
+        assert_eq!(fs::read_to_string(to.join("docs/page.md")).unwrap(), "page");
+        assert_eq!(
+            fs::read_link(to.join("link.md")).unwrap(),
+            PathBuf::from("docs/page.md")
+        );
+        assert_eq!(
+            fs::read_link(to.join("docs-link")).unwrap(),
+            PathBuf::from("docs")
+        );
     }
 
     /// 测试 `build_file_tree` 函数的基本功能
@@ -444,10 +1729,98 @@ mod tests {
         assert!(!file1_node.is_directory);
         assert!(file1_node.children.is_none());
 
-        // 验证路径正确性
-        assert!(root_node.path.ends_with(expected_name));
-        assert!(subdir1_node.path.ends_with("subdir1"));
-        assert!(subdir2_node.path.ends_with("subdir2"));
-        assert!(file1_node.path.ends_with("file1.txt"));
+        // 验证路径为相对于根目录、使用 `/` 分隔的路径
+        assert_eq!(root_node.path, "");
+        assert_eq!(subdir1_node.path, "subdir1");
+        assert_eq!(subdir2_node.path, "subdir2");
+        assert_eq!(file1_node.path, "file1.txt");
+        assert_eq!(subdir1_children[1].path, "subdir1/file3.md");
+
+        // 验证文件类型与大小
+        assert_eq!(subdir1_node.kind, FileKind::Folder);
+        assert_eq!(subdir1_children[1].kind, FileKind::Markdown);
+        assert_eq!(file1_node.kind, FileKind::Attachment);
+        assert_eq!(file1_node.size, "content1".len() as u64);
+        assert!(file1_node.modified.is_some());
+    }
+
+    /// 测试文件树的过滤与按需加载
+    ///
+    /// # 测试目标：
+    /// * 验证 `.git`、`node_modules`、交换文件和 `.gitignore` 忽略的路径不会出现在文件树中
+    /// * 验证额外的忽略规则生效
+    /// * 验证限制层数时未加载的目录没有子节点，但标记了是否有子项
+    #[test]
+    fn test_build_file_tree_filter_and_depth() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        git::Repository::init(root, None, None).unwrap();
+
+        fs::create_dir_all(root.join("docs/deep")).unwrap();
+        fs::create_dir_all(root.join("node_modules/pkg")).unwrap();
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::create_dir_all(root.join("drafts")).unwrap();
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::write(root.join(".gitignore"), "build/\n*.log\n").unwrap();
+        fs::write(root.join("index.md"), "index").unwrap();
+        fs::write(root.join(".index.md.swp"), "swap").unwrap();
+        fs::write(root.join("debug.log"), "log").unwrap();
+        fs::write(root.join("docs/deep/page.md"), "page").unwrap();
+        fs::write(root.join("drafts/wip.md"), "wip").unwrap();
+
+        let filter = TreeFilter::new(root, &["drafts/".to_string()]);
+        let tree = build_file_tree_with(root, &filter, Some(1)).unwrap();
+        let children = tree.children.unwrap();
+        let names: Vec<&str> = children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["docs", "empty", ".gitignore", "index.md"]);
+
+        // 未加载的目录没有子节点，但能区分是否为空目录
+        assert!(children[0].children.is_none());
+        assert!(children[0].has_children);
+        assert!(!children[1].has_children);
+
+        // 按需加载子目录
+        let docs = build_file_tree_with(&root.join("docs"), &filter, Some(1)).unwrap();
+        assert_eq!(docs.path, "docs");
+        let docs_children = docs.children.unwrap();
+        assert_eq!(docs_children[0].name, "deep");
+        assert_eq!(docs_children[0].path, "docs/deep");
+        assert!(docs_children[0].children.is_none());
+
+        // 加载全部层级
+        let tree = build_file_tree_with(root, &filter, None).unwrap();
+        let children = tree.children.unwrap();
+        let deep = &children[0].children.as_ref().unwrap()[0];
+        assert_eq!(deep.children.as_ref().unwrap()[0].name, "page.md");
+    }
+
+    /// 测试文件树中的符号链接
+    ///
+    /// # 测试目标：
+    /// * 验证循环的目录链接不会导致无限递归
+    /// * 验证指向目录的符号链接显示为未展开的目录，指向文件的符号链接正常显示
+    #[cfg(unix)]
+    #[test]
+    fn test_build_file_tree_symlinks() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/page.md"), "page").unwrap();
+        std::os::unix::fs::symlink(".", root.join("loop")).unwrap();
+        std::os::unix::fs::symlink("docs/page.md", root.join("alias.md")).unwrap();
+
+        let tree = build_file_tree(root).unwrap();
+        let children = tree.children.unwrap();
+        let names: Vec<&str> = children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["docs", "loop", "alias.md"]);
+
+        let link = &children[1];
+        assert!(link.is_directory);
+        assert!(!link.has_children);
+        assert!(link.children.as_ref().unwrap().is_empty());
+        assert!(link.error.is_some());
+
+        let lazy = build_file_tree_with(&root.join("loop"), &TreeFilter::new(root, &[]), None);
+        assert!(lazy.unwrap().children.unwrap().is_empty());
     }
 }
@@ -0,0 +1,271 @@
+//! 页面模型模块
+//!
+//! 页面可以在开头包含 YAML 格式的 front matter，例如：
+//!
+//! ```text
+//! ---
+//! title: 部署指南
+//! tags: [运维, 部署]
+//! owner: 张三
+//! status: draft
+//! ---
+//! 正文……
+//! ```
+//!
+//! 此模块把页面拆分为 front matter 和正文，并支持只修改 front matter 中的个别字段而不改动正文。
+//! 修改字段时 front matter 会被重新序列化，其中的注释和原有格式不会保留。
+
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use std::collections::BTreeMap;
+
+use super::Error;
+
+/// front matter 的分隔行
+const DELIMITER: &str = "---";
+
+/// 页面
+///
+/// # 字段
+/// * `front_matter` - 页面的 front matter，页面没有 front matter 时为 `None`
+/// * `body` - front matter 之后的正文，保持原样
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub front_matter: Option<Mapping>,
+    pub body: String,
+}
+
+/// 页面元数据
+///
+/// # 字段
+/// * `title` - 页面标题，依次取 front matter 中的 `title`、正文中的第一个一级标题和文件名
+/// * `tags` - 页面标签，front matter 中的 `tags` 可以是列表或以逗号分隔的字符串
+/// * `owner` - 页面负责人
+/// * `status` - 页面状态
+/// * `fields` - front matter 中的所有字段
+#[derive(Debug, Clone, Serialize)]
+pub struct PageMetadata {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub owner: Option<String>,
+    pub status: Option<String>,
+    pub fields: BTreeMap<String, serde_json::Value>,
+}
+
+impl Page {
+    /// 解析页面内容
+    ///
+    /// 只有第一行是 `---`、且之后存在以 `---` 或 `...` 结束的行时，开头的内容才被视为 front matter。
+    ///
+    /// # 参数
+    /// * `content` - 页面的完整内容
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回解析后的页面
+    /// * 失败时返回 `Err(Error::FrontMatter)`，表示 front matter 不是有效的 YAML 映射
+    pub fn parse(content: &str) -> Result<Self, Error> {
+        let Some((yaml, body)) = split_front_matter(content) else {
+            return Ok(Self {
+                front_matter: None,
+                body: content.to_string(),
+            });
+        };
+
+        let front_matter = match serde_yaml::from_str::<Value>(yaml)
+            .map_err(|e| Error::FrontMatter(e.to_string()))?
+        {
+            Value::Mapping(mapping) => mapping,
+            Value::Null => Mapping::new(),
+            _ => {
+                return Err(Error::FrontMatter(
+                    "front matter 必须是键值映射".to_string(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            front_matter: Some(front_matter),
+            body: body.to_string(),
+        })
+    }
+
+    /// 将页面重新组合为完整内容
+    ///
+    /// front matter 为空时不输出分隔行。
+    pub fn render(&self) -> Result<String, Error> {
+        match &self.front_matter {
+            Some(front_matter) if !front_matter.is_empty() => {
+                let yaml = serde_yaml::to_string(front_matter)
+                    .map_err(|e| Error::FrontMatter(e.to_string()))?;
+                Ok(format!(
+                    "{}\n{}{}\n{}",
+                    DELIMITER, yaml, DELIMITER, self.body
+                ))
+            }
+            _ => Ok(self.body.clone()),
+        }
+    }
+
+    /// 获取 front matter 中的字段
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        self.front_matter.as_ref()?.get(key)
+    }
+
+    /// 设置 front matter 中的字段，值为 `null` 时删除该字段
+    ///
+    /// # 参数
+    /// * `key` - 字段名称
+    /// * `value` - 字段的值，使用 JSON 表示
+    pub fn set_field(&mut self, key: &str, value: &serde_json::Value) -> Result<(), Error> {
+        let front_matter = self.front_matter.get_or_insert_with(Mapping::new);
+        if value.is_null() {
+            front_matter.remove(key);
+        } else {
+            let value =
+                serde_yaml::to_value(value).map_err(|e| Error::FrontMatter(e.to_string()))?;
+            front_matter.insert(Value::String(key.to_string()), value);
+        }
+        Ok(())
+    }
+
+    /// 获取页面的标签
+    pub fn tags(&self) -> Vec<String> {
+        match self.field("tags") {
+            Some(Value::Sequence(items)) => items.iter().filter_map(scalar_to_string).collect(),
+            Some(value) => scalar_to_string(value)
+                .map(|s| {
+                    s.split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
+    /// 获取页面元数据
+    ///
+    /// # 参数
+    /// * `file_name` - 页面的文件名，用于在没有标题时作为标题
+    pub fn metadata(&self, file_name: &str) -> PageMetadata {
+        let title = self
+            .field("title")
+            .and_then(scalar_to_string)
+            .or_else(|| {
+                self.body.lines().find_map(|line| {
+                    line.strip_prefix("# ")
+                        .map(|heading| heading.trim().to_string())
+                })
+            })
+            .unwrap_or_else(|| {
+                file_name
+                    .strip_suffix(".md")
+                    .unwrap_or(file_name)
+                    .to_string()
+            });
+
+        let mut fields = BTreeMap::new();
+        if let Some(front_matter) = &self.front_matter {
+            for (key, value) in front_matter {
+                if let (Some(key), Ok(value)) = (scalar_to_string(key), serde_json::to_value(value))
+                {
+                    fields.insert(key, value);
+                }
+            }
+        }
+
+        PageMetadata {
+            title,
+            tags: self.tags(),
+            owner: self.field("owner").and_then(scalar_to_string),
+            status: self.field("status").and_then(scalar_to_string),
+            fields,
+        }
+    }
+}
+
+/// 拆分 front matter 与正文，页面没有 front matter 时返回 `None`
+fn split_front_matter(content: &str) -> Option<(&str, &str)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let first_line_end = content.find('\n')?;
+    if content[..first_line_end].trim_end() != DELIMITER {
+        return None;
+    }
+
+    let yaml_start = first_line_end + 1;
+    let mut offset = yaml_start;
+    for line in content[yaml_start..].split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == DELIMITER || trimmed == "..." {
+            return Some((
+                &content[yaml_start..offset],
+                &content[offset + line.len()..],
+            ));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// 将 YAML 标量转换为字符串
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试 front matter 的解析与字段修改
+    ///
+    /// # 测试目标：
+    /// * 验证 front matter 与正文被正确拆分，没有 front matter 的页面保持原样
+    /// * 验证元数据中的标题、标签、负责人和状态
+    /// * 验证修改和删除字段后正文保持不变
+    #[test]
+    fn test_parse_and_update_front_matter() {
+        let content =
+            "---\ntitle: 部署指南\ntags: [运维, 部署]\nowner: 张三\n---\n# 标题\n\n正文\n";
+        let mut page = Page::parse(content).unwrap();
+        assert_eq!(page.body, "# 标题\n\n正文\n");
+
+        let metadata = page.metadata("deploy.md");
+        assert_eq!(metadata.title, "部署指南");
+        assert_eq!(metadata.tags, vec!["运维", "部署"]);
+        assert_eq!(metadata.owner.as_deref(), Some("张三"));
+        assert!(metadata.status.is_none());
+        assert_eq!(metadata.fields["owner"], serde_json::json!("张三"));
+
+        page.set_field("status", &serde_json::json!("published"))
+            .unwrap();
+        page.set_field("owner", &serde_json::Value::Null).unwrap();
+        let updated = page.render().unwrap();
+        assert!(updated.ends_with("---\n# 标题\n\n正文\n"));
+        let reparsed = Page::parse(&updated).unwrap();
+        assert_eq!(reparsed.metadata("").status.as_deref(), Some("published"));
+        assert!(reparsed.field("owner").is_none());
+        assert_eq!(reparsed.body, page.body);
+
+        // 没有 front matter 的页面
+        let plain = Page::parse("# 首页\n---\n内容").unwrap();
+        assert!(plain.front_matter.is_none());
+        assert_eq!(plain.metadata("index.md").title, "首页");
+        assert_eq!(plain.render().unwrap(), "# 首页\n---\n内容");
+        assert_eq!(Page::parse("正文").unwrap().metadata("a.md").title, "a");
+
+        // 以逗号分隔的标签
+        let page = Page::parse("---\ntags: a, b\n---\n").unwrap();
+        assert_eq!(page.tags(), vec!["a", "b"]);
+
+        assert!(matches!(
+            Page::parse("---\n- a\n---\n"),
+            Err(Error::FrontMatter(_))
+        ));
+    }
+}
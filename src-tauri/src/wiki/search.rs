@@ -0,0 +1,158 @@
+//! 页面全文搜索
+//!
+//! 搜索不依赖索引，每次直接读取知识库中的所有页面，适合中小规模的知识库。
+//! 查询按空白拆分为多个关键词，忽略大小写，页面必须包含所有关键词才算匹配。
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use super::page::Page;
+use super::{collect_files, is_page, FileNode};
+
+/// 每个页面最多返回的匹配行数
+const MAX_LINES_PER_PAGE: usize = 3;
+
+/// 匹配行最多保留的字符数
+const MAX_LINE_CHARS: usize = 200;
+
+/// 页面中的一行匹配内容
+///
+/// # 字段
+/// * `line` - 行号，从 1 开始
+/// * `text` - 该行的内容，过长时被截断
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchLine {
+    pub line: usize,
+    pub text: String,
+}
+
+/// 一个匹配的页面
+///
+/// # 字段
+/// * `path` - 页面相对于知识库根目录的路径
+/// * `title` - 页面标题
+/// * `score` - 匹配得分，标题中的关键词比正文中的权重更高
+/// * `lines` - 包含关键词的行
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub path: String,
+    pub title: String,
+    pub score: usize,
+    pub lines: Vec<SearchLine>,
+}
+
+/// 在知识库的页面中搜索
+///
+/// # 参数
+/// * `root` - 知识库根目录
+/// * `tree` - 知识库的文件树，只搜索其中的 Markdown 页面
+/// * `query` - 查询内容
+/// * `limit` - 最多返回的页面数量
+///
+/// # 返回值
+/// * `Vec<SearchHit>` - 按得分从高到低排列的匹配页面，查询为空时返回空列表
+pub fn search(root: &Path, tree: &FileNode, query: &str, limit: usize) -> Vec<SearchHit> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut files = Vec::new();
+    collect_files(tree, &mut files);
+
+    let mut hits: Vec<SearchHit> = files
+        .into_iter()
+        .filter(|node| is_page(node.path()))
+        .filter_map(|node| {
+            let content = fs::read_to_string(root.join(node.path())).ok()?;
+            search_page(node, &content, &terms)
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    hits.truncate(limit);
+    hits
+}
+
+/// 在单个页面中搜索，页面不包含所有关键词时返回 `None`
+fn search_page(node: &FileNode, content: &str, terms: &[String]) -> Option<SearchHit> {
+    let lower = content.to_lowercase();
+    let title = Page::parse(content)
+        .map(|page| page.metadata(node.name()).title)
+        .unwrap_or_else(|_| node.name().to_string());
+    let lower_title = title.to_lowercase();
+
+    let mut score = 0;
+    for term in terms {
+        let in_title = lower_title.matches(term.as_str()).count();
+        let in_body = lower.matches(term.as_str()).count();
+        if in_title + in_body == 0 {
+            return None;
+        }
+        score += in_title * 10 + in_body;
+    }
+
+    let lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.to_lowercase();
+            terms.iter().any(|term| line.contains(term.as_str()))
+        })
+        .take(MAX_LINES_PER_PAGE)
+        .map(|(i, line)| SearchLine {
+            line: i + 1,
+            text: line.trim().chars().take(MAX_LINE_CHARS).collect(),
+        })
+        .collect();
+
+    Some(SearchHit {
+        path: node.path().to_string(),
+        title,
+        score,
+        lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wiki::build_file_tree;
+
+    /// 测试页面搜索
+    ///
+    /// # 测试目标：
+    /// * 验证页面必须包含所有关键词，搜索忽略大小写
+    /// * 验证标题匹配的页面排在前面，并返回匹配行的行号
+    /// * 验证只搜索 Markdown 页面，空查询返回空列表
+    #[test]
+    fn test_search() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(
+            root.join("docs/deploy.md"),
+            "# Deploy Guide\n\n部署前先运行测试。\n",
+        )
+        .unwrap();
+        fs::write(root.join("notes.md"), "# 笔记\n\nsee the deploy guide\n").unwrap();
+        fs::write(root.join("other.md"), "# 其他\n\ndeploy only\n").unwrap();
+        fs::write(root.join("deploy.txt"), "deploy guide").unwrap();
+
+        let tree = build_file_tree(root).unwrap();
+        let hits = search(root, &tree, "DEPLOY guide", 10);
+        let paths: Vec<&str> = hits.iter().map(|hit| hit.path.as_str()).collect();
+        assert_eq!(paths, vec!["docs/deploy.md", "notes.md"]);
+        assert_eq!(hits[0].title, "Deploy Guide");
+        assert_eq!(
+            hits[1].lines,
+            vec![SearchLine {
+                line: 3,
+                text: "see the deploy guide".to_string()
+            }]
+        );
+
+        assert_eq!(search(root, &tree, "deploy", 1).len(), 1);
+        assert!(search(root, &tree, "  ", 10).is_empty());
+    }
+}
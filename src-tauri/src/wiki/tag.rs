@@ -0,0 +1,538 @@
+//! 标签索引模块
+//!
+//! 页面的标签来自 front matter 中的 `tags` 字段以及正文中的 `#标签`。
+//! 正文中的标签必须以空白或行首开始，代码块、行内代码和标题中的 `#` 不会被识别为标签；
+//! 标签可以使用 `/` 分级，例如 `#项目/归档`。
+//!
+//! 标签索引保存在知识库的状态目录中（`.git/markwiki/tags.json`），记录每个页面的标签和文件的修改时间。
+//! 读取索引时只会重新解析修改时间或大小发生变化的页面，因此同步或外部编辑后索引也会保持最新。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::content;
+use super::page::Page;
+use super::{list_files, Error, Wiki};
+use crate::git;
+
+/// 标签及使用该标签的页面数量
+///
+/// # 字段
+/// * `name` - 标签名称
+/// * `count` - 使用该标签的页面数量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    pub name: String,
+    pub count: usize,
+}
+
+/// 索引中单个页面的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    /// 文件最后修改时间（Unix 时间戳，单位为毫秒）
+    modified: Option<u128>,
+    /// 文件大小
+    size: u64,
+    /// 页面的标签，已去重并排序
+    tags: Vec<String>,
+}
+
+/// 知识库的标签索引
+pub struct TagIndex {
+    /// 知识库根目录
+    root: PathBuf,
+    /// 索引文件路径
+    file: PathBuf,
+    /// 页面相对路径到页面记录的映射
+    pages: BTreeMap<String, IndexEntry>,
+}
+
+impl TagIndex {
+    /// 打开标签索引，并更新自上次保存以来发生变化的页面
+    ///
+    /// # 参数
+    /// * `root` - 知识库根目录
+    /// * `file` - 索引文件路径，不存在或无法解析时会重新建立索引
+    ///
+    /// # 返回值
+    /// * `Result<Self, Error>` - 成功时返回最新的标签索引
+    /// * 失败时返回 `Err(Error)`
+    pub fn open(root: &Path, file: &Path) -> Result<Self, Error> {
+        let pages = fs::read_to_string(file)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        let mut index = Self {
+            root: root.to_path_buf(),
+            file: file.to_path_buf(),
+            pages,
+        };
+        if index.refresh()? {
+            index.save()?;
+        }
+        Ok(index)
+    }
+
+    /// 打开指定知识库的标签索引
+    pub fn for_wiki(wiki: &Wiki) -> Result<Self, Error> {
+        Self::open(Path::new(&wiki.path), &wiki.state_dir()?.join("tags.json"))
+    }
+
+    /// 更新单个页面的标签，用于页面保存之后
+    ///
+    /// # 参数
+    /// * `relative` - 页面相对于知识库根目录的路径
+    /// * `content` - 页面的新内容
+    pub fn update_page(&mut self, relative: &str, content: &str) -> Result<(), Error> {
+        if !is_indexed(relative) {
+            return Ok(());
+        }
+        let (modified, size) = file_stamp(&self.root.join(relative))?;
+        self.pages.insert(
+            relative.to_string(),
+            IndexEntry {
+                modified,
+                size,
+                tags: extract_tags(content),
+            },
+        );
+        self.save()
+    }
+
+    /// 列出所有标签及使用数量，按标签名称排序
+    pub fn tags(&self) -> Vec<TagCount> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for entry in self.pages.values() {
+            for tag in &entry.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(name, count)| TagCount {
+                name: name.to_string(),
+                count,
+            })
+            .collect()
+    }
+
+    /// 列出使用指定标签（包括其下级标签）的页面
+    ///
+    /// # 参数
+    /// * `tag` - 标签名称，可以带 `#` 前缀
+    ///
+    /// # 返回值
+    /// * `Vec<String>` - 页面相对路径，按路径排序
+    pub fn pages_for(&self, tag: &str) -> Vec<String> {
+        let tag = normalize_tag(tag);
+        self.pages
+            .iter()
+            .filter(|(_, entry)| entry.tags.iter().any(|t| tag_matches(t, &tag)))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// 在所有页面中重命名标签，并将修改的页面作为一次提交
+    ///
+    /// 下级标签会一起重命名，例如将 `项目` 重命名为 `工作` 时，`项目/归档` 会变为 `工作/归档`。
+    /// 只有被修改的页面会被提交，工作区中的其他修改保持不变。
+    /// 需要修改的页面中存在未提交的修改时拒绝重命名，以免这些修改被一起提交。
+    ///
+    /// # 参数
+    /// * `from` - 原标签名称
+    /// * `to` - 新标签名称
+    /// * `message` - 提交信息
+    ///
+    /// # 返回值
+    /// * `Result<Vec<String>, Error>` - 成功时返回被修改的页面相对路径
+    /// * 页面存在未提交的修改时返回 `Err(Error::UncommittedPages)`，其他失败时返回 `Err(Error)`
+    pub fn rename(&mut self, from: &str, to: &str, message: &str) -> Result<Vec<String>, Error> {
+        let from = normalize_tag(from);
+        let to = normalize_tag(to);
+        if from.is_empty() || !is_valid_tag(&to) {
+            return Err(Error::InvalidTag(to));
+        }
+        if from == to {
+            return Ok(Vec::new());
+        }
+
+        // 先计算所有修改，确认相关页面都没有未提交的修改后再写入
+        let mut updates = Vec::new();
+        for path in self.pages_for(&from) {
+            let file = content::read_text(&self.root.join(&path))?;
+            let updated = rename_in_page(&file.content, &from, &to)?;
+            if updated != file.content {
+                updates.push((path, file.version, updated));
+            }
+        }
+        if updates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut repo = git::Repository::open(&self.root)?;
+        let mut dirty = Vec::new();
+        for (path, _, _) in &updates {
+            if repo.is_path_modified(path)? {
+                dirty.push(path.clone());
+            }
+        }
+        if !dirty.is_empty() {
+            return Err(Error::UncommittedPages(dirty));
+        }
+
+        let mut changed = Vec::new();
+        for (path, version, updated) in updates {
+            content::write_text(&self.root.join(&path), &updated, Some(&version))?;
+            self.update_page(&path, &updated)?;
+            changed.push(path);
+        }
+
+        repo.add_paths(&changed)?;
+        repo.commit(message)?;
+        Ok(changed)
+    }
+
+    /// 重新解析发生变化的页面并移除已删除的页面，返回索引是否有变化
+    fn refresh(&mut self) -> Result<bool, Error> {
+        let mut pages = BTreeMap::new();
+        let mut changed = false;
+
+        for path in list_files(&self.root)?
+            .into_iter()
+            .filter(|p| is_indexed(p))
+        {
+            let full_path = self.root.join(&path);
+            let (modified, size) = file_stamp(&full_path)?;
+            match self.pages.remove(&path) {
+                Some(entry) if entry.modified == modified && entry.size == size => {
+                    pages.insert(path, entry);
+                }
+                _ => {
+                    // 无法作为文本读取的页面不包含标签
+                    let tags = content::read_text(&full_path)
+                        .map(|file| extract_tags(&file.content))
+                        .unwrap_or_default();
+                    pages.insert(
+                        path,
+                        IndexEntry {
+                            modified,
+                            size,
+                            tags,
+                        },
+                    );
+                    changed = true;
+                }
+            }
+        }
+
+        changed |= !self.pages.is_empty();
+        self.pages = pages;
+        Ok(changed)
+    }
+
+    /// 保存索引文件
+    fn save(&self) -> Result<(), Error> {
+        let json = serde_json::to_string(&self.pages).map_err(std::io::Error::from)?;
+        content::write_atomic(&self.file, json.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// 提取页面的所有标签，包括 front matter 中的标签和正文中的 `#标签`
+///
+/// front matter 无法解析时，整个页面按正文处理。
+///
+/// # 返回值
+/// * `Vec<String>` - 去重并排序后的标签
+pub fn extract_tags(content: &str) -> Vec<String> {
+    let page = Page::parse(content).unwrap_or_else(|_| Page {
+        front_matter: None,
+        body: content.to_string(),
+    });
+
+    let mut tags: Vec<String> = page
+        .tags()
+        .iter()
+        .map(|tag| normalize_tag(tag))
+        .filter(|tag| is_valid_tag(tag))
+        .collect();
+    tags.extend(
+        inline_tag_spans(&page.body)
+            .into_iter()
+            .map(|(start, end)| page.body[start..end].to_string()),
+    );
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// 查找正文中的 `#标签`，返回标签名称（不含 `#`）在正文中的字节范围
+fn inline_tag_spans(body: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for line in body.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut prev: Option<char> = None;
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == '`' {
+                in_code = !in_code;
+            } else if c == '#' && !in_code && prev.is_none_or(char::is_whitespace) {
+                let start = i + 1;
+                let mut end = start;
+                while let Some(&(j, next)) = chars.peek() {
+                    if !is_tag_char(next) {
+                        break;
+                    }
+                    end = j + next.len_utf8();
+                    chars.next();
+                }
+                let tag = line[start..end].trim_end_matches('/');
+                if is_valid_tag(tag) {
+                    spans.push((line_start + start, line_start + start + tag.len()));
+                }
+                prev = line[..end].chars().next_back();
+                continue;
+            }
+            prev = Some(c);
+        }
+    }
+    spans
+}
+
+/// 在页面的 front matter 和正文中重命名标签
+fn rename_in_page(content: &str, from: &str, to: &str) -> Result<String, Error> {
+    let mut page = Page::parse(content)?;
+    let rename = |tag: &str| -> Option<String> {
+        let normalized = normalize_tag(tag);
+        tag_matches(&normalized, from).then(|| format!("{}{}", to, &normalized[from.len()..]))
+    };
+
+    // front matter 只有在标签变化时才重新生成，保持其原有格式
+    let front_matter_tags = match page.field("tags") {
+        Some(serde_yaml::Value::Sequence(items)) => {
+            let mut renamed = false;
+            let tags: Vec<serde_json::Value> = items
+                .iter()
+                .map(|item| match item.as_str().and_then(rename) {
+                    Some(tag) => {
+                        renamed = true;
+                        serde_json::Value::String(tag)
+                    }
+                    None => serde_json::to_value(item).unwrap_or_default(),
+                })
+                .collect();
+            renamed.then_some(serde_json::Value::Array(tags))
+        }
+        Some(serde_yaml::Value::String(list)) => {
+            let mut renamed = false;
+            let tags: Vec<String> = list
+                .split(',')
+                .map(|tag| match rename(tag) {
+                    Some(tag) => {
+                        renamed = true;
+                        tag
+                    }
+                    None => tag.trim().to_string(),
+                })
+                .collect();
+            renamed.then(|| serde_json::Value::String(tags.join(", ")))
+        }
+        _ => None,
+    };
+
+    let mut body = page.body.clone();
+    for (start, end) in inline_tag_spans(&page.body).into_iter().rev() {
+        if let Some(tag) = rename(&page.body[start..end]) {
+            body.replace_range(start..end, &tag);
+        }
+    }
+
+    match front_matter_tags {
+        Some(tags) => {
+            page.set_field("tags", &tags)?;
+            page.body = body;
+            page.render()
+        }
+        None => {
+            let prefix = &content[..content.len() - page.body.len()];
+            Ok(format!("{}{}", prefix, body))
+        }
+    }
+}
+
+/// 去除标签的 `#` 前缀和首尾空白
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().trim_start_matches('#').trim().to_string()
+}
+
+/// 判断字符是否可以出现在正文中的标签里
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+/// 判断标签是否有效：不能为空，不能只包含数字（如 `#1` 通常表示编号）
+fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag.chars().all(is_tag_char)
+        && !tag.starts_with('/')
+        && !tag.chars().all(|c| c.is_ascii_digit())
+}
+
+/// 判断标签是否为指定标签或其下级标签
+fn tag_matches(tag: &str, parent: &str) -> bool {
+    tag == parent
+        || tag
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// 判断路径是否为索引范围内的页面（隐藏目录中的文件不计入）
+fn is_indexed(relative: &str) -> bool {
+    super::is_page(relative) && !relative.split('/').any(|part| part.starts_with('.'))
+}
+
+/// 获取文件的修改时间和大小，用于判断页面是否需要重新解析
+fn file_stamp(path: &Path) -> Result<(Option<u128>, u64), Error> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis());
+    Ok((modified, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试标签的提取与重命名
+    ///
+    /// # 测试目标：
+    /// * 验证 front matter 与正文中的标签均能被提取，标题、代码和链接锚点中的 `#` 被忽略
+    /// * 验证重命名会同时修改下级标签，且不影响相似的标签
+    #[test]
+    fn test_extract_and_rename_tags() {
+        let content = "---\ntitle: 周报\ntags: [项目, 周报]\n---\n# 标题\n\
+                       本周 #项目/归档 完成，#1 号问题见 [说明](#锚点)。\n\
+                       `#代码` 不是标签\n```\n#注释\n```\n#项目组 #项目\n";
+        assert_eq!(
+            extract_tags(content),
+            vec!["周报", "项目", "项目/归档", "项目组"]
+        );
+
+        let renamed = rename_in_page(content, "项目", "工作").unwrap();
+        assert_eq!(
+            extract_tags(&renamed),
+            vec!["周报", "工作", "工作/归档", "项目组"]
+        );
+        assert!(renamed.contains("本周 #工作/归档 完成"));
+        assert!(renamed.contains("`#代码` 不是标签"));
+
+        // 只有正文中的标签时，front matter 保持原样
+        let content = "---\n# 注释\ntitle: a\n---\n#旧\n";
+        assert_eq!(
+            rename_in_page(content, "旧", "新").unwrap(),
+            "---\n# 注释\ntitle: a\n---\n#新\n"
+        );
+    }
+
+    /// 测试标签索引
+    ///
+    /// # 测试目标：
+    /// * 验证标签数量统计与按标签查找页面
+    /// * 验证外部修改的页面在打开索引时被重新解析，已删除的页面被移除
+    #[test]
+    fn test_tag_index() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("a.md"), "#rust #笔记").unwrap();
+        fs::write(root.join("docs/b.md"), "---\ntags: rust\n---\n").unwrap();
+        fs::write(root.join("c.md"), "#笔记").unwrap();
+        let file = temp_dir.path().join("tags.json");
+
+        let index = TagIndex::open(&root, &file).unwrap();
+        assert_eq!(
+            index.tags(),
+            vec![
+                TagCount {
+                    name: "rust".to_string(),
+                    count: 2
+                },
+                TagCount {
+                    name: "笔记".to_string(),
+                    count: 2
+                },
+            ]
+        );
+        assert_eq!(index.pages_for("#rust"), vec!["a.md", "docs/b.md"]);
+
+        fs::remove_file(root.join("c.md")).unwrap();
+        fs::write(root.join("docs/b.md"), "无标签").unwrap();
+        let index = TagIndex::open(&root, &file).unwrap();
+        assert_eq!(index.pages_for("rust"), vec!["a.md"]);
+        assert_eq!(index.pages_for("笔记"), vec!["a.md"]);
+    }
+
+    /// 测试在知识库中重命名标签
+    ///
+    /// # 测试目标：
+    /// * 验证相关页面存在未提交的修改时拒绝重命名，且不修改任何页面
+    /// * 验证重命名只提交被修改的页面，其他页面的未提交修改保持不变
+    #[test]
+    fn test_rename_commits_only_changed_pages() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("wiki");
+        fs::create_dir_all(&root).unwrap();
+        let mut repo =
+            git::Repository::init(&root, Some("tester"), Some("tester@example.com")).unwrap();
+        fs::write(root.join("a.md"), "#旧\n").unwrap();
+        fs::write(root.join("b.md"), "#旧\n").unwrap();
+        fs::write(root.join("c.md"), "无标签\n").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("初始化").unwrap();
+
+        fs::write(root.join("b.md"), "#旧\n未保存的内容\n").unwrap();
+        fs::write(root.join("c.md"), "其他修改\n").unwrap();
+        let file = temp_dir.path().join("tags.json");
+        let mut index = TagIndex::open(&root, &file).unwrap();
+        match index.rename("旧", "新", "重命名标签") {
+            Err(Error::UncommittedPages(pages)) => assert_eq!(pages, vec!["b.md"]),
+            other => panic!("应拒绝重命名: {:?}", other),
+        }
+        assert_eq!(fs::read_to_string(root.join("a.md")).unwrap(), "#旧\n");
+
+        fs::write(root.join("b.md"), "#旧\n").unwrap();
+        assert_eq!(
+            index.rename("旧", "新", "重命名标签").unwrap(),
+            vec!["a.md", "b.md"]
+        );
+        assert_eq!(fs::read_to_string(root.join("b.md")).unwrap(), "#新\n");
+        assert!(!repo.is_path_modified("a.md").unwrap());
+        assert!(!repo.is_path_modified("b.md").unwrap());
+        assert!(repo.is_path_modified("c.md").unwrap());
+    }
+}
@@ -0,0 +1,238 @@
+//! 页面模板模块
+//!
+//! 页面模板保存在知识库内的 `.markwiki/templates/` 目录中，每个模板是一个 Markdown 文件，
+//! 会随知识库一起同步。创建页面时可以选择模板，模板中的占位符会被替换：
+//!
+//! * `{{title}}` - 页面标题（文件名去掉 `.md` 后缀）
+//! * `{{date}}` - 创建日期，格式为 `YYYY-MM-DD`（UTC）
+//! * `{{author}}` - Git 配置中的用户名
+//!
+//! 未知的占位符会原样保留。
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::meta::META_DIR;
+use super::Error;
+
+/// 模板目录名称，位于 `.markwiki` 目录下
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// 页面模板
+///
+/// # 字段
+/// * `name` - 模板名称（文件名去掉 `.md` 后缀），创建页面时使用该名称选择模板
+/// * `path` - 模板文件相对于知识库根目录的路径
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Template {
+    pub name: String,
+    pub path: String,
+}
+
+/// 填充模板占位符使用的值
+///
+/// # 字段
+/// * `title` - 页面标题
+/// * `date` - 创建日期
+/// * `author` - 作者
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    pub title: String,
+    pub date: String,
+    pub author: String,
+}
+
+impl TemplateContext {
+    /// 使用当前日期创建模板上下文
+    ///
+    /// # 参数
+    /// * `title` - 页面标题
+    /// * `author` - 作者
+    pub fn new(title: &str, author: &str) -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            title: title.to_string(),
+            date: format_date(secs),
+            author: author.to_string(),
+        }
+    }
+
+    /// 获取占位符对应的值，未知的占位符返回 `None`
+    fn value(&self, key: &str) -> Option<&str> {
+        match key {
+            "title" => Some(&self.title),
+            "date" => Some(&self.date),
+            "author" => Some(&self.author),
+            _ => None,
+        }
+    }
+}
+
+/// 获取知识库的模板目录
+pub fn templates_dir(root: &Path) -> PathBuf {
+    root.join(META_DIR).join(TEMPLATES_DIR)
+}
+
+/// 列出知识库中的所有模板
+///
+/// # 参数
+/// * `root` - 知识库根目录
+///
+/// # 返回值
+/// * `Result<Vec<Template>, Error>` - 成功时返回按名称排列的模板列表，没有模板目录时返回空列表
+/// * 失败时返回 `Err(Error)`
+pub fn list_templates(root: &Path) -> Result<Vec<Template>, Error> {
+    let dir = templates_dir(root);
+    let mut templates = Vec::new();
+    if !dir.is_dir() {
+        return Ok(templates);
+    }
+
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        templates.push(Template {
+            name: name.to_string(),
+            path: format!("{}/{}/{}.md", META_DIR, TEMPLATES_DIR, name),
+        });
+    }
+
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+/// 读取模板并填充占位符
+///
+/// # 参数
+/// * `root` - 知识库根目录
+/// * `name` - 模板名称
+/// * `context` - 占位符的值
+///
+/// # 返回值
+/// * `Result<String, Error>` - 成功时返回填充后的页面内容
+/// * 失败时返回 `Err(Error)`，如模板不存在
+pub fn render_template(
+    root: &Path,
+    name: &str,
+    context: &TemplateContext,
+) -> Result<String, Error> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(Error::TemplateNotFound(name.to_string()));
+    }
+
+    let path = templates_dir(root).join(format!("{}.md", name));
+    let content =
+        fs::read_to_string(&path).map_err(|_| Error::TemplateNotFound(name.to_string()))?;
+    Ok(fill_placeholders(&content, context))
+}
+
+/// 替换内容中的 `{{key}}` 占位符，占位符两侧允许有空格
+pub fn fill_placeholders(content: &str, context: &TemplateContext) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => match context.value(after[..end].trim()) {
+                Some(value) => {
+                    out.push_str(value);
+                    rest = &after[end + 2..];
+                }
+                None => {
+                    out.push_str("{{");
+                    rest = after;
+                }
+            },
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 将 Unix 时间戳格式化为 `YYYY-MM-DD`（UTC）
+pub fn format_date(secs: u64) -> String {
+    // 将天数转换为公历日期，算法参见 Howard Hinnant 的 civil_from_days
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试模板的列出与填充
+    ///
+    /// # 测试目标：
+    /// * 验证只列出模板目录中的 Markdown 文件
+    /// * 验证占位符被替换，未知占位符原样保留
+    /// * 验证日期格式化和不存在的模板
+    #[test]
+    fn test_list_and_render_templates() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        assert!(list_templates(root).unwrap().is_empty());
+
+        let dir = templates_dir(root);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("会议记录.md"),
+            "# {{title}}\n\n日期：{{ date }}\n记录人：{{author}}\n{{unknown}} {{",
+        )
+        .unwrap();
+        fs::write(dir.join("日记.md"), "{{date}}").unwrap();
+        fs::write(dir.join("readme.txt"), "").unwrap();
+
+        let names: Vec<String> = list_templates(root)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["会议记录", "日记"]);
+
+        let context = TemplateContext {
+            title: "周会".to_string(),
+            date: "2024-03-01".to_string(),
+            author: "张三".to_string(),
+        };
+        assert_eq!(
+            render_template(root, "会议记录", &context).unwrap(),
+            "# 周会\n\n日期：2024-03-01\n记录人：张三\n{{unknown}} {{"
+        );
+        assert!(matches!(
+            render_template(root, "../日记", &context),
+            Err(Error::TemplateNotFound(_))
+        ));
+        assert!(matches!(
+            render_template(root, "missing", &context),
+            Err(Error::TemplateNotFound(_))
+        ));
+
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(1_709_251_200), "2024-03-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+    }
+}